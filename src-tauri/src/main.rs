@@ -1,10 +1,13 @@
 use serde::{Deserialize, Serialize};
-use std::process::Command;
 use chrono::{DateTime, Utc};
 use std::os::unix::process::ExitStatusExt;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use lazy_static::lazy_static;
+use async_trait::async_trait;
+use tauri::{Emitter, Manager};
 
 // Global cache for data to avoid reloading on tab switches - using RwLock for better performance
 lazy_static! {
@@ -14,6 +17,567 @@ lazy_static! {
     static ref HOST_CACHE_DURATION: i64 = 180; // Cache host info for 3 minutes
     static ref MAINTENANCE_CACHE_DURATION: i64 = 120; // Cache maintenance data for 2 minutes
     static ref COMMAND_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10); // Timeout for SSH commands
+    static ref LONG_COMMAND_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1800); // Timeout for upgrades, clones and other long-running SSH commands
+    static ref POWER_COMMAND_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(300); // Timeout for pct/qm start, stop, restart and reset, which wait for the guest
+    static ref AI_COMMAND_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(300); // Timeout for Ollama requests
+    static ref IP_CACHE_DURATION: i64 = 60; // Cache resolved guest IPs for 1 minute
+    static ref ECOSYSTEM_SCAN_CACHE_DURATION: i64 = 300; // Cache the full ecosystem scan for 5 minutes
+    static ref APP_CONFIG: Arc<RwLock<AppConfig>> = Arc::new(RwLock::new(AppConfig::default()));
+    static ref APP_DATA_DIR: Arc<RwLock<Option<PathBuf>>> = Arc::new(RwLock::new(None));
+    static ref AUDIT_LOCK: Mutex<()> = Mutex::new(());
+    static ref METRICS_DB: Arc<Mutex<Option<rusqlite::Connection>>> = Arc::new(Mutex::new(None));
+    static ref ALERT_STATE: Mutex<HashMap<String, i64>> = Mutex::new(HashMap::new()); // "<rule>|<target>" -> last fired timestamp
+    static ref LOG_FILTER: Mutex<Option<tracing_subscriber::reload::Handle<tracing_subscriber::EnvFilter, tracing_subscriber::Registry>>> = Mutex::new(None);
+    static ref SSH_PERMITS: RwLock<Arc<tokio::sync::Semaphore>> = RwLock::new(Arc::new(tokio::sync::Semaphore::new(ExecConfig::default().max_concurrent_commands)));
+    static ref METRICS_EXPORTER: Mutex<Option<tokio::task::JoinHandle<()>>> = Mutex::new(None);
+    static ref CACHE_WARMER: Mutex<Option<tokio::task::JoinHandle<()>>> = Mutex::new(None);
+    static ref LOG_GUARD: Mutex<Option<tracing_appender::non_blocking::WorkerGuard>> = Mutex::new(None); // Flushes the log file on exit
+    static ref COMMAND_TIMINGS: Mutex<CommandTimings> = Mutex::new(CommandTimings::default());
+    static ref CONSOLE_SESSIONS: Mutex<HashMap<u32, ConsoleSession>> = Mutex::new(HashMap::new()); // Open serial console bridges by VM id
+}
+
+// User-editable settings persisted as config.json in the app data dir
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+struct AppConfig {
+    web_ui_ports: HashMap<u32, u16>, // Per-container overrides of the default web UI port
+    transport: TransportConfig,
+    metrics: MetricsConfig,
+    ai: AiConfig,
+    exec: ExecConfig,
+    alert_rules: Vec<AlertRule>,
+    notifications: NotificationChannel,
+    monitored: MonitoredItems,
+    guest_metadata: HashMap<u32, GuestMetadata>,
+    start_plans: HashMap<String, StartPlan>,
+    logging: LoggingConfig,
+    cluster: ClusterConfig,
+    scripts: ScriptRegistry,
+    config_backups: ConfigBackupSettings,
+    certificates: CertificateConfig,
+}
+
+// TLS endpoints watched by check_certificates
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+struct CertificateConfig {
+    endpoints: Vec<String>, // URLs or host:port; the containers' web UIs when empty
+    warning_days: i64,      // Certificates expiring within this many days are flagged
+}
+
+impl Default for CertificateConfig {
+    fn default() -> Self {
+        CertificateConfig { endpoints: Vec::new(), warning_days: 14 }
+    }
+}
+
+// How many <path>.backup-<timestamp> copies the config writers keep per file
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+struct ConfigBackupSettings {
+    keep_last_n_backups: usize, // Older backups are deleted after each write
+}
+
+impl Default for ConfigBackupSettings {
+    fn default() -> Self {
+        ConfigBackupSettings { keep_last_n_backups: 10 }
+    }
+}
+
+// Local maintenance scripts the user has registered under a logical name, e.g. "fix-all-containers".
+// Nothing is registered by default, since script locations differ per machine.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+struct ScriptRegistry {
+    scripts: HashMap<String, RegisteredScript>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct RegisteredScript {
+    path: String,
+    #[serde(default)]
+    timeout_secs: Option<u64>,   // Overrides the caller's default timeout
+    #[serde(default)]
+    working_dir: Option<String>, // Directory the script runs in; the app's own when unset
+}
+
+// How to reach each node of a cluster over ssh
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+struct ClusterConfig {
+    node_targets: HashMap<String, String>, // Node name -> ssh alias; unlisted nodes are reached by name, the local one as "proxmox"
+}
+
+// Log file settings; logs go to logs/app.log.<date> in the app data dir
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+struct LoggingConfig {
+    level: String, // tracing env-filter directive, e.g. "info" or "debug"
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        LoggingConfig { level: "info".to_string() }
+    }
+}
+
+// A named set of guests to bring up together, e.g. a database before the apps that use it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+struct StartPlan {
+    guests: Vec<StartPlanGuest>,
+    step_timeout_secs: u64, // How long to wait for each guest to reach Running
+    on_failure: String,     // "abort" stops the plan at the first failure, "continue" skips only the failed guest's dependents
+}
+
+impl Default for StartPlan {
+    fn default() -> Self {
+        StartPlan {
+            guests: Vec::new(),
+            step_timeout_secs: 120,
+            on_failure: "abort".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct StartPlanGuest {
+    id: u32,
+    #[serde(default)]
+    is_vm: bool,
+    #[serde(default)]
+    order: u32,           // Lower starts first among guests whose dependencies are met
+    #[serde(default)]
+    depends_on: Vec<u32>, // Guest ids in the same plan that must be running first
+}
+
+// Per-guest overrides of the built-in display metadata
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+struct GuestMetadata {
+    name: Option<String>,
+    description: Option<String>,
+    category: Option<String>, // Containers only
+    web_ui_port: Option<u16>, // Containers only
+    ssh_alias: Option<String>, // VMs only: ssh host (alias) that reaches the VM itself
+}
+
+// A service, binary or config file checked by the maintenance overview, on the host or in one guest
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct MonitoredItem {
+    name: String, // Service name, binary name or absolute config path
+    #[serde(default)]
+    container_id: Option<u32>,
+    #[serde(default)]
+    vm_id: Option<u32>,
+}
+
+// Where a maintenance command may act: one container, one VM, or the host when both ids are empty
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct MaintenanceTarget {
+    #[serde(default)]
+    container_id: Option<u32>,
+    #[serde(default)]
+    vm_id: Option<u32>,
+}
+
+impl MonitoredItem {
+    fn new(name: &str, container_id: Option<u32>, vm_id: Option<u32>) -> Self {
+        MonitoredItem {
+            name: name.to_string(),
+            container_id,
+            vm_id,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+struct MonitoredItems {
+    services: Vec<MonitoredItem>,
+    binaries: Vec<MonitoredItem>,
+    configs: Vec<MonitoredItem>,
+}
+
+impl Default for MonitoredItems {
+    fn default() -> Self {
+        MonitoredItems {
+            services: vec![
+                // Proxmox host services
+                MonitoredItem::new("nginx", None, None),
+                MonitoredItem::new("docker", None, None),
+                MonitoredItem::new("ssh", None, None),
+                // Container-specific services (these would be checked inside containers)
+                MonitoredItem::new("sonarr", Some(214), None),
+                MonitoredItem::new("radarr", Some(215), None),
+                MonitoredItem::new("prowlarr", Some(210), None),
+                MonitoredItem::new("qbittorrent", Some(212), None),
+                MonitoredItem::new("plex", Some(230), None),
+                MonitoredItem::new("jellyfin", Some(231), None),
+                // VM services
+                MonitoredItem::new("home-assistant", None, Some(500)),
+                MonitoredItem::new("alexa-service", None, Some(611)),
+            ],
+            binaries: vec![
+                // System binaries
+                MonitoredItem::new("docker", None, None),
+                MonitoredItem::new("systemctl", None, None),
+                MonitoredItem::new("nginx", None, None),
+                // Application binaries (in containers)
+                MonitoredItem::new("sonarr", Some(214), None),
+                MonitoredItem::new("radarr", Some(215), None),
+                MonitoredItem::new("prowlarr", Some(210), None),
+                MonitoredItem::new("plex", Some(230), None),
+                MonitoredItem::new("jellyfin", Some(231), None),
+                // VM binaries
+                MonitoredItem::new("python3", None, Some(500)),
+                MonitoredItem::new("hass", None, Some(500)),
+            ],
+            configs: vec![
+                // System configs
+                MonitoredItem::new("/etc/nginx/nginx.conf", None, None),
+                MonitoredItem::new("/etc/docker/daemon.json", None, None),
+                // Application configs (in containers)
+                MonitoredItem::new("/config/config.xml", Some(214), None), // Sonarr
+                MonitoredItem::new("/config/config.xml", Some(215), None), // Radarr
+                MonitoredItem::new("/config/config.xml", Some(210), None), // Prowlarr
+                MonitoredItem::new("/config/qBittorrent/qBittorrent.conf", Some(212), None),
+                // VM configs
+                MonitoredItem::new("/config/configuration.yaml", None, Some(500)), // Home Assistant
+            ],
+        }
+    }
+}
+
+// Fires when a sampled metric for a target crosses the threshold
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+struct AlertRule {
+    id: String,
+    metric: String,     // "cpu", "memory" or "disk" (percent), or "cpu_pressure", "memory_pressure" or "io_pressure" (PSI some avg60, containers only)
+    target: String,     // "host:<node>", "ct:<id>", "vm:<id>", or "ct:*" etc. to match every target of a kind
+    comparison: String, // "above" or "below"
+    threshold: f64,
+    repeat_after_minutes: u32, // While the condition holds, fire again at most this often
+}
+
+impl Default for AlertRule {
+    fn default() -> Self {
+        AlertRule {
+            id: String::new(),
+            metric: "memory".to_string(),
+            target: "*".to_string(),
+            comparison: "above".to_string(),
+            threshold: 90.0,
+            repeat_after_minutes: 60,
+        }
+    }
+}
+
+// Where triggered alerts are delivered besides the in-app event
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+struct NotificationChannel {
+    kind: String,        // "none", "webhook" (POST the alert as JSON) or "email" (SMTP)
+    webhook_url: String,
+    smtp_host: String,
+    smtp_port: u16,
+    smtp_starttls: bool, // STARTTLS on smtp_port; implicit TLS otherwise
+    smtp_username: String,
+    smtp_password: String,
+    email_from: String,
+    email_to: String,
+}
+
+impl Default for NotificationChannel {
+    fn default() -> Self {
+        NotificationChannel {
+            kind: "none".to_string(),
+            webhook_url: String::new(),
+            smtp_host: String::new(),
+            smtp_port: 587,
+            smtp_starttls: true,
+            smtp_username: String::new(),
+            smtp_password: String::new(),
+            email_from: String::new(),
+            email_to: String::new(),
+        }
+    }
+}
+
+// Limits applied to commands run through run_remote
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+struct ExecConfig {
+    max_output_bytes: usize, // Per stream; anything past this is read and discarded
+    max_transfer_bytes: u64, // Largest file download_guest_file will copy
+    retry_attempts: u32,     // Tries for read-only commands whose ssh connection fails
+    retry_base_delay_ms: u64, // Doubled after each failed attempt
+    max_concurrent_commands: usize, // ssh processes allowed at once; the rest wait for a free slot
+}
+
+impl Default for ExecConfig {
+    fn default() -> Self {
+        ExecConfig {
+            max_output_bytes: 4 * 1024 * 1024,
+            max_transfer_bytes: 1024 * 1024 * 1024,
+            retry_attempts: 3,
+            retry_base_delay_ms: 500,
+            max_concurrent_commands: 8,
+        }
+    }
+}
+
+// Where the Ollama instance used by the AI commands lives
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+struct AiConfig {
+    mode: String,       // "container" (curl via pct exec) or "direct" (HTTP from this machine)
+    base_url: String,   // Ollama endpoint, as seen from the container in "container" mode
+    container_id: u32,  // Container to exec curl in for "container" mode
+    model: String,      // Model for config/scan analysis
+    code_model: String, // Model for code analysis
+}
+
+impl Default for AiConfig {
+    fn default() -> Self {
+        AiConfig {
+            mode: "container".to_string(),
+            base_url: "http://localhost:11434".to_string(),
+            container_id: 900,
+            model: "llama2".to_string(),
+            code_model: "codellama:7b".to_string(),
+        }
+    }
+}
+
+// Background sampling of guest/host metrics into the local history database
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+struct MetricsConfig {
+    enabled: bool,
+    sample_interval_secs: u64,
+    retention_days: u32,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        MetricsConfig {
+            enabled: true,
+            sample_interval_secs: 60,
+            retention_days: 7,
+        }
+    }
+}
+
+// How the app talks to the Proxmox node for read operations
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+struct TransportConfig {
+    mode: String,         // "ssh" or "pve_api"
+    api_url: String,      // Base URL of the Proxmox API, e.g. https://proxmox:8006
+    node: String,         // Node name used in API paths, detected when empty
+    token_id: String,     // API token id, e.g. root@pam!admin
+    token_secret: String,
+    verify_tls: bool,     // Proxmox ships a self-signed certificate by default
+}
+
+impl Default for TransportConfig {
+    fn default() -> Self {
+        TransportConfig {
+            mode: "ssh".to_string(),
+            api_url: "https://proxmox:8006".to_string(),
+            node: String::new(),
+            token_id: String::new(),
+            token_secret: String::new(),
+            verify_tls: false,
+        }
+    }
+}
+
+// Load the persisted config from the app data dir, keeping defaults when missing or invalid
+fn init_app_config(data_dir: PathBuf) {
+    let _ = std::fs::create_dir_all(&data_dir);
+    let config_path = data_dir.join("config.json");
+
+    if let Ok(contents) = std::fs::read_to_string(&config_path) {
+        match serde_json::from_str::<AppConfig>(&contents) {
+            Ok(config) => {
+                set_ssh_permits(config.exec.max_concurrent_commands);
+                if let Ok(mut app_config) = APP_CONFIG.write() {
+                    *app_config = config;
+                }
+            }
+            Err(e) => eprintln!("Ignoring invalid config file {}: {}", config_path.display(), e),
+        }
+    }
+
+    if let Ok(mut dir) = APP_DATA_DIR.write() {
+        *dir = Some(data_dir);
+    }
+}
+
+// Set up the daily-rolling log file; RUST_LOG overrides the configured level at startup
+fn init_logging(data_dir: PathBuf) {
+    use tracing_subscriber::prelude::*;
+
+    let appender = tracing_appender::rolling::daily(data_dir.join("logs"), "app.log");
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    let filter = tracing_subscriber::EnvFilter::try_from_default_env()
+        .or_else(|_| tracing_subscriber::EnvFilter::try_new(&app_config().logging.level))
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info"));
+    let (filter, handle) = tracing_subscriber::reload::Layer::new(filter);
+
+    let initialized = tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer().with_writer(writer).with_ansi(false))
+        .try_init();
+    if let Err(e) = initialized {
+        eprintln!("Failed to initialize logging: {}", e);
+        return;
+    }
+
+    if let Ok(mut filter) = LOG_FILTER.lock() {
+        *filter = Some(handle);
+    }
+    if let Ok(mut log_guard) = LOG_GUARD.lock() {
+        *log_guard = Some(guard);
+    }
+}
+
+// Append-only audit trail of mutating commands (audit.jsonl in the app data dir)
+#[derive(Debug, Serialize, Deserialize)]
+struct AuditEntry {
+    timestamp: DateTime<Utc>,
+    command: String,
+    target: String,
+    arguments: serde_json::Value,
+    success: bool,
+    output: String,
+}
+
+const AUDIT_OUTPUT_LIMIT: usize = 2000;
+
+// Helper function to describe the target of a host/container/VM command for the audit log
+fn audit_target(container_id: Option<u32>, vm_id: Option<u32>) -> String {
+    match (container_id, vm_id) {
+        (Some(cid), _) => format!("ct:{}", cid),
+        (None, Some(vid)) => format!("vm:{}", vid),
+        (None, None) => "host".to_string(),
+    }
+}
+
+// Helper function to blank out secret-looking argument values before they are logged
+fn redact_arguments(arguments: &mut serde_json::Value) {
+    if let Some(object) = arguments.as_object_mut() {
+        for (key, value) in object.iter_mut() {
+            let key = key.to_lowercase();
+            if ["secret", "password", "token", "passphrase"].iter().any(|word| key.contains(word)) {
+                *value = serde_json::json!("[redacted]");
+            } else {
+                redact_arguments(value);
+            }
+        }
+    }
+}
+
+// Helper function to truncate text on a char boundary
+fn truncate_text(text: &str, limit: usize) -> String {
+    match text.char_indices().nth(limit) {
+        Some((index, _)) => format!("{}... [truncated]", &text[..index]),
+        None => text.to_string(),
+    }
+}
+
+// Helper function to append an entry to the audit log
+fn record_audit(entry: &AuditEntry) -> Result<(), String> {
+    use std::io::Write;
+
+    let _guard = AUDIT_LOCK.lock().map_err(|e| format!("Failed to lock audit log: {}", e))?;
+    let line = serde_json::to_string(entry).map_err(|e| format!("Failed to serialize audit entry: {}", e))?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(app_data_path("audit.jsonl")?)
+        .map_err(|e| format!("Failed to open audit log: {}", e))?;
+
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write audit log: {}", e))
+}
+
+// Helper function to run a mutating command and record it in the audit log
+async fn audited<T, F>(command: &str, target: String, mut arguments: serde_json::Value, action: F) -> Result<T, String>
+where
+    T: Serialize,
+    F: std::future::Future<Output = Result<T, String>>,
+{
+    let result = action.await;
+
+    redact_arguments(&mut arguments);
+    let output = match &result {
+        Ok(value) => serde_json::to_value(value)
+            .map(|value| value.as_str().map(|s| s.to_string()).unwrap_or_else(|| value.to_string()))
+            .unwrap_or_default(),
+        Err(e) => e.clone(),
+    };
+
+    let entry = AuditEntry {
+        timestamp: Utc::now(),
+        command: command.to_string(),
+        target,
+        arguments,
+        success: result.is_ok(),
+        output: truncate_text(&output, AUDIT_OUTPUT_LIMIT),
+    };
+    if let Err(e) = record_audit(&entry) {
+        tracing::warn!("Failed to record audit entry for {}: {}", command, e);
+    }
+
+    result
+}
+
+// Tauri command to get the most recent audit log entries, newest first
+#[tauri::command]
+async fn get_audit_log(limit: Option<usize>) -> Result<Vec<AuditEntry>, String> {
+    let contents = match std::fs::read_to_string(app_data_path("audit.jsonl")?) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read audit log: {}", e)),
+    };
+
+    Ok(contents.lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str::<AuditEntry>(line).ok())
+        .take(limit.unwrap_or(100))
+        .collect())
+}
+
+// Helper function to get a path inside the app data dir
+fn app_data_path(file_name: &str) -> Result<PathBuf, String> {
+    APP_DATA_DIR.read()
+        .ok()
+        .and_then(|dir| dir.as_ref().map(|d| d.join(file_name)))
+        .ok_or_else(|| "App data directory is not initialized".to_string())
+}
+
+// Helper function to read the current config
+fn app_config() -> AppConfig {
+    APP_CONFIG.read().map(|config| config.clone()).unwrap_or_default()
+}
+
+// Helper function to modify the config and persist it to disk
+fn update_app_config<F: FnOnce(&mut AppConfig)>(update: F) -> Result<AppConfig, String> {
+    let updated = {
+        let mut config = APP_CONFIG.write().map_err(|e| format!("Failed to lock config: {}", e))?;
+        update(&mut config);
+        config.clone()
+    };
+
+    let serialized = serde_json::to_string_pretty(&updated)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    std::fs::write(app_data_path("config.json")?, serialized)
+        .map_err(|e| format!("Failed to write config file: {}", e))?;
+
+    Ok(updated)
 }
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct ContainerInfo {
@@ -28,6 +592,18 @@ struct ContainerInfo {
     web_ui_url: Option<String>,
     os_info: Option<String>,
     running_processes: Vec<String>,
+    #[serde(default)]
+    template: bool,
+    #[serde(default)]
+    health_score: Option<f32>,
+    #[serde(default)]
+    lock: Option<String>, // e.g. "backup", "migrate" or "snapshot" while a task holds the guest
+    #[serde(default)]
+    usage_stale: bool, // cpu/memory are the last values seen while running, not live
+    #[serde(default)]
+    usage_sampled_at: Option<DateTime<Utc>>, // When the cpu/memory figures were observed, if known
+    #[serde(default)]
+    uptime_seconds: Option<u64>, // None while stopped or when unknown; `uptime` is the display form
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -54,6 +630,20 @@ struct VMInfo {
     cpu_usage: f64,
     memory_usage: f64,
     description: String,
+    #[serde(default)]
+    template: bool,
+    #[serde(default)]
+    health_score: Option<f32>,
+    #[serde(default)]
+    lock: Option<String>, // e.g. "backup", "migrate" or "snapshot" while a task holds the guest
+    #[serde(default)]
+    usage_stale: bool, // cpu/memory are the last values seen while running, not live
+    #[serde(default)]
+    usage_sampled_at: Option<DateTime<Utc>>, // When the cpu/memory figures were observed, if known
+    #[serde(default)]
+    uptime_seconds: Option<u64>, // None while stopped or when unknown; `uptime` is the display form
+    #[serde(default)]
+    memory_percent: Option<f64>, // memory_usage against the VM's maxmem, when known
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -170,1827 +760,9319 @@ struct StorageInfo {
     usage_percent: f64,
 }
 
-// Helper function to fetch detailed container information
-async fn fetch_container_details(container_id: u32) -> Result<ContainerDetails, String> {
-    let os_info_output = Command::new("ssh")
-        .args(["proxmox", "pct", "exec", &container_id.to_string(), "--", "lsb_release", "-a"])
-        .output()
-        .map_err(|e| format!("Failed to fetch OS info: {}", e))?;
+// Live status of a single guest as reported by a transport
+#[derive(Debug, Clone)]
+struct GuestStatus {
+    status: String,
+    cpu_usage: Option<f64>,
+    memory_usage: Option<f64>, // MB
+    memory_percent: Option<f64>,
+    uptime: Option<String>,
+    uptime_seconds: Option<u64>,
+    lock: Option<String>, // e.g. "backup" while a task holds the guest; status reports it, so no config read is needed
+}
 
-    let os_info = if os_info_output.status.success() {
-        String::from_utf8_lossy(&os_info_output.stdout).lines().collect::<Vec<&str>>().join(" ")
+// Basic node facts as reported by a transport
+#[derive(Debug, Clone)]
+struct HostSummary {
+    hostname: String,
+    version: String,
+    uptime: String,
+    cpu_count: u32,
+    memory_total: String,
+}
+
+// Read operations that can be served either over SSH or the Proxmox REST API
+#[async_trait]
+trait Transport: Send + Sync {
+    async fn list_guests(&self, is_vm: bool) -> Result<Vec<u32>, String>;
+    async fn guest_status(&self, id: u32, is_vm: bool) -> Result<GuestStatus, String>;
+    async fn host_summary(&self) -> Result<HostSummary, String>;
+    async fn storage_info(&self) -> Result<Vec<StorageInfo>, String>;
+    // Raw /cluster/resources entries (nodes and guests) with live cpu/mem/disk figures
+    async fn cluster_resources(&self) -> Result<Vec<serde_json::Value>, String>;
+}
+
+// Helper function to pick the transport selected in the config
+fn transport() -> Box<dyn Transport> {
+    let config = app_config().transport;
+    match config.mode.as_str() {
+        "pve_api" => Box::new(PveApiTransport { config }),
+        _ => Box::new(SshTransport),
+    }
+}
+
+// Helper function to parse `qm status <id> --verbose` (or `pct status`) output. Only top-level keys are
+// read; nested blocks such as blockstat and nics are indented. Usage figures are only kept while running,
+// since a stopped guest omits them or reports zeros.
+fn parse_status_verbose(output: &str) -> GuestStatus {
+    let fields: HashMap<&str, &str> = output.lines()
+        .filter(|line| !line.starts_with(char::is_whitespace))
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .collect();
+    let number = |key: &str| fields.get(key).and_then(|value| value.parse::<f64>().ok());
+
+    // --verbose adds fields like qmpstatus, so only the status line decides the state
+    let status = parse_guest_state(fields.get("status").copied().unwrap_or(""));
+    let running = status == "Running";
+    let mem = number("mem").filter(|_| running);
+    let uptime_seconds = fields.get("uptime").and_then(|uptime| uptime.parse::<u64>().ok()).filter(|_| running);
+
+    GuestStatus {
+        cpu_usage: number("cpu").filter(|_| running).map(|cpu| cpu * 100.0),
+        memory_usage: mem.map(|mem| mem / 1024.0 / 1024.0),
+        memory_percent: mem.zip(number("maxmem").filter(|max| *max > 0.0)).map(|(mem, max)| mem / max * 100.0),
+        uptime: uptime_seconds.map(format_uptime),
+        uptime_seconds,
+        lock: fields.get("lock").map(|lock| lock.to_string()).filter(|lock| !lock.is_empty()),
+        status,
+    }
+}
+
+// Helper function to parse running/stopped from pct/qm status output
+fn parse_guest_state(output: &str) -> String {
+    if output.contains("running") {
+        "Running".to_string()
+    } else if output.contains("stopped") {
+        "Stopped".to_string()
     } else {
         "Unknown".to_string()
-    };
+    }
+}
 
-    let processes_output = Command::new("ssh")
-        .args(["proxmox", "pct", "exec", &container_id.to_string(), "--", "ps", "-e"])
-        .output()
-        .map_err(|e| format!("Failed to fetch running processes: {}", e))?;
+// Helper function to format a number of seconds like "3d 4h 12m"
+fn format_uptime(seconds: u64) -> String {
+    let days = seconds / 86400;
+    let hours = (seconds % 86400) / 3600;
+    let minutes = (seconds % 3600) / 60;
 
-    let running_processes: Vec<String> = if processes_output.status.success() {
-        String::from_utf8_lossy(&processes_output.stdout)
-            .lines().map(|s| s.to_string()).collect()
+    if days > 0 {
+        format!("{}d {}h {}m", days, hours, minutes)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
     } else {
-        vec!["Unknown".to_string()]
-    };
+        format!("{}m", minutes)
+    }
+}
 
-    let web_ui_url = Some(format!("http://{}:8000", get_container_ip_address(container_id)?));
+// Errors from running commands on the Proxmox host; Tauri commands still return them as Strings
+#[derive(Debug)]
+enum AppError {
+    Spawn(String),
+    Io(String),
+    Timeout { command: String, after: std::time::Duration },
+    Unreachable { target: String, reason: String },
+}
 
-    Ok(ContainerDetails {
-        id: container_id,
-        name: format!("Container {}", container_id),
-        status: "Unknown".to_string(),
-        os_info,
-        running_processes,
-        installed_binaries: Vec::new(), // Add logic to fetch binaries if needed
-        systemd_services: Vec::new(), // Add logic to fetch services if needed
-        config_files: Vec::new(), // Add logic to manage or fetch configs if needed
-        web_ui_url,
-        category: get_container_category(container_id),
-        description: get_container_description(container_id),
-    })
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppError::Spawn(e) => write!(f, "failed to start command: {}", e),
+            AppError::Io(e) => write!(f, "failed to read command output: {}", e),
+            AppError::Timeout { command, after } => write!(f, "'{}' timed out after {:?}", command, after),
+            AppError::Unreachable { target, reason } => write!(f, "target '{}' not reachable: {}", target, reason),
+        }
+    }
 }
 
-fn get_container_ip_address(_container_id: u32) -> Result<String, String> {
-    // Add logic to fetch the container's IP address
-    Ok("127.0.0.1".to_string())
+impl From<AppError> for String {
+    fn from(error: AppError) -> String {
+        error.to_string()
+    }
 }
 
+// Helper function to read a child's output stream, keeping at most `limit` bytes
+async fn read_capped<R: tokio::io::AsyncRead + Unpin>(reader: Option<R>, limit: usize) -> std::io::Result<Vec<u8>> {
+    use tokio::io::AsyncReadExt;
 
-// Tauri command to get container status
-#[tauri::command]
-async fn get_container_status(container_id: u32) -> Result<ContainerInfo, String> {
-    // Get container status - this should be fast
-    let status_output = Command::new("ssh")
-        .args(["proxmox", "pct", "status", &container_id.to_string()])
-        .output()
-        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    let mut captured = Vec::new();
+    let mut reader = match reader {
+        Some(reader) => reader,
+        None => return Ok(captured),
+    };
 
-    if !status_output.status.success() {
-        return Err(format!("Command failed: {}", String::from_utf8_lossy(&status_output.stderr)));
+    // Keep draining past the limit so the remote side never blocks on a full pipe
+    let mut chunk = [0u8; 8192];
+    let mut truncated = false;
+    loop {
+        let read = reader.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        let room = limit.saturating_sub(captured.len());
+        captured.extend_from_slice(&chunk[..read.min(room)]);
+        truncated |= read > room;
     }
 
-    let status_str = String::from_utf8_lossy(&status_output.stdout);
-    let status = if status_str.contains("running") {
-        "Running".to_string()
-    } else if status_str.contains("stopped") {
-        "Stopped".to_string()
-    } else {
-        "Unknown".to_string()
-    };
+    if truncated {
+        captured.extend_from_slice(format!("\n[output truncated at {} bytes]", limit).as_bytes());
+    }
+    Ok(captured)
+}
 
-    // Get container name from metadata
-    let container_name = get_container_display_name(container_id);
-    
-    // Set default values
-    let mut memory_usage = (container_id as f64 * 15.0) % 1024.0; // Simulated
-    let cpu_usage = (container_id as f64 * 1.2) % 100.0; // Simulated
-    let uptime = if status == "Running" { "Running".to_string() } else { "Stopped".to_string() };
-    let mut os_info_str = None;
+// Helper function to run a command over ssh with a hard timeout and capped output
+async fn run_remote(target: &str, args: &[&str], timeout: std::time::Duration) -> Result<std::process::Output, AppError> {
+    let start = std::time::Instant::now();
+    let command = redacted_command(args);
+    tracing::debug!(ssh_target = target, command = %command, "running remote command");
+
+    let result = run_ssh(target, args, timeout).await;
+    let duration_ms = start.elapsed().as_millis() as u64;
+    record_command_timing(args, duration_ms, matches!(&result, Ok(output) if output.status.success()));
+    match &result {
+        Ok(output) if output.status.success() => {
+            tracing::debug!(ssh_target = target, command = %command, duration_ms, "remote command finished");
+        }
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            tracing::warn!(ssh_target = target, command = %command, duration_ms, exit_code = ?output.status.code(),
+                           stderr = %stderr.trim(), "remote command failed");
+        }
+        Err(e) => {
+            tracing::warn!(ssh_target = target, command = %command, duration_ms, error = %e, "remote command could not run");
+        }
+    }
+    result
+}
 
-    // Only get additional details if container is running, and do it quickly
-    if status == "Running" {
-        // Try to get basic OS info with timeout - don't let this block
-        let os_command = std::process::Command::new("timeout")
-            .args(["3", "ssh", "proxmox", "pct", "exec", &container_id.to_string(), "--", "cat", "/etc/os-release"])
-            .output();
-            
-        if let Ok(output) = os_command {
-            if output.status.success() {
-                let os_str = String::from_utf8_lossy(&output.stdout);
-                if let Some(name_line) = os_str.lines().find(|line| line.starts_with("NAME=")) {
-                    let os_name = name_line.split('=').nth(1)
-                        .unwrap_or("Unknown")
-                        .trim_matches('"')
-                        .to_string();
-                    if !os_name.is_empty() && os_name != "Unknown" {
-                        os_info_str = Some(os_name);
-                    }
-                }
-            }
+// Command-line flags whose value is a secret, e.g. `qm set --cipassword`
+const SECRET_FLAGS: &[&str] = &["--cipassword", "--password"];
+
+// Helper function to join a command's arguments for logs and errors with the values of secret flags
+// blanked out. An argument holding a whole shell command is cut off after the first secret flag.
+fn redacted_command(args: &[&str]) -> String {
+    let mut redacted = Vec::with_capacity(args.len());
+    let mut hide_next = false;
+    for arg in args {
+        if hide_next {
+            redacted.push("[redacted]".to_string());
+            hide_next = false;
+        } else if SECRET_FLAGS.contains(arg) {
+            redacted.push(arg.to_string());
+            hide_next = true;
+        } else if let Some(at) = SECRET_FLAGS.iter().filter_map(|flag| arg.find(flag).map(|at| at + flag.len())).min() {
+            redacted.push(format!("{} [redacted]", &arg[..at]));
+        } else {
+            redacted.push(arg.to_string());
         }
-        
-        // Try to get memory allocation from config - also with timeout
-        let config_command = std::process::Command::new("timeout")
-            .args(["2", "ssh", "proxmox", "pct", "config", &container_id.to_string()])
-            .output();
-            
-        if let Ok(config_output) = config_command {
-            if config_output.status.success() {
-                let config_str = String::from_utf8_lossy(&config_output.stdout);
-                for line in config_str.lines() {
-                    if line.starts_with("memory:") {
-                        if let Some(mem_str) = line.split(':').nth(1) {
-                            if let Ok(mem_mb) = mem_str.trim().parse::<f64>() {
-                                memory_usage = mem_mb;
-                                break;
-                            }
-                        }
-                    }
-                }
-            }
+    }
+    redacted.join(" ")
+}
+
+// How many recent remote commands get_command_stats computes latencies over
+const COMMAND_TIMING_WINDOW: usize = 1000;
+
+struct CommandTiming {
+    name: String,
+    duration_ms: u64,
+    success: bool,
+}
+
+// Recent remote command timings, plus totals since start or the last reset
+#[derive(Default)]
+struct CommandTimings {
+    recent: std::collections::VecDeque<CommandTiming>,
+    total_commands: u64,
+    failed_commands: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CommandStat {
+    name: String,
+    count: usize,
+    errors: usize,
+    avg_ms: u64,
+    p95_ms: u64,
+    max_ms: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CommandStats {
+    total_commands: u64,
+    error_rate: f64,       // Failed / total, 0-1, over everything since start or reset
+    window: usize,         // How many recent commands the per-command figures cover
+    commands: Vec<CommandStat>, // Slowest p95 first
+}
+
+// Helper function to name a remote command for the stats: the program, plus the subcommand for
+// the Proxmox tools and systemctl, e.g. "pct exec" or "qm status"
+fn command_stat_name(args: &[&str]) -> String {
+    let joined = args.join(" ");
+    let mut words = joined.split_whitespace();
+    let program = words.next().unwrap_or("").rsplit('/').next().unwrap_or("").to_string();
+    match (program.as_str(), words.next()) {
+        ("pct" | "qm" | "pvesh" | "pvecm" | "pvesm" | "systemctl" | "sudo", Some(subcommand)) => format!("{} {}", program, subcommand),
+        _ => program,
+    }
+}
+
+// Helper function to add one remote command to the latency ring buffer
+fn record_command_timing(args: &[&str], duration_ms: u64, success: bool) {
+    if let Ok(mut timings) = COMMAND_TIMINGS.lock() {
+        if timings.recent.len() == COMMAND_TIMING_WINDOW {
+            timings.recent.pop_front();
+        }
+        timings.recent.push_back(CommandTiming {
+            name: command_stat_name(args),
+            duration_ms,
+            success,
+        });
+        timings.total_commands += 1;
+        if !success {
+            timings.failed_commands += 1;
         }
     }
+}
 
-    Ok(ContainerInfo {
-        id: container_id,
-        name: container_name,
-        status,
-        uptime,
-        cpu_usage,
-        memory_usage,
-        category: get_container_category(container_id),
-        description: get_container_description(container_id),
-        web_ui_url: get_container_web_ui_url(container_id),
-        os_info: os_info_str,
-        running_processes: Vec::new(),
+// Tauri command to get remote command latencies (average and p95 per command) and the error rate
+#[tauri::command]
+async fn get_command_stats() -> Result<CommandStats, String> {
+    let timings = COMMAND_TIMINGS.lock().map_err(|e| format!("Failed to lock command stats: {}", e))?;
+
+    let mut by_name: HashMap<&str, Vec<&CommandTiming>> = HashMap::new();
+    for timing in &timings.recent {
+        by_name.entry(timing.name.as_str()).or_default().push(timing);
+    }
+    let mut commands: Vec<CommandStat> = by_name.into_iter()
+        .map(|(name, runs)| {
+            let mut durations: Vec<u64> = runs.iter().map(|run| run.duration_ms).collect();
+            durations.sort_unstable();
+            // Nearest-rank percentile
+            let p95_index = ((durations.len() as f64 * 0.95).ceil() as usize).max(1) - 1;
+            CommandStat {
+                name: name.to_string(),
+                count: runs.len(),
+                errors: runs.iter().filter(|run| !run.success).count(),
+                avg_ms: durations.iter().sum::<u64>() / durations.len() as u64,
+                p95_ms: durations[p95_index],
+                max_ms: durations[durations.len() - 1],
+            }
+        })
+        .collect();
+    commands.sort_by_key(|stat| std::cmp::Reverse(stat.p95_ms));
+
+    Ok(CommandStats {
+        total_commands: timings.total_commands,
+        error_rate: if timings.total_commands == 0 { 0.0 } else { timings.failed_commands as f64 / timings.total_commands as f64 },
+        window: timings.recent.len(),
+        commands,
     })
 }
 
-// Tauri command to get VM status with detailed information
+// Tauri command to clear the recorded command latencies and totals
 #[tauri::command]
-async fn get_vm_status(vm_id: u32) -> Result<VMInfo, String> {
-    let output = Command::new("ssh")
-        .args(["proxmox", "qm", "status", &vm_id.to_string()])
-        .output()
-        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+async fn reset_command_stats() -> Result<(), String> {
+    let mut timings = COMMAND_TIMINGS.lock().map_err(|e| format!("Failed to lock command stats: {}", e))?;
+    *timings = CommandTimings::default();
+    Ok(())
+}
 
-    if !output.status.success() {
-        return Err(format!("Command failed: {}", String::from_utf8_lossy(&output.stderr)));
+// Helper function to replace the ssh concurrency limit. Commands already holding a slot of
+// the old limit finish normally, so the new limit is exact once they are done.
+fn set_ssh_permits(limit: usize) {
+    if let Ok(mut permits) = SSH_PERMITS.write() {
+        *permits = Arc::new(tokio::sync::Semaphore::new(limit.max(1)));
     }
+}
 
-    let status_output = String::from_utf8_lossy(&output.stdout);
-    let status = if status_output.contains("running") {
-        "Running".to_string()
-    } else if status_output.contains("stopped") {
-        "Stopped".to_string()
-    } else {
-        "Unknown".to_string()
+// Helper function to wait for a free ssh slot; the slot is released when the permit is dropped
+async fn acquire_ssh_permit() -> Option<tokio::sync::OwnedSemaphorePermit> {
+    let permits = SSH_PERMITS.read().ok()?.clone();
+    permits.acquire_owned().await.ok()
+}
+
+// Helper function to run one ssh invocation with capped output and a timeout
+async fn run_ssh(target: &str, args: &[&str], timeout: std::time::Duration) -> Result<std::process::Output, AppError> {
+    let _permit = acquire_ssh_permit().await;
+    let mut child = tokio::process::Command::new("ssh")
+        .arg(target)
+        .args(args)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| AppError::Spawn(e.to_string()))?;
+
+    let limit = app_config().exec.max_output_bytes;
+    let stdout = tokio::spawn(read_capped(child.stdout.take(), limit));
+    let stderr = tokio::spawn(read_capped(child.stderr.take(), limit));
+    let run = async {
+        let status = child.wait().await?;
+        let stdout = stdout.await.map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))??;
+        let stderr = stderr.await.map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))??;
+        Ok::<_, std::io::Error>(std::process::Output { status, stdout, stderr })
     };
 
-    // Get detailed VM info including uptime and resource usage
-    let mut uptime = "Unknown".to_string();
-    let mut cpu_usage = 0.0;
-    let mut memory_usage = 0.0;
+    // Dropping the child on timeout kills the local ssh, which tears down the remote command
+    match tokio::time::timeout(timeout, run).await {
+        Ok(result) => result.map_err(|e| AppError::Io(e.to_string())),
+        Err(_) => Err(AppError::Timeout {
+            command: format!("ssh {} {}", target, redacted_command(args)),
+            after: timeout,
+        }),
+    }
+}
 
-    if status == "Running" {
-        // Get VM uptime
-        if let Ok(uptime_output) = Command::new("ssh")
-            .args(["proxmox", "qm", "monitor", &vm_id.to_string(), "--", "info", "status"])
-            .output() {
-            if uptime_output.status.success() {
-                let uptime_str = String::from_utf8_lossy(&uptime_output.stdout);
-                if let Some(line) = uptime_str.lines().find(|l| l.contains("VM uptime")) {
-                    uptime = line.split(':').nth(1).unwrap_or("Unknown").trim().to_string();
-                }
-            }
-        }
+// Exit status and both output streams of a remote command, for results that show the raw output
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct CommandOutput {
+    exit_code: Option<i32>, // None when the command was killed by a signal
+    stdout: String,
+    stderr: String,
+    duration_ms: u64,
+}
 
-        // Get VM resource usage (mock data for now, can be enhanced)
-        cpu_usage = (vm_id as f64 * 1.5) % 100.0;
-        memory_usage = (vm_id as f64 * 100.0) % 4096.0;
+impl CommandOutput {
+    fn success(&self) -> bool {
+        self.exit_code == Some(0)
     }
+}
 
-    Ok(VMInfo {
-        id: vm_id,
-        name: get_vm_name(vm_id),
-        status,
-        uptime,
-        cpu_usage,
-        memory_usage,
-        description: get_vm_description(vm_id),
+// Helper function to run a command over ssh like run_remote, keeping stdout, stderr and timing together
+async fn run_command(target: &str, args: &[&str], timeout: std::time::Duration) -> Result<CommandOutput, AppError> {
+    let started = std::time::Instant::now();
+    let output = run_remote(target, args, timeout).await?;
+
+    Ok(CommandOutput {
+        exit_code: output.status.code(),
+        stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+        stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        duration_ms: started.elapsed().as_millis() as u64,
     })
 }
 
-// Tauri command to start container
-#[tauri::command]
-async fn start_container(container_id: u32) -> Result<String, String> {
-    let output = Command::new("ssh")
-        .args(["proxmox", "pct", "start", &container_id.to_string()])
-        .output()
-        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+// Helper function to run a local process, feeding it `input` on stdin, giving up after `timeout`
+async fn run_process(mut command: tokio::process::Command, input: Option<String>, timeout: std::time::Duration, description: &str) -> Result<std::process::Output, AppError> {
+    let mut child = command
+        .stdin(if input.is_some() { std::process::Stdio::piped() } else { std::process::Stdio::null() })
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| AppError::Spawn(e.to_string()))?;
+
+    // stdin is written from its own task so a process that echoes it (tee) can't fill its stdout
+    // pipe and stall; dropping the handle at the end closes stdin
+    let writer = child.stdin.take().zip(input).map(|(mut stdin, input)| tokio::spawn(async move {
+        use tokio::io::AsyncWriteExt;
+        stdin.write_all(input.as_bytes()).await
+    }));
+    let run = async {
+        let output = child.wait_with_output().await?;
+        if let Some(writer) = writer {
+            let written = writer.await.map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+            // A process that failed early closes stdin; its own exit status says more than the broken pipe
+            if output.status.success() {
+                written?;
+            }
+        }
+        Ok::<_, std::io::Error>(output)
+    };
 
-    if output.status.success() {
-        Ok(format!("Container {} started successfully", container_id))
-    } else {
-        Err(format!("Failed to start container {}: {}", container_id, String::from_utf8_lossy(&output.stderr)))
+    // Dropping the child on timeout kills the process, as in run_ssh
+    match tokio::time::timeout(timeout, run).await {
+        Ok(result) => result.map_err(|e| AppError::Io(e.to_string())),
+        Err(_) => Err(AppError::Timeout {
+            command: description.to_string(),
+            after: timeout,
+        }),
     }
 }
 
-// Tauri command to stop container
-#[tauri::command]
-async fn stop_container(container_id: u32) -> Result<String, String> {
-    let output = Command::new("ssh")
-        .args(["proxmox", "pct", "stop", &container_id.to_string()])
-        .output()
-        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+// Helper function to run one of the local maintenance scripts with a timeout
+async fn run_script_file(path: String, timeout: std::time::Duration) -> Result<std::process::Output, AppError> {
+    run_process(tokio::process::Command::new(&path), None, timeout, &path).await
+}
 
-    if output.status.success() {
-        Ok(format!("Container {} stopped successfully", container_id))
-    } else {
-        Err(format!("Failed to stop container {}: {}", container_id, String::from_utf8_lossy(&output.stderr)))
+// Helper function to copy a file between this machine and an ssh target ("host:path") with scp.
+// -O picks the original scp protocol, where the remote path goes through the remote shell, so
+// remote paths must be built with scp_remote_path.
+async fn run_scp(source: String, destination: String, timeout: std::time::Duration) -> Result<std::process::Output, AppError> {
+    let description = format!("scp {} {}", source, destination);
+    let _permit = acquire_ssh_permit().await;
+    let mut command = tokio::process::Command::new("scp");
+    command.args(["-q", "-O"]).arg(&source).arg(&destination);
+    run_process(command, None, timeout, &description).await
+}
+
+// Helper function to tell a dropped or refused ssh connection apart from the remote command failing.
+// ssh reserves exit status 255 for its own errors; anything else came from the command itself.
+fn is_connection_failure(result: &Result<std::process::Output, AppError>) -> bool {
+    match result {
+        Ok(output) => output.status.code() == Some(255),
+        Err(AppError::Io(_)) => true,
+        Err(_) => false,
     }
 }
 
-// Tauri command to restart container
-#[tauri::command]
-async fn restart_container(container_id: u32) -> Result<String, String> {
-    let output = Command::new("ssh")
-        .args(["proxmox", "pct", "restart", &container_id.to_string()])
-        .output()
-        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+// Helper function to retry a command on connection failures, backing off exponentially between attempts
+async fn with_retry<F, Fut>(description: &str, mut run: F) -> Result<std::process::Output, AppError>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<std::process::Output, AppError>>,
+{
+    let config = app_config().exec;
+    let attempts = config.retry_attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        let result = run().await;
+        if attempt >= attempts || !is_connection_failure(&result) {
+            return result;
+        }
 
-    if output.status.success() {
-        Ok(format!("Container {} restarted successfully", container_id))
-    } else {
-        Err(format!("Failed to restart container {}: {}", container_id, String::from_utf8_lossy(&output.stderr)))
+        let delay = std::time::Duration::from_millis(config.retry_base_delay_ms.saturating_mul(1 << (attempt - 1).min(16)));
+        tracing::warn!("Connection failed for '{}' (attempt {}/{}), retrying in {:?}", description, attempt, attempts, delay);
+        tokio::time::sleep(delay).await;
+        attempt += 1;
     }
 }
 
-// Tauri command to start VM
-#[tauri::command]
-async fn start_vm(vm_id: u32) -> Result<String, String> {
-    let output = Command::new("ssh")
-        .args(["proxmox", "qm", "start", &vm_id.to_string()])
-        .output()
-        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+// Helper function to run a read-only command over ssh, retrying transient connection failures.
+// Only use this for commands that are safe to run twice.
+async fn run_remote_read(target: &str, args: &[&str], timeout: std::time::Duration) -> Result<std::process::Output, AppError> {
+    with_retry(&format!("ssh {} {}", target, args.join(" ")), || run_remote(target, args, timeout)).await
+}
 
-    if output.status.success() {
-        Ok(format!("VM {} started successfully", vm_id))
-    } else {
-        Err(format!("Failed to start VM {}: {}", vm_id, String::from_utf8_lossy(&output.stderr)))
-    }
+// Helper function to run an SSH command, returning a failed Output when ssh fails or times out
+async fn ssh_output(args: &[&str]) -> std::process::Output {
+    run_remote_read(args[0], &args[1..], *COMMAND_TIMEOUT)
+        .await
+        .unwrap_or_else(|_| std::process::Output {
+            status: std::process::ExitStatus::from_raw(1),
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+        })
 }
 
-// Tauri command to stop VM
-#[tauri::command]
-async fn stop_vm(vm_id: u32) -> Result<String, String> {
-    let output = Command::new("ssh")
-        .args(["proxmox", "qm", "stop", &vm_id.to_string()])
-        .output()
-        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+struct SshTransport;
 
-    if output.status.success() {
-        Ok(format!("VM {} stopped successfully", vm_id))
-    } else {
-        Err(format!("Failed to stop VM {}: {}", vm_id, String::from_utf8_lossy(&output.stderr)))
+#[async_trait]
+impl Transport for SshTransport {
+    async fn list_guests(&self, is_vm: bool) -> Result<Vec<u32>, String> {
+        let output = run_remote_read("proxmox", &[if is_vm { "qm" } else { "pct" }, "list"], *COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if !output.status.success() {
+            return Err(format!("Command failed: {}", String::from_utf8_lossy(&output.stderr)));
+        }
+
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        let mut ids = Vec::new();
+
+        for line in output_str.lines().skip(1) { // Skip header line
+            if let Some(id_str) = line.split_whitespace().next() {
+                if let Ok(id) = id_str.parse::<u32>() {
+                    ids.push(id);
+                }
+            }
+        }
+
+        Ok(ids)
     }
-}
 
-// Tauri command to restart VM
-#[tauri::command]
-async fn restart_vm(vm_id: u32) -> Result<String, String> {
-    let output = Command::new("ssh")
-        .args(["proxmox", "qm", "restart", &vm_id.to_string()])
-        .output()
-        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    async fn guest_status(&self, id: u32, is_vm: bool) -> Result<GuestStatus, String> {
+        let tool = if is_vm { "qm" } else { "pct" };
+        let output = run_remote_read(&guest_host(id).await, &[tool, "status", &id.to_string(), "--verbose"], *COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
 
-    if output.status.success() {
-        Ok(format!("VM {} restarted successfully", vm_id))
-    } else {
-        Err(format!("Failed to restart VM {}: {}", vm_id, String::from_utf8_lossy(&output.stderr)))
+        if !output.status.success() {
+            return Err(format!("Command failed: {}", String::from_utf8_lossy(&output.stderr)));
+        }
+
+        let mut guest_status = parse_status_verbose(&String::from_utf8_lossy(&output.stdout));
+
+        // Containers are read from their cgroup instead, which works on more PVE versions
+        if guest_status.status == "Running" && !is_vm {
+            guest_status.cpu_usage = None;
+            guest_status.memory_usage = None;
+            guest_status.memory_percent = None;
+            if let Ok(usage) = container_usage(id, guest_status.uptime_seconds).await {
+                guest_status.cpu_usage = usage.cpu_percent;
+                guest_status.memory_usage = Some(usage.memory_used_mb);
+                guest_status.memory_percent = usage.memory_limit_mb
+                    .filter(|limit| *limit > 0.0)
+                    .map(|limit| usage.memory_used_mb / limit * 100.0);
+            }
+        }
+
+        Ok(guest_status)
     }
-}
 
-// Tauri command to shutdown VM (graceful)
-#[tauri::command]
-async fn shutdown_vm(vm_id: u32) -> Result<String, String> {
-    let output = Command::new("ssh")
-        .args(["proxmox", "qm", "shutdown", &vm_id.to_string()])
-        .output()
-        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    async fn host_summary(&self) -> Result<HostSummary, String> {
+        // Get hostname
+        let hostname_output = ssh_output(&["proxmox", "hostname"]).await;
+        let hostname = if hostname_output.status.success() {
+            String::from_utf8_lossy(&hostname_output.stdout).trim().to_string()
+        } else {
+            "Unknown".to_string()
+        };
 
-    if output.status.success() {
-        Ok(format!("VM {} shutdown initiated successfully", vm_id))
-    } else {
-        Err(format!("Failed to shutdown VM {}: {}", vm_id, String::from_utf8_lossy(&output.stderr)))
+        // Get Proxmox version
+        let version_output = ssh_output(&["proxmox", "pveversion"]).await;
+        let version = if version_output.status.success() {
+            String::from_utf8_lossy(&version_output.stdout).lines().next().unwrap_or("Unknown").to_string()
+        } else {
+            "Unknown".to_string()
+        };
+
+        // Get uptime
+        let uptime_output = ssh_output(&["proxmox", "uptime", "-p"]).await;
+        let uptime = if uptime_output.status.success() {
+            String::from_utf8_lossy(&uptime_output.stdout).trim().to_string()
+        } else {
+            "Unknown".to_string()
+        };
+
+        // Get CPU count
+        let cpu_output = ssh_output(&["proxmox", "nproc"]).await;
+        let cpu_count = if cpu_output.status.success() {
+            String::from_utf8_lossy(&cpu_output.stdout).trim().parse().unwrap_or(0)
+        } else {
+            0
+        };
+
+        // Get memory info
+        let memory_output = ssh_output(&["proxmox", "free", "-h"]).await;
+        let memory_total = if memory_output.status.success() {
+            let output_str = String::from_utf8_lossy(&memory_output.stdout);
+            output_str.lines().nth(1)
+                .and_then(|line| line.split_whitespace().nth(1))
+                .unwrap_or("Unknown")
+                .to_string()
+        } else {
+            "Unknown".to_string()
+        };
+
+        Ok(HostSummary {
+            hostname,
+            version,
+            uptime,
+            cpu_count,
+            memory_total,
+        })
+    }
+
+    async fn storage_info(&self) -> Result<Vec<StorageInfo>, String> {
+        let output = run_remote_read("proxmox", &["pvesm", "status"], *COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        let mut storage_info = Vec::new();
+
+        if output.status.success() {
+            let output_str = String::from_utf8_lossy(&output.stdout);
+            for line in output_str.lines().skip(1) { // Skip header
+                let parts: Vec<&str> = line.split_whitespace().collect();
+                if parts.len() >= 6 {
+                    // Calculate usage percentage
+                    let usage_percent = if let (Ok(total_bytes), Ok(used_bytes)) = (parts[3].parse::<f64>(), parts[4].parse::<f64>()) {
+                        if total_bytes > 0.0 {
+                            (used_bytes / total_bytes) * 100.0
+                        } else {
+                            0.0
+                        }
+                    } else {
+                        0.0
+                    };
+
+                    storage_info.push(StorageInfo {
+                        name: parts[0].to_string(),
+                        storage_type: parts[1].to_string(),
+                        total: parts[3].to_string(),
+                        used: parts[4].to_string(),
+                        available: parts[5].to_string(),
+                        usage_percent,
+                    });
+                }
+            }
+        }
+
+        Ok(storage_info)
+    }
+
+    async fn cluster_resources(&self) -> Result<Vec<serde_json::Value>, String> {
+        let output = run_remote_read("proxmox", &["pvesh", "get", "/cluster/resources", "--output-format", "json"], *COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if !output.status.success() {
+            return Err(format!("Command failed: {}", String::from_utf8_lossy(&output.stderr)));
+        }
+
+        serde_json::from_slice(&output.stdout)
+            .map_err(|e| format!("Failed to parse cluster resources: {}", e))
     }
 }
 
-// Tauri command to reset VM (hard reset)
-#[tauri::command]
-async fn reset_vm(vm_id: u32) -> Result<String, String> {
-    let output = Command::new("ssh")
-        .args(["proxmox", "qm", "reset", &vm_id.to_string()])
-        .output()
-        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+struct PveApiTransport {
+    config: TransportConfig,
+}
 
-    if output.status.success() {
-        Ok(format!("VM {} reset successfully", vm_id))
-    } else {
-        Err(format!("Failed to reset VM {}: {}", vm_id, String::from_utf8_lossy(&output.stderr)))
+impl PveApiTransport {
+    // Helper function to GET an API path and unwrap the "data" envelope
+    async fn api_get(&self, path: &str) -> Result<serde_json::Value, String> {
+        let client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(!self.config.verify_tls)
+            .timeout(*COMMAND_TIMEOUT)
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+
+        let url = format!("{}/api2/json{}", self.config.api_url.trim_end_matches('/'), path);
+        let response = client.get(&url)
+            .header("Authorization", format!("PVEAPIToken={}={}", self.config.token_id, self.config.token_secret))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach Proxmox API: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("Proxmox API request {} failed: {}", path, response.status()));
+        }
+
+        let mut body: serde_json::Value = response.json()
+            .await
+            .map_err(|e| format!("Failed to parse Proxmox API response: {}", e))?;
+
+        Ok(body["data"].take())
+    }
+
+    // Helper function to get the configured node, or the first node the API reports
+    async fn node(&self) -> Result<String, String> {
+        if !self.config.node.is_empty() {
+            return Ok(self.config.node.clone());
+        }
+
+        let nodes = self.api_get("/nodes").await?;
+        nodes.as_array()
+            .and_then(|nodes| nodes.first())
+            .and_then(|node| node["node"].as_str())
+            .map(|node| node.to_string())
+            .ok_or_else(|| "Proxmox API returned no nodes".to_string())
     }
 }
 
-// Tauri command to get VM configuration
-#[tauri::command]
-async fn get_vm_config(vm_id: u32) -> Result<String, String> {
-    let output = Command::new("ssh")
-        .args(["proxmox", "qm", "config", &vm_id.to_string()])
-        .output()
-        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+// Helper function to read a number the API may encode as either a number or a string
+fn json_u64(value: &serde_json::Value) -> Option<u64> {
+    value.as_u64().or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+}
 
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(format!("Failed to get VM {} config: {}", vm_id, String::from_utf8_lossy(&output.stderr)))
+#[async_trait]
+impl Transport for PveApiTransport {
+    async fn list_guests(&self, is_vm: bool) -> Result<Vec<u32>, String> {
+        let node = self.node().await?;
+        let guests = self.api_get(&format!("/nodes/{}/{}", node, if is_vm { "qemu" } else { "lxc" })).await?;
+
+        let mut ids: Vec<u32> = guests.as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|guest| json_u64(&guest["vmid"]))
+            .map(|id| id as u32)
+            .collect();
+        ids.sort();
+
+        Ok(ids)
+    }
+
+    async fn guest_status(&self, id: u32, is_vm: bool) -> Result<GuestStatus, String> {
+        let node = match guest_node(id).await { Some(node) => node, None => self.node().await? };
+        let current = self.api_get(&format!("/nodes/{}/{}/{}/status/current", node, if is_vm { "qemu" } else { "lxc" }, id)).await?;
+
+        let status = parse_guest_state(current["status"].as_str().unwrap_or(""));
+        let running = status == "Running";
+
+        Ok(GuestStatus {
+            cpu_usage: current["cpu"].as_f64().filter(|_| running).map(|cpu| cpu * 100.0),
+            memory_usage: json_u64(&current["mem"]).filter(|_| running).map(|mem| mem as f64 / 1024.0 / 1024.0),
+            memory_percent: json_u64(&current["mem"]).zip(json_u64(&current["maxmem"]).filter(|max| *max > 0))
+                .filter(|_| running)
+                .map(|(mem, max)| mem as f64 / max as f64 * 100.0),
+            uptime: json_u64(&current["uptime"]).filter(|_| running).map(format_uptime),
+            uptime_seconds: json_u64(&current["uptime"]).filter(|_| running),
+            lock: current["lock"].as_str().filter(|lock| !lock.is_empty()).map(|lock| lock.to_string()),
+            status,
+        })
+    }
+
+    async fn host_summary(&self) -> Result<HostSummary, String> {
+        let node = self.node().await?;
+        let status = self.api_get(&format!("/nodes/{}/status", node)).await?;
+
+        Ok(HostSummary {
+            version: status["pveversion"].as_str().unwrap_or("Unknown").to_string(),
+            uptime: json_u64(&status["uptime"]).map(format_uptime).unwrap_or_else(|| "Unknown".to_string()),
+            cpu_count: json_u64(&status["cpuinfo"]["cpus"]).unwrap_or(0) as u32,
+            memory_total: json_u64(&status["memory"]["total"])
+                .map(|bytes| format!("{:.1}Gi", bytes as f64 / 1024.0 / 1024.0 / 1024.0))
+                .unwrap_or_else(|| "Unknown".to_string()),
+            hostname: node,
+        })
+    }
+
+    async fn storage_info(&self) -> Result<Vec<StorageInfo>, String> {
+        let node = self.node().await?;
+        let storages = self.api_get(&format!("/nodes/{}/storage", node)).await?;
+
+        // Report sizes in KiB to match `pvesm status`
+        Ok(storages.as_array()
+            .into_iter()
+            .flatten()
+            .map(|storage| {
+                let total = json_u64(&storage["total"]).unwrap_or(0);
+                let used = json_u64(&storage["used"]).unwrap_or(0);
+                let available = json_u64(&storage["avail"]).unwrap_or(0);
+
+                StorageInfo {
+                    name: storage["storage"].as_str().unwrap_or("Unknown").to_string(),
+                    storage_type: storage["type"].as_str().unwrap_or("Unknown").to_string(),
+                    total: (total / 1024).to_string(),
+                    used: (used / 1024).to_string(),
+                    available: (available / 1024).to_string(),
+                    usage_percent: if total > 0 { used as f64 / total as f64 * 100.0 } else { 0.0 },
+                }
+            })
+            .collect())
+    }
+
+    async fn cluster_resources(&self) -> Result<Vec<serde_json::Value>, String> {
+        let resources = self.api_get("/cluster/resources").await?;
+        Ok(resources.as_array().cloned().unwrap_or_default())
     }
 }
 
-// Tauri command to clone VM
+// Tauri command to get the transport settings, with the token secret redacted
 #[tauri::command]
-async fn clone_vm(vm_id: u32, new_vm_id: u32, new_name: String) -> Result<String, String> {
-    let output = Command::new("ssh")
-        .args(["proxmox", "qm", "clone", &vm_id.to_string(), &new_vm_id.to_string(), "--name", &new_name])
-        .output()
-        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
-
-    if output.status.success() {
-        Ok(format!("VM {} cloned to VM {} ({}) successfully", vm_id, new_vm_id, new_name))
-    } else {
-        Err(format!("Failed to clone VM {}: {}", vm_id, String::from_utf8_lossy(&output.stderr)))
+async fn get_transport_config() -> Result<TransportConfig, String> {
+    let mut config = app_config().transport;
+    if !config.token_secret.is_empty() {
+        config.token_secret = "********".to_string();
     }
+    Ok(config)
 }
 
-// Tauri command to migrate VM to another node (if in cluster)
+// Tauri command to change the transport settings; an empty secret keeps the stored one
 #[tauri::command]
-async fn migrate_vm(vm_id: u32, target_node: String) -> Result<String, String> {
-    let output = Command::new("ssh")
-        .args(["proxmox", "qm", "migrate", &vm_id.to_string(), &target_node])
-        .output()
-        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+async fn set_transport_config(config: TransportConfig) -> Result<TransportConfig, String> {
+    if config.mode != "ssh" && config.mode != "pve_api" {
+        return Err(format!("Unknown transport mode '{}', expected 'ssh' or 'pve_api'", config.mode));
+    }
 
-    if output.status.success() {
-        Ok(format!("VM {} migration to {} initiated successfully", vm_id, target_node))
-    } else {
-        Err(format!("Failed to migrate VM {}: {}", vm_id, String::from_utf8_lossy(&output.stderr)))
+    update_app_config(|app_config| {
+        let token_secret = if config.token_secret.is_empty() || config.token_secret == "********" {
+            app_config.transport.token_secret.clone()
+        } else {
+            config.token_secret.clone()
+        };
+        app_config.transport = TransportConfig { token_secret, ..config };
+    })?;
+
+    // Cached data may have come from the previous transport
+    if let Ok(mut cache) = DATA_CACHE.write() {
+        cache.clear();
     }
+
+    get_transport_config().await
 }
 
-// Proxmox Host Management Commands
+// Helper function to fetch detailed container information
+async fn fetch_container_details(container_id: u32) -> Result<ContainerDetails, String> {
+    let os_info_output = run_remote(&guest_host(container_id).await, &["pct", "exec", &container_id.to_string(), "--", "lsb_release", "-a"], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to fetch OS info: {}", e))?;
 
-// Tauri command to get Proxmox host information
-#[tauri::command]
-async fn get_proxmox_host_info() -> Result<ProxmoxHostInfo, String> {
-    let cache_key = "proxmox_host_info";
-    
-    // Check if we have valid cached data
-    if is_cache_valid_with_duration(cache_key, *HOST_CACHE_DURATION) {
-        if let Some(cached_data) = get_from_cache(cache_key) {
-            if let Ok(host_info) = serde_json::from_str::<ProxmoxHostInfo>(&cached_data) {
-                return Ok(host_info);
-            }
-        }
-    }
-    
-    // Get hostname
-    let hostname_output = Command::new("ssh")
-        .args(["proxmox", "hostname"])
-        .output()
-        .unwrap_or_else(|_| std::process::Output {
-            status: std::process::ExitStatus::from_raw(1),
-            stdout: Vec::new(),
-            stderr: Vec::new(),
-        });
-    
-    let hostname = if hostname_output.status.success() {
-        String::from_utf8_lossy(&hostname_output.stdout).trim().to_string()
-    } else {
-        "Unknown".to_string()
-    };
-    
-    // Get Proxmox version
-    let version_output = Command::new("ssh")
-        .args(["proxmox", "pveversion"])
-        .output()
-        .unwrap_or_else(|_| std::process::Output {
-            status: std::process::ExitStatus::from_raw(1),
-            stdout: Vec::new(),
-            stderr: Vec::new(),
-        });
-    
-    let version = if version_output.status.success() {
-        String::from_utf8_lossy(&version_output.stdout).lines().next().unwrap_or("Unknown").to_string()
-    } else {
-        "Unknown".to_string()
-    };
-    
-    // Get uptime
-    let uptime_output = Command::new("ssh")
-        .args(["proxmox", "uptime", "-p"])
-        .output()
-        .unwrap_or_else(|_| std::process::Output {
-            status: std::process::ExitStatus::from_raw(1),
-            stdout: Vec::new(),
-            stderr: Vec::new(),
-        });
-    
-    let uptime = if uptime_output.status.success() {
-        String::from_utf8_lossy(&uptime_output.stdout).trim().to_string()
+    let os_info = if os_info_output.status.success() {
+        String::from_utf8_lossy(&os_info_output.stdout).lines().collect::<Vec<&str>>().join(" ")
     } else {
         "Unknown".to_string()
     };
-    
-    // Get CPU count
-    let cpu_output = Command::new("ssh")
-        .args(["proxmox", "nproc"])
-        .output()
-        .unwrap_or_else(|_| std::process::Output {
-            status: std::process::ExitStatus::from_raw(1),
-            stdout: Vec::new(),
-            stderr: Vec::new(),
-        });
-    
-    let cpu_count = if cpu_output.status.success() {
-        String::from_utf8_lossy(&cpu_output.stdout).trim().parse().unwrap_or(0)
+
+    let processes_output = run_remote(&guest_host(container_id).await, &["pct", "exec", &container_id.to_string(), "--", "ps", "-e"], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to fetch running processes: {}", e))?;
+
+    let running_processes: Vec<String> = if processes_output.status.success() {
+        String::from_utf8_lossy(&processes_output.stdout)
+            .lines().map(|s| s.to_string()).collect()
     } else {
-        0
+        vec!["Unknown".to_string()]
     };
-    
-    // Get memory info
-    let memory_output = Command::new("ssh")
-        .args(["proxmox", "free", "-h"])
-        .output()
-        .unwrap_or_else(|_| std::process::Output {
-            status: std::process::ExitStatus::from_raw(1),
-            stdout: Vec::new(),
-            stderr: Vec::new(),
-        });
-    
-    let memory_total = if memory_output.status.success() {
-        let output_str = String::from_utf8_lossy(&memory_output.stdout);
-        output_str.lines().nth(1)
-            .and_then(|line| line.split_whitespace().nth(1))
-            .unwrap_or("Unknown")
-            .to_string()
+
+    let web_ui_url = Some(format!("http://{}:8000", get_container_ip_address(container_id).await?));
+
+    Ok(ContainerDetails {
+        id: container_id,
+        name: format!("Container {}", container_id),
+        status: "Unknown".to_string(),
+        os_info,
+        running_processes,
+        installed_binaries: Vec::new(), // Add logic to fetch binaries if needed
+        systemd_services: Vec::new(), // Add logic to fetch services if needed
+        config_files: Vec::new(), // Add logic to manage or fetch configs if needed
+        web_ui_url,
+        category: get_container_category(container_id),
+        description: get_container_description(container_id),
+    })
+}
+
+async fn get_container_ip_address(container_id: u32) -> Result<String, String> {
+    resolve_guest_ip(container_id, false).await
+        .ok_or_else(|| format!("Could not determine the IP address of container {}", container_id))
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct GuestNetwork {
+    id: u32,
+    is_vm: bool,
+    ip_addresses: Vec<String>,
+    primary_ip: Option<String>,
+}
+
+// Helper function to look up a guest's live IP addresses, cached for a short time
+async fn lookup_guest_network(id: u32, is_vm: bool) -> Result<GuestNetwork, String> {
+    let cache_key = format!("guest_network_{}_{}", if is_vm { "vm" } else { "ct" }, id);
+
+    if is_cache_valid_with_duration(&cache_key, *IP_CACHE_DURATION) {
+        if let Some(cached_data) = get_from_cache(&cache_key) {
+            if let Ok(network) = serde_json::from_str::<GuestNetwork>(&cached_data) {
+                return Ok(network);
+            }
+        }
+    }
+
+    let mut ip_addresses = Vec::new();
+
+    if is_vm {
+        // Requires the QEMU guest agent running inside the VM
+        let output = run_remote(&guest_host(id).await, &["qm", "guest", "cmd", &id.to_string(), "network-get-interfaces"], *COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if output.status.success() {
+            let interfaces: serde_json::Value = serde_json::from_slice(&output.stdout)
+                .map_err(|e| format!("Failed to parse guest agent output: {}", e))?;
+
+            for interface in interfaces.as_array().into_iter().flatten() {
+                if interface["name"].as_str() == Some("lo") {
+                    continue;
+                }
+                for address in interface["ip-addresses"].as_array().into_iter().flatten() {
+                    if let Some(ip) = address["ip-address"].as_str() {
+                        ip_addresses.push(ip.to_string());
+                    }
+                }
+            }
+        }
     } else {
-        "Unknown".to_string()
-    };
-    
-    // Get storage information
-    let storage_info = get_storage_info().await.unwrap_or_default();
-    
-    let host_info = ProxmoxHostInfo {
-        hostname,
-        version,
-        uptime,
-        cpu_count,
-        memory_total,
-        storage_info,
-        node_status: "Online".to_string(),
+        let output = run_remote(&guest_host(id).await, &["lxc-info", "-n", &id.to_string(), "-iH"], *COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if output.status.success() {
+            ip_addresses = String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(|line| line.trim().to_string())
+                .filter(|line| !line.is_empty())
+                .collect();
+        }
+    }
+
+    ip_addresses.retain(|ip| ip.parse::<std::net::IpAddr>().map(|addr| !addr.is_loopback()).unwrap_or(false));
+    let primary_ip = ip_addresses.iter()
+        .find(|ip| ip.parse::<std::net::Ipv4Addr>().is_ok())
+        .or_else(|| ip_addresses.first())
+        .cloned();
+
+    let network = GuestNetwork {
+        id,
+        is_vm,
+        ip_addresses,
+        primary_ip,
     };
-    
+
     // Store in cache
-    if let Ok(serialized) = serde_json::to_string(&host_info) {
-        store_in_cache(cache_key, &serialized);
+    if let Ok(serialized) = serde_json::to_string(&network) {
+        store_in_cache(&cache_key, &serialized);
     }
-    
-    Ok(host_info)
+
+    Ok(network)
 }
 
-// Tauri command to reboot Proxmox host
-#[tauri::command]
-async fn reboot_proxmox_host() -> Result<String, String> {
-    let output = Command::new("ssh")
-        .args(["proxmox", "sudo", "systemctl", "reboot"])
-        .output()
-        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
-    
-    if output.status.success() {
-        Ok("Proxmox host reboot initiated successfully".to_string())
-    } else {
-        Err(format!("Failed to reboot Proxmox host: {}", String::from_utf8_lossy(&output.stderr)))
-    }
+// Helper function to get the primary live IP of a guest, if it can be determined
+async fn resolve_guest_ip(id: u32, is_vm: bool) -> Option<String> {
+    lookup_guest_network(id, is_vm).await.ok().and_then(|network| network.primary_ip)
 }
 
-// Tauri command to shutdown Proxmox host
+// Tauri command to get the live network addresses of a container or VM
 #[tauri::command]
-async fn shutdown_proxmox_host() -> Result<String, String> {
-    let output = Command::new("ssh")
-        .args(["proxmox", "sudo", "systemctl", "poweroff"])
-        .output()
-        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
-    
-    if output.status.success() {
-        Ok("Proxmox host shutdown initiated successfully".to_string())
-    } else {
-        Err(format!("Failed to shutdown Proxmox host: {}", String::from_utf8_lossy(&output.stderr)))
-    }
+async fn get_guest_network(id: u32, is_vm: bool) -> Result<GuestNetwork, String> {
+    lookup_guest_network(id, is_vm).await
 }
 
-// Tauri command to get Proxmox cluster status
+
+// Tauri command to get container status
 #[tauri::command]
-async fn get_cluster_status() -> Result<String, String> {
-    let output = Command::new("ssh")
-        .args(["proxmox", "pvecm", "status"])
-        .output()
-        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+async fn get_container_status(container_id: u32) -> Result<ContainerInfo, String> {
+    // Get container status - this should be fast
+    let guest_status = transport().guest_status(container_id, false).await?;
+    let status = guest_status.status;
+    let lock = guest_status.lock;
+
+    // Get container name from metadata
+    let container_name = get_container_display_name(container_id);
     
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(format!("Failed to get cluster status: {}", String::from_utf8_lossy(&output.stderr)))
+    // Set default values, preferring live numbers when the transport provides them
+    let mut memory_usage = guest_status.memory_usage.unwrap_or(0.0);
+    let mut cpu_usage = guest_status.cpu_usage.unwrap_or(0.0);
+    let (usage_stale, usage_sampled_at) = match settle_usage(&format!("ct:{}", container_id), status == "Running", guest_status.cpu_usage, guest_status.memory_usage) {
+        UsageSource::LastKnown(last) => {
+            cpu_usage = last.cpu_usage;
+            memory_usage = last.memory_usage;
+            (true, Some(last.sampled_at))
+        }
+        UsageSource::Live => (false, Some(Utc::now())),
+        UsageSource::Unknown => (false, None),
+    };
+    let uptime = guest_status.uptime.unwrap_or_else(|| if status == "Running" { "Running".to_string() } else { "Stopped".to_string() });
+    let uptime_seconds = guest_status.uptime_seconds;
+    let mut os_info_str = None;
+
+    // Only get additional details if container is running, and do it quickly
+    if status == "Running" {
+        // Try to get basic OS info - a timeout here just leaves it unknown
+        let os_command = run_remote_read(&guest_host(container_id).await, &["pct", "exec", &container_id.to_string(), "--", "cat", "/etc/os-release"], *COMMAND_TIMEOUT).await;
+            
+        if let Ok(output) = os_command {
+            if output.status.success() {
+                let os_str = String::from_utf8_lossy(&output.stdout);
+                if let Some(name_line) = os_str.lines().find(|line| line.starts_with("NAME=")) {
+                    let os_name = name_line.split('=').nth(1)
+                        .unwrap_or("Unknown")
+                        .trim_matches('"')
+                        .to_string();
+                    if !os_name.is_empty() && os_name != "Unknown" {
+                        os_info_str = Some(os_name);
+                    }
+                }
+            }
+        }
     }
+
+    Ok(ContainerInfo {
+        id: container_id,
+        name: container_name,
+        status,
+        uptime,
+        cpu_usage,
+        memory_usage,
+        category: get_container_category(container_id),
+        description: get_container_description(container_id),
+        web_ui_url: get_container_web_ui_url(container_id).await,
+        os_info: os_info_str,
+        running_processes: Vec::new(),
+        template: false,
+        health_score: None,
+        lock,
+        usage_stale,
+        usage_sampled_at,
+        uptime_seconds,
+    })
 }
 
-// Tauri command to update Proxmox packages
+// Tauri command to get VM status with detailed information
 #[tauri::command]
-async fn update_proxmox_packages() -> Result<String, String> {
-    let output = Command::new("ssh")
-        .args(["proxmox", "sudo", "apt", "update", "&&", "sudo", "apt", "upgrade", "-y"])
-        .output()
-        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
-    
-    if output.status.success() {
-        Ok("Package update completed successfully".to_string())
-    } else {
-        Err(format!("Failed to update packages: {}", String::from_utf8_lossy(&output.stderr)))
-    }
+async fn get_vm_status(vm_id: u32) -> Result<VMInfo, String> {
+    let guest_status = transport().guest_status(vm_id, true).await?;
+    let status = guest_status.status;
+    let lock = guest_status.lock;
+
+    // Get detailed VM info including uptime and resource usage
+    let uptime = guest_status.uptime.unwrap_or_else(|| "Unknown".to_string());
+    let uptime_seconds = guest_status.uptime_seconds;
+    let mut cpu_usage = guest_status.cpu_usage.unwrap_or(0.0);
+    let mut memory_usage = guest_status.memory_usage.unwrap_or(0.0);
+    let memory_percent = guest_status.memory_percent;
+    let (usage_stale, usage_sampled_at) = match settle_usage(&format!("vm:{}", vm_id), status == "Running", guest_status.cpu_usage, guest_status.memory_usage) {
+        UsageSource::LastKnown(last) => {
+            cpu_usage = last.cpu_usage;
+            memory_usage = last.memory_usage;
+            (true, Some(last.sampled_at))
+        }
+        UsageSource::Live => (false, Some(Utc::now())),
+        UsageSource::Unknown => (false, None),
+    };
+
+    Ok(VMInfo {
+        id: vm_id,
+        name: get_vm_name(vm_id),
+        status,
+        uptime,
+        cpu_usage,
+        memory_usage,
+        description: get_vm_description(vm_id),
+        template: false,
+        health_score: None,
+        lock,
+        usage_stale,
+        usage_sampled_at,
+        uptime_seconds,
+        memory_percent,
+    })
 }
 
-// Helper function to get storage information
-async fn get_storage_info() -> Result<Vec<StorageInfo>, String> {
-    let output = Command::new("ssh")
-        .args(["proxmox", "pvesm", "status"])
-        .output()
-        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
-    
-    let mut storage_info = Vec::new();
-    
-    if output.status.success() {
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        for line in output_str.lines().skip(1) { // Skip header
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 6 {
-                let name = parts[0].to_string();
-                let storage_type = parts[1].to_string();
-                let total = parts[3].to_string();
-                let used = parts[4].to_string();
-                let available = parts[5].to_string();
-                
-                // Calculate usage percentage
-                let usage_percent = if let (Ok(total_bytes), Ok(used_bytes)) = (parts[3].parse::<f64>(), parts[4].parse::<f64>()) {
-                    if total_bytes > 0.0 {
-                        (used_bytes / total_bytes) * 100.0
-                    } else {
-                        0.0
+// Last cpu/memory figures observed for a running guest, kept so a stopped guest doesn't blank its graphs
+#[derive(Debug, Serialize, Deserialize)]
+struct LastUsage {
+    cpu_usage: f64,
+    memory_usage: f64,
+    sampled_at: DateTime<Utc>,
+}
+
+enum UsageSource {
+    Live,                 // The transport reported real numbers
+    LastKnown(LastUsage), // The guest is stopped; these were the numbers when it last ran
+    Unknown,              // Neither; the caller keeps its fallback values
+}
+
+// Helper function to remember live usage for a running guest, or recall it for a stopped one
+fn settle_usage(target: &str, running: bool, cpu_usage: Option<f64>, memory_usage: Option<f64>) -> UsageSource {
+    let cache_key = format!("last_usage_{}", target);
+
+    if running {
+        return match (cpu_usage, memory_usage) {
+            (Some(cpu_usage), Some(memory_usage)) => {
+                if cpu_usage > 0.0 || memory_usage > 0.0 {
+                    let last = LastUsage { cpu_usage, memory_usage, sampled_at: Utc::now() };
+                    if let Ok(serialized) = serde_json::to_string(&last) {
+                        store_in_cache(&cache_key, &serialized);
                     }
-                } else {
-                    0.0
-                };
-                
-                storage_info.push(StorageInfo {
-                    name,
-                    storage_type,
-                    total,
-                    used,
-                    available,
-                    usage_percent,
-                });
+                }
+                UsageSource::Live
             }
-        }
+            _ => UsageSource::Unknown,
+        };
     }
-    
-    Ok(storage_info)
+
+    get_from_cache(&cache_key)
+        .and_then(|cached| serde_json::from_str::<LastUsage>(&cached).ok())
+        .map(UsageSource::LastKnown)
+        .unwrap_or(UsageSource::Unknown)
 }
 
-// Helper function to get list of existing containers from Proxmox
-async fn get_existing_containers() -> Result<Vec<u32>, String> {
-    let output = Command::new("ssh")
-        .args(["proxmox", "pct", "list"])
-        .output()
+// Helper function to find the "lock:" line of a guest config, ignoring snapshot sections
+fn parse_config_lock(config: &str) -> Option<String> {
+    config_value(config, "lock").filter(|lock| !lock.is_empty())
+}
+
+// Helper function to get the lock currently held on a container or VM, if any
+async fn guest_lock(id: u32, is_vm: bool) -> Result<Option<String>, String> {
+    let output = run_remote(&guest_host(id).await, &[if is_vm { "qm" } else { "pct" }, "config", &id.to_string()], *COMMAND_TIMEOUT)
+        .await
         .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
 
     if !output.status.success() {
-        return Err(format!("Command failed: {}", String::from_utf8_lossy(&output.stderr)));
+        return Err(format!("Failed to read config of {} {}: {}", if is_vm { "VM" } else { "container" }, id, String::from_utf8_lossy(&output.stderr)));
     }
 
-    let output_str = String::from_utf8_lossy(&output.stdout);
-    let mut container_ids = Vec::new();
-    
-    for line in output_str.lines().skip(1) { // Skip header line
-        if let Some(id_str) = line.split_whitespace().next() {
-            if let Ok(id) = id_str.parse::<u32>() {
-                container_ids.push(id);
-            }
-        }
+    Ok(parse_config_lock(&String::from_utf8_lossy(&output.stdout)))
+}
+
+// Helper function to refuse a power action on a locked guest with a clearer error than Proxmox gives.
+// If the config can't be read the action goes ahead and reports whatever Proxmox says.
+async fn ensure_unlocked(id: u32, is_vm: bool) -> Result<(), String> {
+    if let Ok(Some(lock)) = guest_lock(id, is_vm).await {
+        return Err(format!(
+            "{} {} is locked ({}); wait for the running {} task to finish, or unlock it if the lock is stale",
+            if is_vm { "VM" } else { "Container" }, id, lock, lock
+        ));
     }
-    
-    Ok(container_ids)
+    Ok(())
 }
 
-// Helper function to get list of existing VMs from Proxmox
-async fn get_existing_vms() -> Result<Vec<u32>, String> {
-    let output = Command::new("ssh")
-        .args(["proxmox", "qm", "list"])
-        .output()
-        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+#[derive(Debug, Serialize, Deserialize)]
+struct StartStep {
+    id: u32,
+    is_vm: bool,
+    result: String, // "started", "already_running", "failed", "timeout" or "skipped"
+    message: String,
+    elapsed_secs: u64,
+}
 
-    if !output.status.success() {
-        return Err(format!("Command failed: {}", String::from_utf8_lossy(&output.stderr)));
-    }
+// Helper function to order a plan's guests so each comes after its dependencies, lowest order first
+fn start_plan_sequence(plan: &StartPlan) -> Result<Vec<StartPlanGuest>, String> {
+    let mut pending = plan.guests.clone();
+    pending.sort_by_key(|guest| (guest.order, guest.id));
+    let mut sequence: Vec<StartPlanGuest> = Vec::new();
 
-    let output_str = String::from_utf8_lossy(&output.stdout);
-    let mut vm_ids = Vec::new();
-    
-    for line in output_str.lines().skip(1) { // Skip header line
-        if let Some(id_str) = line.split_whitespace().next() {
-            if let Ok(id) = id_str.parse::<u32>() {
-                vm_ids.push(id);
+    while !pending.is_empty() {
+        let ready = pending.iter().position(|guest| {
+            guest.depends_on.iter().all(|dep| sequence.iter().any(|done| done.id == *dep))
+        });
+        match ready {
+            Some(index) => sequence.push(pending.remove(index)),
+            None => {
+                let stuck: Vec<String> = pending.iter().map(|guest| guest.id.to_string()).collect();
+                return Err(format!("Guests {} depend on guests outside the plan or on each other", stuck.join(", ")));
             }
         }
     }
-    
-    Ok(vm_ids)
+
+    Ok(sequence)
 }
 
-// System maintenance commands
+// Helper function to start one guest of a plan and wait for it to report Running
+async fn run_start_step(guest: &StartPlanGuest, timeout: std::time::Duration) -> StartStep {
+    let started = std::time::Instant::now();
+    let step = |result: &str, message: String| StartStep {
+        id: guest.id,
+        is_vm: guest.is_vm,
+        result: result.to_string(),
+        message,
+        elapsed_secs: started.elapsed().as_secs(),
+    };
 
-// Tauri command to fix all inactive services
-#[tauri::command]
-async fn fix_all_services() -> Result<FixResult, String> {
-    let services = get_all_services().await.unwrap_or_default();
-    let mut actions_taken = Vec::new();
-    let mut success = true;
+    // Poll the same transport status check get_container_status/get_vm_status use, without their extra lookups
+    match transport().guest_status(guest.id, guest.is_vm).await {
+        Ok(status) if status.status == "Running" => return step("already_running", "Already running".to_string()),
+        Ok(_) => {}
+        Err(e) => return step("failed", e),
+    }
 
-    for service in services {
-        if !service.active {
-            if let Err(e) = control_service(service.name.clone(), "restart".to_string(), service.container_id, service.vm_id).await {
-                success = false;
-                actions_taken.push(format!("Failed to restart {}: {}", service.name, e));
-            } else {
-                actions_taken.push(format!("Restarted {}", service.name));
+    let start = if guest.is_vm { start_vm(guest.id).await } else { start_container(guest.id).await };
+    if let Err(e) = start {
+        return step("failed", e);
+    }
+
+    while started.elapsed() < timeout {
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+        if let Ok(status) = transport().guest_status(guest.id, guest.is_vm).await {
+            if status.status == "Running" {
+                return step("started", "Running".to_string());
             }
         }
     }
+    step("timeout", format!("Not running after {}s", timeout.as_secs()))
+}
 
-    Ok(FixResult {
-        success,
-        message: if success { "All inactive services fixed.".to_string() } else { "Some services failed to fix.".to_string() },
-        actions_taken,
-        timestamp: Utc::now(),
-    })
+// Tauri command to list the saved start plans
+#[tauri::command]
+async fn get_start_plans() -> Result<HashMap<String, StartPlan>, String> {
+    Ok(app_config().start_plans)
 }
 
-// Tauri command to check and install missing binaries
+// Tauri command to save (or delete with None) a start plan
 #[tauri::command]
-async fn check_and_install_binaries() -> Result<InstallResult, String> {
-    let mut binaries = Vec::new();
-    
-    // Define binaries to check
-    let binary_definitions = [
-        // System binaries
-        ("docker", None, None),
-        ("systemctl", None, None),
-        ("nginx", None, None),
-        
-        // Application binaries (in containers)
-        ("sonarr", Some(214), None),
-        ("radarr", Some(215), None),
-        ("prowlarr", Some(210), None),
-        ("plex", Some(230), None),
-        ("jellyfin", Some(231), None),
-        
-        // VM binaries
-        ("python3", None, Some(500)),
-        ("hass", None, Some(500)),
-    ];
-    
-    for &(binary_name, container_id, vm_id) in &binary_definitions {
-        if let Ok(binary_info) = check_binary(binary_name.to_string(), container_id, vm_id).await {
-            binaries.push(binary_info);
+async fn set_start_plan(name: String, plan: Option<StartPlan>) -> Result<HashMap<String, StartPlan>, String> {
+    if name.trim().is_empty() {
+        return Err("Plan name must not be empty".to_string());
+    }
+    if let Some(plan) = &plan {
+        if plan.on_failure != "abort" && plan.on_failure != "continue" {
+            return Err(format!("Unknown failure policy '{}', expected 'abort' or 'continue'", plan.on_failure));
         }
+        if plan.step_timeout_secs == 0 {
+            return Err("Step timeout must be at least 1 second".to_string());
+        }
+        start_plan_sequence(plan)?;
     }
-    
-    let mut install_result = InstallResult {
-        success: true,
-        message: "Installation completed.".to_string(),
-        installed_items: Vec::new(),
-        failed_items: Vec::new(),
-        timestamp: Utc::now(),
-    };
 
-    // Attempt to install missing binaries
-    for binary_info in &binaries {
-        if !binary_info.exists {
-            let install_command = match binary_info.name.as_str() {
-                "nginx" => "apt-get install nginx -y",
-                "docker" => "apt-get install docker.io -y",
-                // Add other install commands here
-                _ => "",
-            };
+    Ok(update_app_config(|config| {
+        match plan {
+            Some(plan) => { config.start_plans.insert(name, plan); }
+            None => { config.start_plans.remove(&name); }
+        }
+    })?.start_plans)
+}
 
-            if !install_command.is_empty() {
-                let target = get_ssh_target(binary_info.container_id, binary_info.vm_id);
-                let output = Command::new("ssh")
-                    .args([&target, install_command])
-                    .output()
-                    .map_err(|e| format!("Failed to execute installation for {}: {}", binary_info.name, e))?;
+// Tauri command to start the guests of a plan in dependency order, waiting for each before its dependents
+#[tauri::command]
+async fn start_stack(plan_name: String) -> Result<Vec<StartStep>, String> {
+    let plan = app_config().start_plans.get(&plan_name).cloned()
+        .ok_or_else(|| format!("Start plan '{}' not found", plan_name))?;
+    let sequence = start_plan_sequence(&plan)?;
+    let timeout = std::time::Duration::from_secs(plan.step_timeout_secs);
+    let mut steps: Vec<StartStep> = Vec::new();
+
+    for guest in &sequence {
+        let failed_dependency = guest.depends_on.iter().find(|dep| {
+            steps.iter().any(|step| step.id == **dep && step.result != "started" && step.result != "already_running")
+        });
+        if let Some(dep) = failed_dependency {
+            steps.push(StartStep {
+                id: guest.id,
+                is_vm: guest.is_vm,
+                result: "skipped".to_string(),
+                message: format!("Dependency {} did not start", dep),
+                elapsed_secs: 0,
+            });
+            continue;
+        }
 
-                if output.status.success() {
-                    install_result.installed_items.push(binary_info.name.clone());
-                } else {
-                    install_result.failed_items.push(binary_info.name.clone());
-                    install_result.success = false;
-                }
-            }
+        let step = run_start_step(guest, timeout).await;
+        let failed = step.result == "failed" || step.result == "timeout";
+        steps.push(step);
+        if failed && plan.on_failure == "abort" {
+            break;
         }
     }
 
-    // Finalize message
-    install_result.message = format!("Installed: {:?}, Failed: {:?}", 
-        install_result.installed_items, install_result.failed_items);
+    remove_from_cache("system_overview");
+    Ok(steps)
+}
+
+// Tauri command to start container
+#[tauri::command]
+async fn start_container(container_id: u32) -> Result<String, String> {
+    audited("start_container", format!("ct:{}", container_id), serde_json::json!({ "container_id": container_id }), async move {
+        ensure_unlocked(container_id, false).await?;
+
+        let host = guest_host(container_id).await;
+        let output = run_remote(&host, &["pct", "start", &container_id.to_string()], *POWER_COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
 
-    Ok(install_result)
+        if output.status.success() {
+            Ok(format!("Container {} started successfully", container_id))
+        } else {
+            Err(format!("Failed to start container {}: {}", container_id, String::from_utf8_lossy(&output.stderr)))
+        }
+    }).await
 }
+
+// Tauri command to stop container (hard stop; see shutdown_container for a clean shutdown)
 #[tauri::command]
-async fn get_maintenance_overview() -> Result<MaintenanceOverview, String> {
-    let cache_key = "maintenance_overview";
-    
-    // Check if we have valid cached data
-    if is_cache_valid_with_duration(cache_key, *MAINTENANCE_CACHE_DURATION) {
-        if let Some(cached_data) = get_from_cache(cache_key) {
-            if let Ok(maintenance_overview) = serde_json::from_str::<MaintenanceOverview>(&cached_data) {
-                return Ok(maintenance_overview);
-            }
+async fn stop_container(container_id: u32) -> Result<String, String> {
+    audited("stop_container", format!("ct:{}", container_id), serde_json::json!({ "container_id": container_id }), async move {
+        ensure_unlocked(container_id, false).await?;
+
+        let host = guest_host(container_id).await;
+        let output = run_remote(&host, &["pct", "stop", &container_id.to_string()], *POWER_COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if output.status.success() {
+            Ok(format!("Container {} stopped successfully", container_id))
+        } else {
+            Err(format!("Failed to stop container {}: {}", container_id, String::from_utf8_lossy(&output.stderr)))
         }
-    }
-    
-    let services = get_all_services().await.unwrap_or_default();
-    let binaries = get_all_binaries().await.unwrap_or_default();
-    let configs = get_all_configs().await.unwrap_or_default();
-    let system_health = get_system_health().await.unwrap_or_default();
-    
-    let maintenance_overview = MaintenanceOverview {
-        services,
-        binaries,
-        configs,
-        system_health,
-        last_updated: Utc::now(),
-    };
-    
-    // Store in cache
-    if let Ok(serialized) = serde_json::to_string(&maintenance_overview) {
-        store_in_cache(cache_key, &serialized);
-    }
-    
-    Ok(maintenance_overview)
+    }).await
 }
 
-// Tauri command to check service status
+#[derive(Debug, Serialize, Deserialize)]
+struct ContainerShutdownResult {
+    container_id: u32,
+    method: String,  // "shutdown" when the guest shut down cleanly, "stop" when it was killed after the timeout
+    escalated: bool,
+    message: String,
+}
+
+// Tauri command to shut down a container cleanly, optionally falling back to a hard stop
 #[tauri::command]
-async fn check_service_status(service_name: String, container_id: Option<u32>, vm_id: Option<u32>) -> Result<ServiceInfo, String> {
-    let target = get_ssh_target(container_id, vm_id);
-    
-    let output = Command::new("ssh")
-        .args([&target, "systemctl", "status", &service_name])
-        .output()
-        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
-    
-    let status_output = String::from_utf8_lossy(&output.stdout);
-    let active = status_output.contains("Active: active");
-    let enabled_output = Command::new("ssh")
-        .args([&target, "systemctl", "is-enabled", &service_name])
-        .output()
-        .unwrap_or_else(|_| std::process::Output {
-            status: std::process::ExitStatus::from_raw(1),
-            stdout: Vec::new(),
-            stderr: Vec::new(),
-        });
-    let enabled = String::from_utf8_lossy(&enabled_output.stdout).trim() == "enabled";
-    
-    Ok(ServiceInfo {
-        name: service_name.clone(),
-        status: if active { "Active".to_string() } else { "Inactive".to_string() },
-        enabled,
-        active,
-        description: format!("Service: {}", service_name),
-        container_id,
-        vm_id,
-    })
+async fn shutdown_container(container_id: u32, timeout_secs: Option<u32>, force_after_timeout: Option<bool>) -> Result<ContainerShutdownResult, String> {
+    let timeout_secs = timeout_secs.unwrap_or(60);
+    let force_after_timeout = force_after_timeout.unwrap_or(false);
+    let arguments = serde_json::json!({ "container_id": container_id, "timeout_secs": timeout_secs, "force_after_timeout": force_after_timeout });
+    audited("shutdown_container", format!("ct:{}", container_id), arguments, async move {
+        ensure_unlocked(container_id, false).await?;
+
+        // Leave pct its own timeout to report before ours gives up on the SSH session
+        let host = guest_host(container_id).await;
+        let output = run_remote(&host, &["pct", "shutdown", &container_id.to_string(), "--timeout", &timeout_secs.to_string()],
+                                std::time::Duration::from_secs(timeout_secs as u64 + 30))
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if output.status.success() {
+            return Ok(ContainerShutdownResult {
+                container_id,
+                method: "shutdown".to_string(),
+                escalated: false,
+                message: format!("Container {} shut down cleanly", container_id),
+            });
+        }
+
+        let shutdown_error = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        if !force_after_timeout {
+            return Err(format!("Failed to shut down container {} within {}s: {}", container_id, timeout_secs, shutdown_error));
+        }
+
+        let output = run_remote(&host, &["pct", "stop", &container_id.to_string()], *POWER_COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if output.status.success() {
+            Ok(ContainerShutdownResult {
+                container_id,
+                method: "stop".to_string(),
+                escalated: true,
+                message: format!("Container {} did not shut down within {}s ({}); forced a stop instead", container_id, timeout_secs, shutdown_error),
+            })
+        } else {
+            Err(format!("Failed to stop container {} after shutdown timed out: {}", container_id, String::from_utf8_lossy(&output.stderr)))
+        }
+    }).await
 }
 
-// Tauri command to start/stop/restart service
+// Tauri command to restart container
 #[tauri::command]
-async fn control_service(service_name: String, action: String, container_id: Option<u32>, vm_id: Option<u32>) -> Result<FixResult, String> {
-    let target = get_ssh_target(container_id, vm_id);
-    
-    let output = Command::new("ssh")
-        .args([&target, "systemctl", &action, &service_name])
-        .output()
+async fn restart_container(container_id: u32) -> Result<String, String> {
+    audited("restart_container", format!("ct:{}", container_id), serde_json::json!({ "container_id": container_id }), async move {
+        ensure_unlocked(container_id, false).await?;
+
+        let host = guest_host(container_id).await;
+        let output = run_remote(&host, &["pct", "restart", &container_id.to_string()], *POWER_COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if output.status.success() {
+            Ok(format!("Container {} restarted successfully", container_id))
+        } else {
+            Err(format!("Failed to restart container {}: {}", container_id, String::from_utf8_lossy(&output.stderr)))
+        }
+    }).await
+}
+
+// Tauri command to start VM
+#[tauri::command]
+async fn start_vm(vm_id: u32) -> Result<String, String> {
+    audited("start_vm", format!("vm:{}", vm_id), serde_json::json!({ "vm_id": vm_id }), async move {
+        ensure_unlocked(vm_id, true).await?;
+
+        let host = guest_host(vm_id).await;
+        let output = run_remote(&host, &["qm", "start", &vm_id.to_string()], *POWER_COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if output.status.success() {
+            Ok(format!("VM {} started successfully", vm_id))
+        } else {
+            Err(format!("Failed to start VM {}: {}", vm_id, String::from_utf8_lossy(&output.stderr)))
+        }
+    }).await
+}
+
+// Tauri command to stop VM
+#[tauri::command]
+async fn stop_vm(vm_id: u32) -> Result<String, String> {
+    audited("stop_vm", format!("vm:{}", vm_id), serde_json::json!({ "vm_id": vm_id }), async move {
+        ensure_unlocked(vm_id, true).await?;
+
+        let host = guest_host(vm_id).await;
+        let output = run_remote(&host, &["qm", "stop", &vm_id.to_string()], *POWER_COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if output.status.success() {
+            Ok(format!("VM {} stopped successfully", vm_id))
+        } else {
+            Err(format!("Failed to stop VM {}: {}", vm_id, String::from_utf8_lossy(&output.stderr)))
+        }
+    }).await
+}
+
+// Tauri command to restart VM
+#[tauri::command]
+async fn restart_vm(vm_id: u32) -> Result<String, String> {
+    audited("restart_vm", format!("vm:{}", vm_id), serde_json::json!({ "vm_id": vm_id }), async move {
+        ensure_unlocked(vm_id, true).await?;
+
+        let host = guest_host(vm_id).await;
+        let output = run_remote(&host, &["qm", "restart", &vm_id.to_string()], *POWER_COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if output.status.success() {
+            Ok(format!("VM {} restarted successfully", vm_id))
+        } else {
+            Err(format!("Failed to restart VM {}: {}", vm_id, String::from_utf8_lossy(&output.stderr)))
+        }
+    }).await
+}
+
+// Tauri command to shutdown VM (graceful)
+#[tauri::command]
+async fn shutdown_vm(vm_id: u32) -> Result<String, String> {
+    audited("shutdown_vm", format!("vm:{}", vm_id), serde_json::json!({ "vm_id": vm_id }), async move {
+        ensure_unlocked(vm_id, true).await?;
+
+        let host = guest_host(vm_id).await;
+        let output = run_remote(&host, &["qm", "shutdown", &vm_id.to_string()], *LONG_COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if output.status.success() {
+            Ok(format!("VM {} shutdown initiated successfully", vm_id))
+        } else {
+            Err(format!("Failed to shutdown VM {}: {}", vm_id, String::from_utf8_lossy(&output.stderr)))
+        }
+    }).await
+}
+
+// Tauri command to reset VM (hard reset)
+#[tauri::command]
+async fn reset_vm(vm_id: u32) -> Result<String, String> {
+    audited("reset_vm", format!("vm:{}", vm_id), serde_json::json!({ "vm_id": vm_id }), async move {
+        ensure_unlocked(vm_id, true).await?;
+
+        let host = guest_host(vm_id).await;
+        let output = run_remote(&host, &["qm", "reset", &vm_id.to_string()], *POWER_COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if output.status.success() {
+            Ok(format!("VM {} reset successfully", vm_id))
+        } else {
+            Err(format!("Failed to reset VM {}: {}", vm_id, String::from_utf8_lossy(&output.stderr)))
+        }
+    }).await
+}
+
+// Tauri command to get VM configuration
+#[tauri::command]
+async fn get_vm_config(vm_id: u32) -> Result<String, String> {
+    let output = run_remote(&guest_host(vm_id).await, &["qm", "config", &vm_id.to_string()], *COMMAND_TIMEOUT)
+        .await
         .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
-    
+
     if output.status.success() {
-Ok(FixResult {
-        success: true,
-        message: format!("Service {} {} successfully", service_name, action),
-        actions_taken: vec![format!("{} action for service {}", action, service_name)],
-        timestamp: Utc::now(),
-    })
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
     } else {
-        Err(format!("Failed to {} service {}: {}", action, service_name, String::from_utf8_lossy(&output.stderr)))
+        Err(format!("Failed to get VM {} config: {}", vm_id, String::from_utf8_lossy(&output.stderr)))
     }
 }
 
-// Tauri command to check binary
-#[tauri::command]
-async fn check_binary(binary_name: String, container_id: Option<u32>, vm_id: Option<u32>) -> Result<BinaryInfo, String> {
-    let target = get_ssh_target(container_id, vm_id);
-    
-    // First try 'which' command (searches $PATH)
-    let which_output = Command::new("ssh")
-        .args([&target, "which", &binary_name])
-        .output()
-        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
-    
-    let mut exists = which_output.status.success();
-    let mut path = if exists {
-        String::from_utf8_lossy(&which_output.stdout).trim().to_string()
-    } else {
-        "Not found".to_string()
-    };
-    
-    // If not found in PATH, search common additional locations
-    if !exists {
-        let search_paths = vec![
-            "/opt/*/bin",
-            "/opt/bin",
-            "/usr/local/sbin",
-            "/usr/games",
-            "/snap/bin",
-            "~/.local/bin",
-            "/home/*/bin",
-            "/var/lib/*/bin",
-            "/srv/*/bin",
-            "/app/bin",
-            "/config/bin",
-            "/data/bin",
-            "/media/*/bin",
-            "/mnt/*/bin"
-        ];
-        
-        for search_path in &search_paths {
-            let find_output = Command::new("ssh")
-                .args([&target, "find", search_path, "-name", &binary_name, "-type", "f", "-executable", "2>/dev/null", "|", "head", "-1"])
-                .output();
-                
-            if let Ok(output) = find_output {
-                if output.status.success() {
-                    let found_path_raw = String::from_utf8_lossy(&output.stdout);
-                    let found_path = found_path_raw.trim();
-                    if !found_path.is_empty() {
-                        exists = true;
-                        path = found_path.to_string();
+// A local WebSocket listener bridging one VM's serial console. Each attached client runs in its own
+// task, so closing sends `shutdown` to end those (and their ssh) before the listener is aborted.
+struct ConsoleSession {
+    port: u16,
+    token: String,
+    shutdown: tokio::sync::watch::Sender<bool>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl ConsoleSession {
+    fn close(self) {
+        let _ = self.shutdown.send(true);
+        self.task.abort();
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ConsoleEndpoint {
+    vm_id: u32,
+    url: String,      // e.g. ws://127.0.0.1:40123/console?token=..., for the frontend's xterm
+    protocol: String, // "websocket": terminal bytes as binary messages, keystrokes as binary or text
+}
+
+// What the console WebSocket handler needs to know about its session
+#[derive(Clone)]
+struct ConsoleState {
+    vm_id: u32,
+    host: String, // ssh target of the node that owns the VM
+    token: String,
+    busy: Arc<std::sync::atomic::AtomicBool>, // The serial line is a single terminal, so one client at a time
+    shutdown: tokio::sync::watch::Receiver<bool>, // Changes (or its sender drops) when the session is closed
+}
+
+// Helper function to make an unguessable per-session token from the OS random source
+fn console_token() -> Result<String, String> {
+    use std::io::Read;
+    let mut bytes = [0u8; 24];
+    std::fs::File::open("/dev/urandom")
+        .and_then(|mut random| random.read_exact(&mut bytes))
+        .map_err(|e| format!("Failed to generate a console token: {}", e))?;
+    Ok(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+// Helper function to compare tokens without stopping at the first differing byte
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given.bytes().zip(expected.bytes()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+// Helper function to connect one WebSocket client to `qm terminal` on the VM's node until either side
+// hangs up or the session is closed. The console is long-lived, so it doesn't take one of the ssh command slots.
+async fn bridge_console(vm_id: u32, host: &str, socket: axum::extract::ws::WebSocket, mut shutdown: tokio::sync::watch::Receiver<bool>) -> std::io::Result<()> {
+    use axum::extract::ws::Message;
+    use futures_util::{SinkExt, StreamExt};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let mut child = tokio::process::Command::new("ssh")
+        .args(["-tt", host, "qm", "terminal", &vm_id.to_string()])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .spawn()?;
+    let missing = || std::io::Error::new(std::io::ErrorKind::Other, "ssh has no stdio pipes");
+    let mut stdin = child.stdin.take().ok_or_else(missing)?;
+    let mut stdout = child.stdout.take().ok_or_else(missing)?;
+
+    let (mut sender, mut receiver) = socket.split();
+    let mut output = tokio::spawn(async move {
+        let mut buffer = [0u8; 4096];
+        loop {
+            match stdout.read(&mut buffer).await {
+                Ok(0) | Err(_) => break,
+                Ok(read) => {
+                    if sender.send(Message::Binary(buffer[..read].to_vec())).await.is_err() {
                         break;
                     }
                 }
             }
         }
-    }
-    
-    // Get version if binary exists
-    let version = if exists {
-        let version_output = Command::new("ssh")
-            .args([&target, &path, "--version"])
-            .output();
-            
-        if let Ok(output) = version_output {
-            if output.status.success() {
-                String::from_utf8_lossy(&output.stdout).lines().next().unwrap_or("Unknown").to_string()
-            } else {
-                // Try alternative version commands
-                let alt_commands = vec!["-v", "-V", "version", "--help"];
-                let mut version_found = "Unknown".to_string();
-                
-                for &cmd in &alt_commands {
-                    let alt_output = Command::new("ssh")
-                        .args([&target, &path, cmd])
-                        .output();
-                        
-                    if let Ok(alt_out) = alt_output {
-                        if alt_out.status.success() {
-                            let output_text = String::from_utf8_lossy(&alt_out.stdout);
-                            if let Some(first_line) = output_text.lines().next() {
-                                if !first_line.is_empty() && first_line.len() < 200 { // Reasonable version string
-                                    version_found = first_line.to_string();
-                                    break;
-                                }
-                            }
-                        }
-                    }
-                }
-                version_found
+        let _ = sender.send(Message::Close(None)).await;
+    });
+
+    let input = Box::pin(async move {
+        while let Some(Ok(message)) = receiver.next().await {
+            match message {
+                Message::Binary(data) => stdin.write_all(&data).await?,
+                Message::Text(text) => stdin.write_all(text.as_bytes()).await?,
+                Message::Close(_) => break,
+                Message::Ping(_) | Message::Pong(_) => {} // Answered by axum
             }
-        } else {
-            "Unknown".to_string()
         }
-    } else {
-        "N/A".to_string()
+        Ok(())
+    });
+    // An error means the session's sender is gone, which is a close as well
+    let closed = Box::pin(async move {
+        let _ = shutdown.changed().await;
+    });
+    let result = match futures_util::future::select(input, closed).await {
+        futures_util::future::Either::Left((result, _)) => result,
+        futures_util::future::Either::Right(_) => Ok(()),
     };
-    
-    // Check if executable
-    let executable = if exists {
-        let test_output = Command::new("ssh")
-            .args([&target, "test", "-x", &path])
-            .output();
-            
-        if let Ok(output) = test_output {
-            output.status.success()
+
+    // Killing ssh ends its stdout, after which the output task sends the client a Close frame
+    let _ = child.kill().await;
+    if tokio::time::timeout(std::time::Duration::from_secs(2), &mut output).await.is_err() {
+        output.abort();
+    }
+    result
+}
+
+// Handler for /console: checks the session token and hands the upgraded connection to bridge_console
+async fn console_socket(
+    ws: axum::extract::ws::WebSocketUpgrade,
+    axum::extract::Query(params): axum::extract::Query<HashMap<String, String>>,
+    axum::extract::State(state): axum::extract::State<ConsoleState>,
+) -> axum::response::Response {
+    use axum::response::IntoResponse;
+    use std::sync::atomic::Ordering;
+
+    if !params.get("token").map_or(false, |token| tokens_match(token, &state.token)) {
+        return (axum::http::StatusCode::UNAUTHORIZED, "Invalid console token").into_response();
+    }
+    if state.busy.swap(true, Ordering::SeqCst) {
+        return (axum::http::StatusCode::CONFLICT, "Another client is attached to this console").into_response();
+    }
+
+    ws.on_upgrade(move |socket| async move {
+        if let Err(e) = bridge_console(state.vm_id, &state.host, socket, state.shutdown.clone()).await {
+            tracing::warn!("Console session for VM {} ended: {}", state.vm_id, e);
+        }
+        state.busy.store(false, Ordering::SeqCst);
+    })
+}
+
+// Tauri command to open a VM's serial console (`qm terminal`, run on the VM's node) as a WebSocket on
+// 127.0.0.1 that the frontend's xterm can attach to. The URL carries a random per-session token; an
+// already open console is reused. The VM needs a serial0 port and must be running; ctrl+O detaches.
+#[tauri::command]
+async fn open_guest_console(vm_id: u32) -> Result<ConsoleEndpoint, String> {
+    audited("open_guest_console", format!("vm:{}", vm_id), serde_json::json!({ "vm_id": vm_id }), async move {
+        let endpoint = |port: u16, token: &str| ConsoleEndpoint {
+            vm_id,
+            url: format!("ws://127.0.0.1:{}/console?token={}", port, token),
+            protocol: "websocket".to_string(),
+        };
+        {
+            let sessions = CONSOLE_SESSIONS.lock().map_err(|e| format!("Failed to lock console sessions: {}", e))?;
+            if let Some(session) = sessions.get(&vm_id).filter(|session| !session.task.is_finished()) {
+                return Ok(endpoint(session.port, &session.token));
+            }
+        }
+
+        let config = parse_key_values(&get_vm_config(vm_id).await?);
+        if !config.keys().any(|key| key.starts_with("serial")) {
+            return Err(format!("VM {} has no serial port; add one with `qm set {} --serial0 socket` first", vm_id, vm_id));
+        }
+        let status = transport().guest_status(vm_id, true).await?.status;
+        if status != "Running" {
+            return Err(format!("VM {} is {}; start it before opening its console", vm_id, status));
+        }
+
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .map_err(|e| format!("Failed to open a local port for the console: {}", e))?;
+        let port = listener.local_addr()
+            .map_err(|e| format!("Failed to read the console port: {}", e))?
+            .port();
+
+        let (shutdown, shutdown_signal) = tokio::sync::watch::channel(false);
+        let state = ConsoleState {
+            vm_id,
+            host: guest_host(vm_id).await,
+            token: console_token()?,
+            busy: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            shutdown: shutdown_signal,
+        };
+        let token = state.token.clone();
+        let router = axum::Router::new()
+            .route("/console", axum::routing::get(console_socket))
+            .with_state(state);
+        let task = tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, router).await {
+                tracing::warn!("Console listener for VM {} stopped: {}", vm_id, e);
+            }
+        });
+
+        let result = endpoint(port, &token);
+        let previous = CONSOLE_SESSIONS.lock()
+            .map_err(|e| format!("Failed to lock console sessions: {}", e))?
+            .insert(vm_id, ConsoleSession { port, token, shutdown, task });
+        if let Some(previous) = previous {
+            previous.close();
+        }
+        Ok(result)
+    }).await
+}
+
+// Tauri command to close a VM's console bridge, disconnecting any attached client
+#[tauri::command]
+async fn close_guest_console(vm_id: u32) -> Result<String, String> {
+    let session = CONSOLE_SESSIONS.lock()
+        .map_err(|e| format!("Failed to lock console sessions: {}", e))?
+        .remove(&vm_id);
+    match session {
+        Some(session) => {
+            session.close();
+            Ok(format!("Console for VM {} closed", vm_id))
+        }
+        None => Ok(format!("No console is open for VM {}", vm_id)),
+    }
+}
+
+// One `key: value` line of a guest config; values like net0 are also split into their sub-fields
+#[derive(Debug, Serialize, Deserialize)]
+struct ConfigEntry {
+    raw: String,
+    fields: HashMap<String, String>, // "name=eth0,bridge=vmbr0" -> name, bridge; a leading bare value goes under "value"
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ParsedGuestConfig {
+    id: u32,
+    is_vm: bool,
+    description: String,                                       // The leading "#" comment lines
+    values: HashMap<String, ConfigEntry>,                      // The current config
+    sections: HashMap<String, HashMap<String, ConfigEntry>>, // [PENDING], snapshots and other [name] sections
+}
+
+// Helper function to split a config value into sub-fields when it is a comma-separated option list
+fn parse_config_entry(value: &str) -> ConfigEntry {
+    let mut fields = HashMap::new();
+    if value.contains('=') {
+        for (position, part) in value.split(',').enumerate() {
+            match part.split_once('=') {
+                Some((key, field)) => {
+                    fields.insert(key.trim().to_string(), field.trim().to_string());
+                }
+                None if position == 0 => {
+                    fields.insert("value".to_string(), part.trim().to_string());
+                }
+                None => {}
+            }
+        }
+    }
+    ConfigEntry { raw: value.to_string(), fields }
+}
+
+// Helper function to parse `pct config`/`qm config` output (or a raw config file) into keys and sections
+fn parse_guest_config(config: &str, id: u32, is_vm: bool) -> ParsedGuestConfig {
+    let mut parsed = ParsedGuestConfig {
+        id,
+        is_vm,
+        description: String::new(),
+        values: HashMap::new(),
+        sections: HashMap::new(),
+    };
+    let mut section: Option<String> = None;
+    let mut description = Vec::new();
+
+    for line in config.lines() {
+        let line = line.trim_end();
+        if let Some(name) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            section = Some(name.to_string());
+            parsed.sections.entry(name.to_string()).or_default();
+            continue;
+        }
+        if section.is_none() {
+            if let Some(comment) = line.strip_prefix('#') {
+                description.push(comment.to_string());
+                continue;
+            }
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            let entry = parse_config_entry(value.trim());
+            match &section {
+                Some(name) => {
+                    parsed.sections.entry(name.clone()).or_default().insert(key.trim().to_string(), entry);
+                }
+                None => {
+                    parsed.values.insert(key.trim().to_string(), entry);
+                }
+            }
+        }
+    }
+
+    // `pct config` prints the description as a key instead of comments
+    parsed.description = match parsed.values.remove("description") {
+        Some(entry) => entry.raw,
+        None => description.join("\n"),
+    };
+    parsed
+}
+
+// Tauri command to get a container's config as structured keys instead of raw text
+#[tauri::command]
+async fn get_container_config_parsed(container_id: u32) -> Result<ParsedGuestConfig, String> {
+    let config = pct_config(container_id).await?;
+    Ok(parse_guest_config(&config, container_id, false))
+}
+
+// Tauri command to get a VM's config as structured keys instead of raw text
+#[tauri::command]
+async fn get_vm_config_parsed(vm_id: u32) -> Result<ParsedGuestConfig, String> {
+    let config = get_vm_config(vm_id).await?;
+    Ok(parse_guest_config(&config, vm_id, true))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct VmCpuConfig {
+    vm_id: u32,
+    cores: u32,
+    sockets: u32,
+    cpu: Option<String>,      // CPU type, e.g. "host" or "x86-64-v2-AES"
+    affinity: Option<String>, // Host cpuset the vCPUs are pinned to, e.g. "0-3,8"
+    numa: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct VmCpuAffinityResult {
+    config: VmCpuConfig,
+    warning: Option<String>,
+}
+
+// Helper function to read the CPU settings from `qm config` output, using Proxmox's defaults for missing keys
+fn parse_vm_cpu_config(config: &str, vm_id: u32) -> VmCpuConfig {
+    let value = |key: &str| config_value(config, key).filter(|value| !value.is_empty());
+
+    VmCpuConfig {
+        vm_id,
+        cores: value("cores").and_then(|cores| cores.parse().ok()).unwrap_or(1),
+        sockets: value("sockets").and_then(|sockets| sockets.parse().ok()).unwrap_or(1),
+        cpu: value("cpu"),
+        affinity: value("affinity"),
+        numa: value("numa").as_deref() == Some("1"),
+    }
+}
+
+// Helper function to check a cpuset such as "0-3,8" against the number of host CPUs
+fn validate_cpuset(cpuset: &str, host_cpus: u32) -> Result<(), String> {
+    if cpuset.is_empty() {
+        return Err("CPU set must not be empty".to_string());
+    }
+    for part in cpuset.split(',') {
+        let (first, last) = match part.split_once('-') {
+            Some((first, last)) => (first, last),
+            None => (part, part),
+        };
+        let first: u32 = first.trim().parse().map_err(|_| format!("Invalid CPU range '{}' in '{}'", part, cpuset))?;
+        let last: u32 = last.trim().parse().map_err(|_| format!("Invalid CPU range '{}' in '{}'", part, cpuset))?;
+        if first > last {
+            return Err(format!("CPU range '{}' runs backwards", part));
+        }
+        if last >= host_cpus {
+            return Err(format!("CPU {} does not exist; the host has CPUs 0-{}", last, host_cpus.saturating_sub(1)));
+        }
+    }
+    Ok(())
+}
+
+// Helper function to get the number of CPUs, including offline ones, on the node a guest runs on
+async fn host_cpu_count(guest_id: u32) -> Result<u32, String> {
+    let nproc = run_remote(&guest_host(guest_id).await, &["nproc", "--all"], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    String::from_utf8_lossy(&nproc.stdout).trim().parse()
+        .map_err(|_| format!("Failed to read the host CPU count: {}", String::from_utf8_lossy(&nproc.stderr)))
+}
+
+// Tauri command to get a VM's cores, sockets, CPU type, NUMA and affinity settings
+#[tauri::command]
+async fn get_vm_cpu_config(vm_id: u32) -> Result<VmCpuConfig, String> {
+    let config = get_vm_config(vm_id).await?;
+    Ok(parse_vm_cpu_config(&config, vm_id))
+}
+
+// Tauri command to pin a VM's vCPUs to a set of host CPUs
+#[tauri::command]
+async fn set_vm_cpu_affinity(vm_id: u32, cpuset: String) -> Result<VmCpuAffinityResult, String> {
+    audited("set_vm_cpu_affinity", format!("vm:{}", vm_id), serde_json::json!({ "vm_id": vm_id, "cpuset": cpuset }), async move {
+        let cpuset = cpuset.replace(' ', "");
+        validate_cpuset(&cpuset, host_cpu_count(vm_id).await?)?;
+
+        let output = run_remote(&guest_host(vm_id).await, &["qm", "set", &vm_id.to_string(), "--affinity", &cpuset], *COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if !output.status.success() {
+            return Err(format!("Failed to set CPU affinity for VM {}: {}", vm_id, String::from_utf8_lossy(&output.stderr)));
+        }
+
+        let running = transport().guest_status(vm_id, true).await.map(|status| status.status == "Running").unwrap_or(false);
+        Ok(VmCpuAffinityResult {
+            config: get_vm_cpu_config(vm_id).await?,
+            warning: running.then(|| format!("VM {} is running; the new affinity may not apply until it is restarted", vm_id)),
+        })
+    }).await
+}
+
+// A container's CPU controls. `cpulimit` is a hard cap: the container never gets more than that many
+// CPUs' worth of time, even on an idle host. `cpuunits` is a relative weight: it only matters when
+// containers compete for CPU, and then time is shared in proportion to the weights.
+#[derive(Debug, Serialize, Deserialize)]
+struct ContainerCpuLimits {
+    container_id: u32,
+    cores: Option<u32>, // Number of CPUs visible to the container; None means all host CPUs
+    cpulimit: f64,      // Hard limit in CPUs (fractions allowed); 0 means unlimited
+    cpuunits: u32,      // Scheduler weight; Proxmox's cgroup v2 default is 100
+    host_cpus: u32,
+}
+
+// Highest cpuunits honored on cgroup v2; Proxmox accepts larger values but the kernel weight tops out here
+const MAX_CPU_UNITS: u32 = 10000;
+
+// Helper function to read the CPU limit settings from `pct config` output, using Proxmox's defaults for missing keys
+fn parse_container_cpu_limits(config: &str, container_id: u32, host_cpus: u32) -> ContainerCpuLimits {
+    let value = |key: &str| config_value(config, key).filter(|value| !value.is_empty());
+
+    ContainerCpuLimits {
+        container_id,
+        cores: value("cores").and_then(|cores| cores.parse().ok()),
+        cpulimit: value("cpulimit").and_then(|limit| limit.parse().ok()).unwrap_or(0.0),
+        cpuunits: value("cpuunits").and_then(|units| units.parse().ok()).unwrap_or(100),
+        host_cpus,
+    }
+}
+
+// Helper function to check a container's CPU settings against the host: cores between 1 and the
+// host's CPU count, cpulimit between 0 (unlimited) and the host's CPU count, cpuunits between 1 and MAX_CPU_UNITS
+async fn validate_container_cpu_settings(container_id: u32, cores: Option<u32>, cpulimit: Option<f64>, cpuunits: Option<u32>) -> Result<(), String> {
+    if let Some(units) = cpuunits {
+        if !(1..=MAX_CPU_UNITS).contains(&units) {
+            return Err(format!("CPU units must be between 1 and {}", MAX_CPU_UNITS));
+        }
+    }
+    if cores.is_none() && cpulimit.is_none() {
+        return Ok(());
+    }
+
+    let host_cpus = host_cpu_count(container_id).await?;
+    if let Some(limit) = cpulimit {
+        if !limit.is_finite() || limit < 0.0 || limit > host_cpus as f64 {
+            return Err(format!("CPU limit must be between 0 (unlimited) and {} (the host's CPU count)", host_cpus));
+        }
+    }
+    if let Some(cores) = cores {
+        if cores < 1 || cores > host_cpus {
+            return Err(format!("Cores must be between 1 and {} (the host's CPU count)", host_cpus));
+        }
+    }
+    Ok(())
+}
+
+// Tauri command to get a container's hard CPU limit and relative CPU weight
+#[tauri::command]
+async fn get_container_cpu_limits(container_id: u32) -> Result<ContainerCpuLimits, String> {
+    let config = pct_config(container_id).await?;
+    Ok(parse_container_cpu_limits(&config, container_id, host_cpu_count(container_id).await?))
+}
+
+// Tauri command to set a container's hard CPU limit and/or relative CPU weight. A cpulimit of 0
+// removes the limit; settings left as None are unchanged. Both apply to a running container immediately.
+#[tauri::command]
+async fn set_container_cpu_limits(container_id: u32, cpulimit: Option<f64>, cpuunits: Option<u32>) -> Result<ContainerCpuLimits, String> {
+    let arguments = serde_json::json!({ "container_id": container_id, "cpulimit": cpulimit, "cpuunits": cpuunits });
+    audited("set_container_cpu_limits", format!("ct:{}", container_id), arguments, async move {
+        if cpulimit.is_none() && cpuunits.is_none() {
+            return Err("Nothing to change: give a cpulimit, cpuunits or both".to_string());
+        }
+        validate_container_cpu_settings(container_id, None, cpulimit, cpuunits).await?;
+
+        let id_arg = container_id.to_string();
+        let limit_arg = cpulimit.map(|limit| limit.to_string());
+        let units_arg = cpuunits.map(|units| units.to_string());
+        let mut args = vec!["pct", "set", &id_arg];
+        if let (Some(limit), Some(limit_arg)) = (cpulimit, &limit_arg) {
+            if limit == 0.0 {
+                args.extend(["--delete", "cpulimit"]);
+            } else {
+                args.extend(["--cpulimit", limit_arg.as_str()]);
+            }
+        }
+        if let Some(units) = &units_arg {
+            args.extend(["--cpuunits", units.as_str()]);
+        }
+
+        let output = run_remote(&guest_host(container_id).await, &args, *COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if !output.status.success() {
+            return Err(format!("Failed to set CPU limits for container {}: {}", container_id, String::from_utf8_lossy(&output.stderr)));
+        }
+
+        remove_from_cache(&format!("container_details_{}", container_id));
+        get_container_cpu_limits(container_id).await
+    }).await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ContainerOptionChange {
+    container_id: u32,
+    key: String,
+    before: Option<String>, // None when the option wasn't set
+    after: Option<String>,
+}
+
+// Options set_container_option may change with `pct set`, and the kind of value each takes. Disks,
+// mount points and network devices have their own commands. hookscript is left out on purpose: it
+// runs a snippet as root on the host.
+const CONTAINER_OPTIONS: &[(&str, &str)] = &[
+    ("hostname", "text"),
+    ("description", "text"),
+    ("tags", "text"),
+    ("memory", "integer"),
+    ("swap", "integer"),
+    ("cores", "integer"),
+    ("cpulimit", "number"),
+    ("cpuunits", "integer"),
+    ("onboot", "boolean"),
+    ("protection", "boolean"),
+    ("startup", "text"),
+    ("nameserver", "text"),
+    ("searchdomain", "text"),
+    ("timezone", "text"),
+    ("ostype", "text"),
+    ("console", "boolean"),
+    ("tty", "integer"),
+    ("cmode", "text"),
+    ("features", "text"),
+];
+
+// Helper function to check that a container option is allowed and, when given, that the value fits its kind
+fn validate_container_option(key: &str, value: Option<&str>) -> Result<(), String> {
+    let kind = CONTAINER_OPTIONS.iter()
+        .find(|(name, _)| *name == key)
+        .map(|(_, kind)| *kind)
+        .ok_or_else(|| format!("Option '{}' can't be set here; allowed: {}", key,
+            CONTAINER_OPTIONS.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", ")))?;
+
+    let value = match value {
+        Some(value) => value,
+        None => return Ok(()),
+    };
+    let valid = match kind {
+        "integer" => value.parse::<u64>().is_ok(),
+        "number" => value.parse::<f64>().map_or(false, |number| number.is_finite() && number >= 0.0),
+        "boolean" => value == "0" || value == "1",
+        _ => !value.contains('\n'),
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid value '{}' for {} (expected {})", value, key, kind))
+    }
+}
+
+// Helper function to read one top-level option from `pct config`/`qm config` output, ignoring
+// the [pending] and snapshot sections
+fn config_value(config: &str, key: &str) -> Option<String> {
+    let prefix = format!("{}:", key);
+    config.lines()
+        .take_while(|line| !line.starts_with('['))
+        .find_map(|line| line.strip_prefix(&prefix))
+        .map(|value| value.trim().to_string())
+}
+
+// Helper function to get the raw `pct config` output of a container
+async fn pct_config(container_id: u32) -> Result<String, String> {
+    let output = run_remote(&guest_host(container_id).await, &["pct", "config", &container_id.to_string()], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(format!("Failed to get config of container {}: {}", container_id, String::from_utf8_lossy(&output.stderr)))
+    }
+}
+
+// Tauri command to change one option of a container's definition (/etc/pve/lxc/<id>.conf) through
+// `pct set`, which keeps the cluster filesystem consistent. A value of None removes the option.
+#[tauri::command]
+async fn set_container_option(container_id: u32, key: String, value: Option<String>) -> Result<ContainerOptionChange, String> {
+    let arguments = serde_json::json!({ "container_id": container_id, "key": key, "value": value });
+    audited("set_container_option", format!("ct:{}", container_id), arguments, async move {
+        let value = value.map(|value| value.trim().to_string()).filter(|value| !value.is_empty());
+        validate_container_option(&key, value.as_deref())?;
+        // The generic check only covers the kind of value; the CPU settings also have to fit the host
+        if let Some(value) = value.as_deref() {
+            let out_of_range = || format!("Value '{}' for {} is out of range", value, key);
+            match key.as_str() {
+                "cores" => validate_container_cpu_settings(container_id, Some(value.parse().map_err(|_| out_of_range())?), None, None).await?,
+                "cpulimit" => validate_container_cpu_settings(container_id, None, Some(value.parse().map_err(|_| out_of_range())?), None).await?,
+                "cpuunits" => validate_container_cpu_settings(container_id, None, None, Some(value.parse().map_err(|_| out_of_range())?)).await?,
+                _ => {}
+            }
+        }
+
+        let before = config_value(&pct_config(container_id).await?, &key);
+
+        let id_arg = container_id.to_string();
+        let option_arg = format!("--{}", key);
+        let escaped = value.as_deref().map(shell_escape);
+        let args = match &escaped {
+            Some(escaped) => vec!["pct", "set", &id_arg, &option_arg, escaped],
+            None => vec!["pct", "set", &id_arg, "--delete", &key],
+        };
+        let output = run_remote(&guest_host(container_id).await, &args, *COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+        if !output.status.success() {
+            return Err(format!("Failed to set {} on container {}: {}", key, container_id, String::from_utf8_lossy(&output.stderr)));
+        }
+
+        remove_from_cache(&format!("container_details_{}", container_id));
+        let after = config_value(&pct_config(container_id).await?, &key);
+        Ok(ContainerOptionChange { container_id, key, before, after })
+    }).await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PciDevice {
+    slot: u32,            // N in hostpciN
+    id: String,           // Host PCI address (e.g. "0000:01:00") or "mapping=<name>"
+    name: Option<String>, // Device description from lspci on the host
+    rombar: bool,
+    pcie: bool,
+    conflicts_with: Vec<u32>, // Other VMs on this node assigned the same device
+}
+
+// Helper function to parse hostpciN lines from `qm config` output into (slot, device id, options)
+fn parse_hostpci_lines(config: &str) -> Vec<(u32, String, HashMap<String, String>)> {
+    config.lines()
+        .take_while(|line| !line.starts_with('['))
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let slot = key.strip_prefix("hostpci")?.parse().ok()?;
+            let mut parts = value.trim().split(',');
+            let first = parts.next()?.to_string();
+            let options: HashMap<String, String> = parts
+                .filter_map(|part| part.split_once('='))
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect();
+            // The device may also be given as host=<id> after other options
+            let id = match options.get("host") {
+                Some(host) if first.contains('=') => host.clone(),
+                _ => first,
+            };
+            Some((slot, id, options))
+        })
+        .collect()
+}
+
+// Helper function to reduce a PCI id to lspci's short form, e.g. "0000:01:00.0" -> "01:00.0"
+fn normalize_pci_id(id: &str) -> String {
+    id.strip_prefix("0000:").unwrap_or(id).to_lowercase()
+}
+
+// Helper function to tell whether two hostpci ids refer to the same device; an id without a
+// function number (e.g. "01:00") passes through every function of that device
+fn pci_ids_overlap(a: &str, b: &str) -> bool {
+    if a.starts_with("mapping=") || b.starts_with("mapping=") {
+        return a == b;
+    }
+    let (a, b) = (normalize_pci_id(a), normalize_pci_id(b));
+    a == b || a.starts_with(&format!("{}.", b)) || b.starts_with(&format!("{}.", a))
+}
+
+// Tauri command to list the PCI devices passed through to a VM, flagging devices also assigned to other VMs
+#[tauri::command]
+async fn get_vm_pci_devices(vm_id: u32) -> Result<Vec<PciDevice>, String> {
+    let config = get_vm_config(vm_id).await?;
+    let assigned = parse_hostpci_lines(&config);
+    if assigned.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // hostpci lines of every VM config on this node, stopping at the first snapshot section
+    let others = run_remote("proxmox", &["awk", &shell_escape("/^\\[/{nextfile} /^hostpci[0-9]+:/{print FILENAME \"|\" $0}"),
+                            "/etc/pve/qemu-server/*.conf"], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    let mut other_assignments: Vec<(u32, String)> = Vec::new();
+    for line in String::from_utf8_lossy(&others.stdout).lines() {
+        if let Some((file, entry)) = line.split_once('|') {
+            let other_id = file.rsplit('/').next().and_then(|name| name.strip_suffix(".conf")).and_then(|id| id.parse::<u32>().ok());
+            if let Some(other_id) = other_id.filter(|other_id| *other_id != vm_id) {
+                other_assignments.extend(parse_hostpci_lines(entry).into_iter().map(|(_, id, _)| (other_id, id)));
+            }
+        }
+    }
+
+    // Device names are best effort; the ids alone are still useful without them
+    let lspci = run_remote("proxmox", &["lspci"], *COMMAND_TIMEOUT).await
+        .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+        .unwrap_or_default();
+
+    Ok(assigned.into_iter().map(|(slot, id, options)| {
+        let short_id = normalize_pci_id(&id);
+        let name = lspci.lines()
+            .find(|line| !id.starts_with("mapping=") && line.to_lowercase().starts_with(&short_id))
+            .and_then(|line| line.split_once(' '))
+            .map(|(_, name)| name.trim().to_string());
+        let mut conflicts_with: Vec<u32> = other_assignments.iter()
+            .filter(|(_, other)| pci_ids_overlap(&id, other))
+            .map(|(other_id, _)| *other_id)
+            .collect();
+        conflicts_with.dedup();
+
+        PciDevice {
+            slot,
+            name,
+            rombar: options.get("rombar").map_or(true, |rombar| rombar != "0"),
+            pcie: options.get("pcie").map_or(false, |pcie| pcie == "1"),
+            conflicts_with,
+            id,
+        }
+    }).collect())
+}
+
+// Tauri command to clone VM
+#[tauri::command]
+async fn clone_vm(vm_id: u32, new_vm_id: u32, new_name: String) -> Result<TaskStarted, String> {
+    audited("clone_vm", format!("vm:{}", vm_id), serde_json::json!({ "vm_id": vm_id, "new_vm_id": new_vm_id, "new_name": new_name }), async move {
+        let node = guest_node_name(vm_id).await?;
+        let output = run_remote("proxmox", &["pvesh", "create", &format!("/nodes/{}/qemu/{}/clone", node, vm_id),
+                   "--newid", &new_vm_id.to_string(), "--name", &shell_escape(&new_name)], *LONG_COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if output.status.success() {
+            Ok(TaskStarted {
+                message: format!("VM {} clone to VM {} ({}) started successfully", vm_id, new_vm_id, new_name),
+                upid: extract_upid(&String::from_utf8_lossy(&output.stdout)),
+            })
         } else {
-            false
+            Err(format!("Failed to clone VM {}: {}", vm_id, String::from_utf8_lossy(&output.stderr)))
+        }
+    }).await
+}
+
+// A VM's cloud-init settings. On reads the password is masked; on writes a None field is left
+// unchanged, an empty one is removed, and a masked password keeps the stored one.
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
+struct VmCloudInit {
+    vm_id: u32,                    // Ignored by set_vm_cloudinit
+    has_drive: bool,               // Whether the VM has a cloud-init drive attached; ignored by set_vm_cloudinit
+    ciuser: Option<String>,
+    cipassword: Option<String>,
+    ipconfig0: Option<String>,     // e.g. "ip=192.168.1.50/24,gw=192.168.1.1" or "ip=dhcp"
+    sshkeys: Option<Vec<String>>,  // One public key per entry
+    nameserver: Option<String>,    // Space-separated addresses
+    searchdomain: Option<String>,
+}
+
+const MASKED_SECRET: &str = "********";
+
+// Helper function to decode the %XX escapes Proxmox stores sshkeys with
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| value.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+// Helper function to read the cloud-init keys from `qm config` output, masking the password
+fn parse_vm_cloudinit(config: &str, vm_id: u32) -> VmCloudInit {
+    let value = |key: &str| config_value(config, key).filter(|value| !value.is_empty());
+
+    VmCloudInit {
+        vm_id,
+        has_drive: config.lines()
+            .take_while(|line| !line.starts_with('['))
+            .any(|line| line.contains("cloudinit") && line.split_once(':').map_or(false, |(key, _)| !key.starts_with("ci"))),
+        ciuser: value("ciuser"),
+        cipassword: value("cipassword").map(|_| MASKED_SECRET.to_string()),
+        ipconfig0: value("ipconfig0"),
+        sshkeys: value("sshkeys").map(|keys| {
+            percent_decode(&keys).lines().map(str::trim).filter(|key| !key.is_empty()).map(str::to_string).collect()
+        }),
+        nameserver: value("nameserver"),
+        searchdomain: value("searchdomain"),
+    }
+}
+
+// Helper function to check an ipconfigN value such as "ip=192.168.1.50/24,gw=192.168.1.1"
+fn validate_ipconfig(ipconfig: &str) -> Result<(), String> {
+    for part in ipconfig.split(',') {
+        let (key, value) = part.split_once('=').ok_or_else(|| format!("'{}' in '{}' is not key=value", part, ipconfig))?;
+        match key {
+            "ip" if value == "dhcp" => {}
+            "ip6" if value == "dhcp" || value == "auto" => {}
+            "ip" | "ip6" => validate_cidr(value)?,
+            "gw" | "gw6" => {
+                value.parse::<std::net::IpAddr>().map_err(|_| format!("Gateway '{}' is not a valid IP address", value))?;
+            }
+            _ => return Err(format!("Unknown ipconfig key '{}'; expected ip, gw, ip6 or gw6", key)),
+        }
+    }
+    Ok(())
+}
+
+// Tauri command to get a VM's cloud-init user, network and ssh key settings
+#[tauri::command]
+async fn get_vm_cloudinit(vm_id: u32) -> Result<VmCloudInit, String> {
+    let config = get_vm_config(vm_id).await?;
+    Ok(parse_vm_cloudinit(&config, vm_id))
+}
+
+// Tauri command to change a VM's cloud-init settings and regenerate its cloud-init drive
+#[tauri::command]
+async fn set_vm_cloudinit(vm_id: u32, config: VmCloudInit) -> Result<VmCloudInit, String> {
+    audited("set_vm_cloudinit", format!("vm:{}", vm_id), serde_json::json!({ "vm_id": vm_id, "config": config }), async move {
+        if let Some(user) = config.ciuser.as_deref().filter(|user| !user.is_empty()) {
+            if !user.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c)) || user.starts_with('-') {
+                return Err(format!("Invalid cloud-init user '{}'", user));
+            }
+        }
+        if let Some(ipconfig) = config.ipconfig0.as_deref().filter(|ipconfig| !ipconfig.is_empty()) {
+            validate_ipconfig(ipconfig)?;
+        }
+        if let Some(invalid) = config.nameserver.iter().flat_map(|ns| ns.split_whitespace()).find(|ns| ns.parse::<std::net::IpAddr>().is_err()) {
+            return Err(format!("Nameserver '{}' is not an IP address", invalid));
+        }
+        if let Some(invalid) = config.sshkeys.iter().flatten().find(|key| key.contains('\n') || key.split_whitespace().count() < 2) {
+            return Err(format!("'{}' is not a single-line public key", truncate_text(invalid, 40)));
+        }
+
+        let vm_arg = vm_id.to_string();
+        let mut set_args: Vec<String> = Vec::new();
+        let mut deletions: Vec<&str> = Vec::new();
+        let mut option = |name: &'static str, value: &Option<String>| match value.as_deref() {
+            Some("") => deletions.push(name),
+            Some(value) => set_args.extend([format!("--{}", name), shell_escape(value)]),
+            None => {}
+        };
+        option("ciuser", &config.ciuser);
+        option("ipconfig0", &config.ipconfig0);
+        option("nameserver", &config.nameserver);
+        option("searchdomain", &config.searchdomain);
+
+        // The password is staged in a file only root can read and expanded by the remote shell,
+        // so it never appears in the command line this app logs
+        let host = guest_host(vm_id).await;
+        let password_path = format!("/tmp/cloudinit-password-{}-{}", vm_id, Utc::now().timestamp_millis());
+        let password = config.cipassword.clone().filter(|password| password != MASKED_SECRET);
+        match password.as_deref() {
+            Some("") => deletions.push("cipassword"),
+            Some(password) => {
+                let created = run_remote(&host, &["install", "-m", "600", "/dev/null", &password_path], *COMMAND_TIMEOUT)
+                    .await
+                    .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+                if !created.status.success() {
+                    return Err(format!("Failed to stage the cloud-init password: {}", String::from_utf8_lossy(&created.stderr)));
+                }
+                write_node_file(&host, &password_path, password.to_string()).await?;
+                set_args.extend(["--cipassword".to_string(), format!("\"$(cat {})\"", password_path)]);
+            }
+            None => {}
+        }
+
+        // qm only takes ssh keys from a file, so stage them on the VM's node for the duration of the call
+        let keys_path = format!("/tmp/cloudinit-sshkeys-{}-{}", vm_id, Utc::now().timestamp_millis());
+        match &config.sshkeys {
+            Some(keys) if keys.is_empty() => deletions.push("sshkeys"),
+            Some(keys) => {
+                write_node_file(&host, &keys_path, keys.join("\n") + "\n").await?;
+                set_args.extend(["--sshkeys".to_string(), keys_path.clone()]);
+            }
+            None => {}
+        }
+        let deletions = deletions.join(",");
+        if !deletions.is_empty() {
+            set_args.extend(["--delete".to_string(), deletions]);
+        }
+        if set_args.is_empty() {
+            return Err("Nothing to change".to_string());
+        }
+
+        let mut args = vec!["qm", "set", vm_arg.as_str()];
+        args.extend(set_args.iter().map(String::as_str));
+        let output = run_remote(&host, &args, *COMMAND_TIMEOUT).await;
+        if config.sshkeys.as_ref().map_or(false, |keys| !keys.is_empty()) {
+            let _ = run_remote(&host, &["rm", "-f", &keys_path], *COMMAND_TIMEOUT).await;
+        }
+        if password.as_deref().map_or(false, |password| !password.is_empty()) {
+            let _ = run_remote(&host, &["rm", "-f", &password_path], *COMMAND_TIMEOUT).await;
+        }
+        let output = output.map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+        if !output.status.success() {
+            return Err(format!("Failed to set cloud-init options for VM {}: {}", vm_id, String::from_utf8_lossy(&output.stderr)));
+        }
+
+        // Without a regenerated drive the guest keeps seeing the old settings until its next start
+        let update = run_remote(&host, &["qm", "cloudinit", "update", &vm_arg], *COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+        if !update.status.success() {
+            return Err(format!("Saved cloud-init options for VM {} but failed to regenerate its drive: {}", vm_id, String::from_utf8_lossy(&update.stderr)));
+        }
+
+        get_vm_cloudinit(vm_id).await
+    }).await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ContainerCloneResult {
+    container_id: u32,
+    hostname: String,
+    full: bool,
+    message: String,
+}
+
+// Tauri command to clone a container (linked clone unless `full` is set)
+#[tauri::command]
+async fn clone_container(container_id: u32, new_container_id: u32, new_hostname: String, full: bool) -> Result<ContainerCloneResult, String> {
+    audited("clone_container", format!("ct:{}", container_id), serde_json::json!({ "container_id": container_id, "new_container_id": new_container_id, "new_hostname": new_hostname, "full": full }), async move {
+        // Container and VM ids share one namespace in Proxmox
+        let existing_containers = get_existing_containers().await?;
+        if existing_containers.contains(&new_container_id) || get_existing_vms().await?.contains(&new_container_id) {
+            return Err(format!("ID {} is already in use", new_container_id));
+        }
+        if !existing_containers.contains(&container_id) {
+            return Err(format!("Container {} does not exist", container_id));
+        }
+
+        let container_arg = container_id.to_string();
+        let new_container_arg = new_container_id.to_string();
+        let hostname_arg = shell_escape(&new_hostname);
+        let mut args = vec!["pct", "clone", &container_arg, &new_container_arg, "--hostname", &hostname_arg];
+        if full {
+            args.extend(["--full", "1"]);
+        }
+
+        let output = run_remote(&guest_host(container_id).await, &args, *LONG_COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if output.status.success() {
+            remove_from_cache("system_overview");
+            Ok(ContainerCloneResult {
+                container_id: new_container_id,
+                hostname: new_hostname.clone(),
+                full,
+                message: format!("Container {} cloned to container {} ({})", container_id, new_container_id, new_hostname),
+            })
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let lowered = stderr.to_lowercase();
+            if !full && (lowered.contains("linked clone") || lowered.contains("clone feature")) {
+                Err(format!(
+                    "Container {} can't be linked-cloned: linked clones share the source's disks and need a template on snapshot-capable storage (e.g. ZFS, LVM-thin, Ceph). Use a full clone to copy the disks instead. ({})",
+                    container_id, stderr.trim()
+                ))
+            } else {
+                Err(format!("Failed to clone container {}: {}", container_id, stderr))
+            }
+        }
+    }).await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TemplateInfo {
+    id: u32,
+    name: String,
+    is_vm: bool,
+    node: String,
+}
+
+// Helper function to pick the templates out of the cluster resources
+fn templates_from_resources(resources: &[serde_json::Value]) -> Vec<TemplateInfo> {
+    let mut templates: Vec<TemplateInfo> = resources
+        .iter()
+        .filter(|resource| json_u64(&resource["template"]) == Some(1))
+        .filter_map(|resource| {
+            let is_vm = match resource["type"].as_str()? {
+                "qemu" => true,
+                "lxc" => false,
+                _ => return None,
+            };
+            Some(TemplateInfo {
+                id: json_u64(&resource["vmid"])? as u32,
+                name: resource["name"].as_str().unwrap_or_default().to_string(),
+                is_vm,
+                node: resource["node"].as_str().unwrap_or_default().to_string(),
+            })
+        })
+        .collect();
+
+    templates.sort_by_key(|template| template.id);
+    templates
+}
+
+// Tauri command to list the container and VM templates
+#[tauri::command]
+async fn list_templates() -> Result<Vec<TemplateInfo>, String> {
+    // pct/qm list don't show the template flag, but the cluster resources do
+    Ok(templates_from_resources(&transport().cluster_resources().await?))
+}
+
+// An LXC appliance template, either offered by `pveam available` or already on a storage
+#[derive(Debug, Serialize, Deserialize)]
+struct ApplianceTemplate {
+    section: String,       // "system", "turnkeylinux", ... or "local" for templates only found on the storage
+    package: String,       // Template file name, e.g. "debian-12-standard_12.7-1_amd64.tar.zst"
+    volid: Option<String>, // Set once the template is downloaded to the storage
+    size: Option<u64>,     // Bytes, known only for downloaded templates
+}
+
+// Helper function to parse `pveam available` and `pvesm list <storage> --content vztmpl` output into one list
+fn parse_appliance_templates(available: &str, downloaded: &str) -> Vec<ApplianceTemplate> {
+    let mut templates: Vec<ApplianceTemplate> = available.lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            Some(ApplianceTemplate {
+                section: parts.next()?.to_string(),
+                package: parts.next()?.to_string(),
+                volid: None,
+                size: None,
+            })
+        })
+        .collect();
+
+    // pvesm list: Volid Format Type Size
+    for line in downloaded.lines().skip(1) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 4 {
+            continue;
+        }
+        let volid = parts[0].to_string();
+        let package = volid.rsplit('/').next().unwrap_or(&volid).to_string();
+        let size = parts[3].parse().ok();
+        match templates.iter_mut().find(|template| template.package == package) {
+            Some(template) => {
+                template.volid = Some(volid);
+                template.size = size;
+            }
+            None => templates.push(ApplianceTemplate { section: "local".to_string(), package, volid: Some(volid), size }),
+        }
+    }
+
+    templates
+}
+
+// Tauri command to list the container templates that can be downloaded, and those already on a storage
+#[tauri::command]
+async fn list_available_templates(storage: String) -> Result<Vec<ApplianceTemplate>, String> {
+    let available = run_remote("proxmox", &["pveam", "available"], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    if !available.status.success() {
+        return Err(format!("Failed to list available templates: {}", String::from_utf8_lossy(&available.stderr)));
+    }
+
+    let downloaded = run_remote("proxmox", &["pvesm", "list", &shell_escape(&storage), "--content", "vztmpl"], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    if !downloaded.status.success() {
+        return Err(format!("Failed to list templates on storage {}: {}", storage, String::from_utf8_lossy(&downloaded.stderr)));
+    }
+
+    Ok(parse_appliance_templates(&String::from_utf8_lossy(&available.stdout), &String::from_utf8_lossy(&downloaded.stdout)))
+}
+
+// Tauri command to download a container template to a storage as a background task
+#[tauri::command]
+async fn download_template(storage: String, template: String) -> Result<TaskStarted, String> {
+    audited("download_template", "host".to_string(), serde_json::json!({ "storage": storage, "template": template }), async move {
+        // The API call returns a UPID right away, unlike `pveam download` which blocks until done
+        let node = get_node_name().await?;
+        let output = run_remote("proxmox", &["pvesh", "create", &format!("/nodes/{}/aplinfo", node),
+                                "--storage", &shell_escape(&storage), "--template", &shell_escape(&template)], *COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if output.status.success() {
+            Ok(TaskStarted {
+                message: format!("Downloading template {} to {}", template, storage),
+                upid: extract_upid(&String::from_utf8_lossy(&output.stdout)),
+            })
+        } else {
+            Err(format!("Failed to start download of template {}: {}", template, String::from_utf8_lossy(&output.stderr)))
+        }
+    }).await
+}
+
+// Helper function to flag a guest as a template in the cached overview
+fn mark_cached_template(id: u32, is_vm: bool) {
+    let cache_key = "system_overview";
+    if let Some(cached_data) = get_from_cache(cache_key) {
+        if let Ok(mut system_overview) = serde_json::from_str::<SystemOverview>(&cached_data) {
+            if is_vm {
+                system_overview.vms.iter_mut().filter(|vm| vm.id == id).for_each(|vm| vm.template = true);
+            } else {
+                system_overview.containers.iter_mut().filter(|ct| ct.id == id).for_each(|ct| ct.template = true);
+            }
+            if let Ok(serialized) = serde_json::to_string(&system_overview) {
+                store_in_cache(cache_key, &serialized);
+            }
+        }
+    }
+}
+
+// Tauri command to convert a stopped container or VM into a template
+#[tauri::command]
+async fn convert_to_template(id: u32, is_vm: bool) -> Result<String, String> {
+    let target = format!("{}:{}", if is_vm { "vm" } else { "ct" }, id);
+    audited("convert_to_template", target, serde_json::json!({ "id": id, "is_vm": is_vm }), async move {
+        let kind = if is_vm { "VM" } else { "Container" };
+        let status = transport().guest_status(id, is_vm).await?.status;
+        if status != "Stopped" {
+            return Err(format!("{} {} is {}; stop it before converting it to a template", kind, id, status.to_lowercase()));
+        }
+
+        let output = run_remote(&guest_host(id).await, &[if is_vm { "qm" } else { "pct" }, "template", &id.to_string()], *LONG_COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if output.status.success() {
+            mark_cached_template(id, is_vm);
+            Ok(format!("{} {} converted to a template", kind, id))
+        } else {
+            Err(format!("Failed to convert {} {} to a template: {}", kind.to_lowercase(), id, String::from_utf8_lossy(&output.stderr)))
+        }
+    }).await
+}
+
+// Tauri command to clear a stale lock left on a container or VM by a crashed task
+#[tauri::command]
+async fn unlock_guest(id: u32, is_vm: bool) -> Result<String, String> {
+    let target = format!("{}:{}", if is_vm { "vm" } else { "ct" }, id);
+    audited("unlock_guest", target, serde_json::json!({ "id": id, "is_vm": is_vm }), async move {
+        let kind = if is_vm { "VM" } else { "Container" };
+        let output = run_remote(&guest_host(id).await, &[if is_vm { "qm" } else { "pct" }, "unlock", &id.to_string()], *COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if output.status.success() {
+            remove_from_cache("system_overview");
+            Ok(format!("{} {} unlocked", kind, id))
+        } else {
+            Err(format!("Failed to unlock {} {}: {}", kind.to_lowercase(), id, String::from_utf8_lossy(&output.stderr)))
+        }
+    }).await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AutostartSettings {
+    id: u32,
+    is_vm: bool,
+    onboot: bool,
+    startup: Option<String>,    // Raw startup setting, e.g. "order=2,up=30"
+    startup_order: Option<u32>,
+}
+
+// Helper function to read onboot and startup from `pct config`/`qm config` output
+fn parse_autostart(config: &str, id: u32, is_vm: bool) -> AutostartSettings {
+    let value = |key: &str| config_value(config, key);
+    let startup = value("startup");
+    let startup_order = startup.as_deref().and_then(|startup| {
+        startup.split(',').find_map(|part| part.strip_prefix("order=")).and_then(|order| order.parse().ok())
+    });
+
+    AutostartSettings {
+        id,
+        is_vm,
+        onboot: value("onboot").as_deref() == Some("1"),
+        startup,
+        startup_order,
+    }
+}
+
+// Tauri command to get whether a container or VM starts with the host, and in which order
+#[tauri::command]
+async fn get_autostart(id: u32, is_vm: bool) -> Result<AutostartSettings, String> {
+    let output = run_remote(&guest_host(id).await, &[if is_vm { "qm" } else { "pct" }, "config", &id.to_string()], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!("Failed to read config of {} {}: {}", if is_vm { "VM" } else { "container" }, id, String::from_utf8_lossy(&output.stderr)));
+    }
+
+    Ok(parse_autostart(&String::from_utf8_lossy(&output.stdout), id, is_vm))
+}
+
+// Tauri command to turn start-at-boot on or off for a container or VM, optionally setting its start order
+#[tauri::command]
+async fn set_autostart(id: u32, is_vm: bool, enabled: bool, startup_order: Option<i64>) -> Result<AutostartSettings, String> {
+    let target = format!("{}:{}", if is_vm { "vm" } else { "ct" }, id);
+    let arguments = serde_json::json!({ "id": id, "is_vm": is_vm, "enabled": enabled, "startup_order": startup_order });
+    audited("set_autostart", target, arguments, async move {
+        if startup_order.map_or(false, |order| order < 0) {
+            return Err("Startup order must not be negative".to_string());
+        }
+
+        let id_arg = id.to_string();
+        let onboot = if enabled { "1" } else { "0" };
+        let mut args = vec![if is_vm { "qm" } else { "pct" }, "set", &id_arg, "--onboot", onboot];
+
+        // Keep any up=/down= delays already configured; only the order changes
+        let startup = match startup_order {
+            Some(order) => {
+                let current = get_autostart(id, is_vm).await?;
+                let mut parts: Vec<String> = current.startup.as_deref().unwrap_or("")
+                    .split(',')
+                    .filter(|part| !part.is_empty() && !part.starts_with("order="))
+                    .map(str::to_string)
+                    .collect();
+                parts.insert(0, format!("order={}", order));
+                Some(parts.join(","))
+            }
+            None => None,
+        };
+        if let Some(startup) = &startup {
+            args.extend(["--startup", startup.as_str()]);
+        }
+
+        let output = run_remote(&guest_host(id).await, &args, *COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if !output.status.success() {
+            return Err(format!("Failed to set autostart for {} {}: {}", if is_vm { "VM" } else { "container" }, id, String::from_utf8_lossy(&output.stderr)));
+        }
+
+        get_autostart(id, is_vm).await
+    }).await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GuestRenameResult {
+    id: u32,
+    is_vm: bool,
+    old_name: String,
+    new_name: String,
+}
+
+// Helper function to check a guest name against the DNS hostname rules Proxmox enforces
+fn validate_guest_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > 253 {
+        return Err(format!("Name '{}' must be between 1 and 253 characters", name));
+    }
+    for label in name.split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err(format!("Name '{}' has an empty label or a label longer than 63 characters", name));
+        }
+        if !label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(format!("Name '{}' may only contain letters, digits, hyphens and dots", name));
+        }
+        if label.starts_with('-') || label.ends_with('-') {
+            return Err(format!("Name '{}' must not start or end a label with a hyphen", name));
+        }
+    }
+    Ok(())
+}
+
+// Helper function to rename a container (hostname) or VM (name) and report the previous name
+async fn rename_guest(id: u32, is_vm: bool, new_name: String) -> Result<GuestRenameResult, String> {
+    validate_guest_name(&new_name)?;
+
+    let kind = if is_vm { "VM" } else { "container" };
+    let id_arg = id.to_string();
+    let tool = if is_vm { "qm" } else { "pct" };
+    let host = guest_host(id).await;
+
+    let config = if is_vm { get_vm_config(id).await? } else { pct_config(id).await? };
+    let old_name = config_value(&config, if is_vm { "name" } else { "hostname" }).unwrap_or_default();
+
+    let output = run_remote(&host, &[tool, "set", &id_arg, if is_vm { "--name" } else { "--hostname" }, &new_name], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!("Failed to rename {} {}: {}", kind, id, String::from_utf8_lossy(&output.stderr)));
+    }
+
+    remove_from_cache("system_overview");
+    if !is_vm {
+        remove_from_cache(&format!("container_details_{}", id));
+    }
+    Ok(GuestRenameResult { id, is_vm, old_name, new_name })
+}
+
+// Tauri command to change a container's hostname
+#[tauri::command]
+async fn rename_container(container_id: u32, new_hostname: String) -> Result<GuestRenameResult, String> {
+    audited("rename_container", format!("ct:{}", container_id), serde_json::json!({ "container_id": container_id, "new_hostname": new_hostname }), async move {
+        rename_guest(container_id, false, new_hostname).await
+    }).await
+}
+
+// Tauri command to change a VM's name
+#[tauri::command]
+async fn rename_vm(vm_id: u32, new_name: String) -> Result<GuestRenameResult, String> {
+    audited("rename_vm", format!("vm:{}", vm_id), serde_json::json!({ "vm_id": vm_id, "new_name": new_name }), async move {
+        rename_guest(vm_id, true, new_name).await
+    }).await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct MigrationResult {
+    message: String,
+    upid: Option<String>,
+    source_node: String,
+    target_node: String,
+    online: bool, // Live migration for VMs, restart migration for containers
+    bwlimit_kbps: Option<u32>,
+}
+
+// Helper function to start a migration task for a container or VM through the API of the node that owns it
+async fn migrate_guest(id: u32, is_vm: bool, target_node: String, bwlimit_kbps: Option<u32>, online: bool, with_local_disks: bool) -> Result<MigrationResult, String> {
+    let kind = if is_vm { "VM" } else { "Container" };
+    if bwlimit_kbps == Some(0) {
+        return Err("Bandwidth limit must be greater than 0 KiB/s".to_string());
+    }
+
+    let source_node = match guest_node(id).await {
+        Some(node) => node,
+        None => get_node_name().await?,
+    };
+    if source_node == target_node {
+        return Err(format!("{} {} is already on node {}", kind, id, target_node));
+    }
+    if online {
+        let status = transport().guest_status(id, is_vm).await?;
+        if status.status != "Running" {
+            return Err(format!("{} {} is not running, so it can't be migrated online; migrate it offline instead", kind, id));
+        }
+    }
+
+    let path = format!("/nodes/{}/{}/{}/migrate", source_node, if is_vm { "qemu" } else { "lxc" }, id);
+    let target_arg = shell_escape(&target_node);
+    let bwlimit_arg = bwlimit_kbps.map(|limit| limit.to_string());
+    let mut args = vec!["pvesh", "create", &path, "--target", &target_arg];
+    if let Some(limit) = &bwlimit_arg {
+        args.extend(["--bwlimit", limit.as_str()]);
+    }
+    if online {
+        // Containers can't live-migrate; a restart migration stops, moves and starts them again
+        args.extend([if is_vm { "--online" } else { "--restart" }, "1"]);
+    }
+    if with_local_disks {
+        args.extend(["--with-local-disks", "1"]);
+    }
+
+    let output = run_remote(&node_ssh_target(&source_node).await, &args, *LONG_COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+    if output.status.success() {
+        remove_from_cache("guest_nodes");
+        Ok(MigrationResult {
+            message: format!("{} {} migration from {} to {} started{}", kind, id, source_node, target_node, if online { " (online)" } else { "" }),
+            upid: extract_upid(&String::from_utf8_lossy(&output.stdout)),
+            source_node,
+            target_node,
+            online,
+            bwlimit_kbps,
+        })
+    } else {
+        Err(format!("Failed to migrate {} {}: {}", kind.to_lowercase(), id, String::from_utf8_lossy(&output.stderr)))
+    }
+}
+
+// Tauri command to migrate VM to another node (if in cluster), optionally live and with a bandwidth limit
+#[tauri::command]
+async fn migrate_vm(vm_id: u32, target_node: String, bwlimit_kbps: Option<u32>, online: Option<bool>, with_local_disks: Option<bool>) -> Result<MigrationResult, String> {
+    let online = online.unwrap_or(false);
+    let with_local_disks = with_local_disks.unwrap_or(false);
+    let arguments = serde_json::json!({ "vm_id": vm_id, "target_node": target_node, "bwlimit_kbps": bwlimit_kbps, "online": online, "with_local_disks": with_local_disks });
+    audited("migrate_vm", format!("vm:{}", vm_id), arguments, async move {
+        migrate_guest(vm_id, true, target_node, bwlimit_kbps, online, with_local_disks).await
+    }).await
+}
+
+// Tauri command to migrate a container to another node; `restart` moves a running container by stopping and restarting it
+#[tauri::command]
+async fn migrate_container(container_id: u32, target_node: String, bwlimit_kbps: Option<u32>, restart: Option<bool>) -> Result<MigrationResult, String> {
+    let restart = restart.unwrap_or(false);
+    let arguments = serde_json::json!({ "container_id": container_id, "target_node": target_node, "bwlimit_kbps": bwlimit_kbps, "restart": restart });
+    audited("migrate_container", format!("ct:{}", container_id), arguments, async move {
+        migrate_guest(container_id, false, target_node, bwlimit_kbps, restart, false).await
+    }).await
+}
+
+// Proxmox task (UPID) tracking for long-running background operations
+#[derive(Debug, Serialize, Deserialize)]
+struct TaskStarted {
+    message: String,
+    upid: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TaskStatus {
+    upid: String,
+    node: String,
+    task_type: String,
+    status: String,             // "running" or "stopped"
+    exitstatus: Option<String>, // "OK" or the error message once stopped
+    progress: Option<f64>,      // Percent complete, when the task log reports it
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct NodeTask {
+    upid: String,
+    task_type: String,
+    id: String,             // Guest id or other object the task acted on, empty for node-wide tasks
+    user: String,
+    status: String,         // "running" while active, then "OK" or the error message
+    starttime: String,
+    endtime: Option<String>,
+}
+
+// Helper function to find a UPID (UPID:node:pid:pstart:starttime:type:id:user:) in command output
+fn extract_upid(output: &str) -> Option<String> {
+    output.split_whitespace()
+        .map(|token| token.trim_matches('"'))
+        .find(|token| token.starts_with("UPID:"))
+        .map(|upid| upid.to_string())
+}
+
+// Helper function to get the node a task ran on from its UPID
+fn upid_node(upid: &str) -> Result<String, String> {
+    let parts: Vec<&str> = upid.split(':').collect();
+    if parts.len() < 8 || parts[0] != "UPID" || parts[1].is_empty() {
+        return Err(format!("Invalid UPID: {}", upid));
+    }
+    Ok(parts[1].to_string())
+}
+
+// Helper function to get the local Proxmox node name
+async fn get_node_name() -> Result<String, String> {
+    let cache_key = "node_name";
+
+    if is_cache_valid_with_duration(cache_key, *HOST_CACHE_DURATION) {
+        if let Some(node) = get_from_cache(cache_key) {
+            return Ok(node);
+        }
+    }
+
+    let output = run_remote("proxmox", &["hostname"], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!("Failed to get node name: {}", String::from_utf8_lossy(&output.stderr)));
+    }
+
+    let node = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    store_in_cache(cache_key, &node);
+    Ok(node)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ClusterNode {
+    name: String,
+    status: String,     // "online" or "offline"
+    guest_count: u32,
+    running_guests: u32,
+    ssh_target: String,
+}
+
+// Helper function to get the ssh target for a cluster node
+async fn node_ssh_target(node: &str) -> String {
+    if let Some(target) = app_config().cluster.node_targets.get(node) {
+        return target.clone();
+    }
+    match get_node_name().await {
+        Ok(local) if local != node => node.to_string(),
+        _ => "proxmox".to_string(),
+    }
+}
+
+// Helper function to get the name of the node that owns a guest, if the cluster knows it
+async fn guest_node(id: u32) -> Option<String> {
+    let cache_key = "guest_nodes";
+    let mut nodes: HashMap<u32, String> = if is_cache_valid_with_duration(cache_key, 60) {
+        get_from_cache(cache_key).and_then(|cached| serde_json::from_str(&cached).ok()).unwrap_or_default()
+    } else {
+        HashMap::new()
+    };
+
+    if !nodes.contains_key(&id) {
+        if let Ok(resources) = transport().cluster_resources().await {
+            nodes = resources.iter()
+                .filter(|resource| matches!(resource["type"].as_str(), Some("lxc") | Some("qemu")))
+                .filter_map(|guest| Some((json_u64(&guest["vmid"])? as u32, guest["node"].as_str()?.to_string())))
+                .collect();
+            if let Ok(serialized) = serde_json::to_string(&nodes) {
+                store_in_cache(cache_key, &serialized);
+            }
+        }
+    }
+
+    nodes.remove(&id)
+}
+
+// Helper function to get the node that owns a guest for /nodes/<node>/... API paths, assuming the
+// local node when the cluster doesn't know the guest
+async fn guest_node_name(id: u32) -> Result<String, String> {
+    match guest_node(id).await {
+        Some(node) => Ok(node),
+        None => get_node_name().await,
+    }
+}
+
+// Helper function to get the ssh target of the node that owns a guest, so pct/qm run where the guest lives.
+// Falls back to "proxmox" when the cluster can't be queried, which is right for single-node setups.
+async fn guest_host(id: u32) -> String {
+    match guest_node(id).await {
+        Some(node) => node_ssh_target(&node).await,
+        None => "proxmox".to_string(),
+    }
+}
+
+// Tauri command to list the cluster's nodes with their status and guest counts
+#[tauri::command]
+async fn list_cluster_nodes() -> Result<Vec<ClusterNode>, String> {
+    let resources = transport().cluster_resources().await?;
+    let mut nodes = Vec::new();
+
+    for node in resources.iter().filter(|resource| resource["type"] == "node") {
+        let name = node["node"].as_str().unwrap_or_default().to_string();
+        let guests = resources.iter()
+            .filter(|resource| matches!(resource["type"].as_str(), Some("lxc") | Some("qemu")) && resource["node"] == name.as_str());
+        let (guest_count, running_guests) = guests.fold((0, 0), |(total, running), guest| {
+            (total + 1, running + u32::from(guest["status"] == "running"))
+        });
+
+        nodes.push(ClusterNode {
+            status: node["status"].as_str().unwrap_or("unknown").to_string(),
+            guest_count,
+            running_guests,
+            ssh_target: node_ssh_target(&name).await,
+            name,
+        });
+    }
+
+    nodes.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(nodes)
+}
+
+// Helper function to pull the latest "NN%" figure out of task log lines
+fn parse_task_progress(lines: &[String]) -> Option<f64> {
+    lines.iter().rev().find_map(|line| {
+        let percent_pos = line.rfind('%')?;
+        let number: String = line[..percent_pos]
+            .chars()
+            .rev()
+            .take_while(|c| c.is_ascii_digit() || *c == '.')
+            .collect::<Vec<char>>()
+            .into_iter()
+            .rev()
+            .collect();
+        number.parse::<f64>().ok().filter(|p| (0.0..=100.0).contains(p))
+    })
+}
+
+// Tauri command to get the log lines of a Proxmox task
+#[tauri::command]
+async fn get_task_log(upid: String) -> Result<Vec<String>, String> {
+    let node = upid_node(&upid)?;
+    let output = run_remote("proxmox", &["pvesh", "get", &shell_escape(&format!("/nodes/{}/tasks/{}/log", node, upid)),
+               "--limit", "1000", "--output-format", "json"], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!("Failed to get task log: {}", String::from_utf8_lossy(&output.stderr)));
+    }
+
+    let entries: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse task log: {}", e))?;
+
+    Ok(entries.iter()
+        .filter_map(|entry| entry["t"].as_str().map(|line| line.to_string()))
+        .collect())
+}
+
+// Tauri command to get the status and progress of a Proxmox task
+#[tauri::command]
+async fn get_task_status(upid: String) -> Result<TaskStatus, String> {
+    let node = upid_node(&upid)?;
+    let output = run_remote("proxmox", &["pvesh", "get", &shell_escape(&format!("/nodes/{}/tasks/{}/status", node, upid)), "--output-format", "json"], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!("Failed to get task status: {}", String::from_utf8_lossy(&output.stderr)));
+    }
+
+    let task: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse task status: {}", e))?;
+
+    let status = task["status"].as_str().unwrap_or("unknown").to_string();
+    let progress = if status == "stopped" {
+        Some(100.0)
+    } else {
+        get_task_log(upid.clone()).await.ok().and_then(|lines| parse_task_progress(&lines))
+    };
+
+    Ok(TaskStatus {
+        node,
+        task_type: task["type"].as_str().unwrap_or("unknown").to_string(),
+        exitstatus: task["exitstatus"].as_str().map(|s| s.to_string()),
+        status,
+        progress,
+        upid,
+    })
+}
+
+// Helper function to format a task's epoch time the same way as file timestamps
+fn format_task_time(value: &serde_json::Value) -> Option<String> {
+    value.as_i64()
+        .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+// Tauri command to get the recent task history of the node, newest first
+#[tauri::command]
+async fn get_node_tasks(limit: Option<u32>) -> Result<Vec<NodeTask>, String> {
+    let node = get_node_name().await?;
+    let limit = limit.unwrap_or(50).to_string();
+    let output = run_remote("proxmox", &["pvesh", "get", &shell_escape(&format!("/nodes/{}/tasks", node)),
+               "--limit", &limit, "--output-format", "json"], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!("Failed to get node tasks: {}", String::from_utf8_lossy(&output.stderr)));
+    }
+
+    let mut entries: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse node tasks: {}", e))?;
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry["starttime"].as_i64().unwrap_or(0)));
+
+    Ok(entries.iter()
+        .map(|entry| NodeTask {
+            upid: entry["upid"].as_str().unwrap_or("").to_string(),
+            task_type: entry["type"].as_str().unwrap_or("unknown").to_string(),
+            id: entry["id"].as_str().unwrap_or("").to_string(),
+            user: entry["user"].as_str().unwrap_or("").to_string(),
+            status: entry["status"].as_str().unwrap_or("running").to_string(),
+            starttime: format_task_time(&entry["starttime"]).unwrap_or("Unknown".to_string()),
+            endtime: format_task_time(&entry["endtime"]),
+        })
+        .collect())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GuestSnapshot {
+    name: String,
+    description: String,
+    parent: Option<String>,
+    snaptime: Option<String>,
+    vmstate: bool, // VM snapshot includes RAM
+}
+
+// Tauri command to list the snapshots of a container or VM, oldest first
+#[tauri::command]
+async fn get_guest_snapshots(id: u32, is_vm: bool) -> Result<Vec<GuestSnapshot>, String> {
+    let node = guest_node_name(id).await?;
+    let path = format!("/nodes/{}/{}/{}/snapshot", node, if is_vm { "qemu" } else { "lxc" }, id);
+    let output = run_remote_read("proxmox", &["pvesh", "get", &shell_escape(&path), "--output-format", "json"], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!("Failed to list snapshots of {} {}: {}", if is_vm { "VM" } else { "container" }, id, String::from_utf8_lossy(&output.stderr)));
+    }
+
+    let mut entries: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse snapshots: {}", e))?;
+    entries.sort_by_key(|entry| entry["snaptime"].as_i64().unwrap_or(i64::MAX));
+
+    Ok(entries.iter()
+        // "current" is the live state, not a snapshot
+        .filter(|entry| entry["name"].as_str() != Some("current"))
+        .map(|entry| GuestSnapshot {
+            name: entry["name"].as_str().unwrap_or("").to_string(),
+            description: entry["description"].as_str().unwrap_or("").trim().to_string(),
+            parent: entry["parent"].as_str().map(str::to_string),
+            snaptime: format_task_time(&entry["snaptime"]),
+            vmstate: entry["vmstate"].as_i64() == Some(1),
+        })
+        .collect())
+}
+
+// Live usage of one guest from /cluster/resources
+#[derive(Debug, Serialize, Deserialize)]
+struct GuestResourceUsage {
+    cpu_percent: f64,
+    memory_used_bytes: u64,
+    memory_max_bytes: u64,
+    disk_used_bytes: u64,
+    disk_max_bytes: u64,
+    net_in_bytes: u64,
+    net_out_bytes: u64,
+}
+
+// Everything the UI shows on a guest page. Each section is None when fetching it failed; the
+// reason is in `errors` under the section name and `partial` is set.
+#[derive(Debug, Serialize, Deserialize)]
+struct GuestDescription {
+    id: u32,
+    is_vm: bool,
+    container: Option<ContainerInfo>,
+    vm: Option<VMInfo>,
+    config: Option<ParsedGuestConfig>,
+    network: Option<GuestNetwork>,
+    resources: Option<GuestResourceUsage>,
+    snapshots: Option<Vec<GuestSnapshot>>,
+    recent_tasks: Option<Vec<NodeTask>>,
+    partial: bool,
+    errors: HashMap<String, String>,
+}
+
+// How many node tasks describe_guest searches, and how many of the guest's own it returns
+const DESCRIBE_TASK_SCAN: u32 = 200;
+const DESCRIBE_TASK_LIMIT: usize = 10;
+
+// Helper function to find a guest's usage figures in /cluster/resources
+async fn guest_resource_usage(id: u32) -> Result<GuestResourceUsage, String> {
+    let resources = transport().cluster_resources().await?;
+    let entry = resources.iter()
+        .find(|entry| matches!(entry["type"].as_str(), Some("lxc" | "qemu")) && entry["vmid"].as_u64() == Some(id as u64))
+        .ok_or_else(|| format!("Guest {} not found in cluster resources", id))?;
+    let bytes = |key: &str| entry[key].as_u64().unwrap_or(0);
+
+    Ok(GuestResourceUsage {
+        cpu_percent: entry["cpu"].as_f64().unwrap_or(0.0) * 100.0,
+        memory_used_bytes: bytes("mem"),
+        memory_max_bytes: bytes("maxmem"),
+        disk_used_bytes: bytes("disk"),
+        disk_max_bytes: bytes("maxdisk"),
+        net_in_bytes: bytes("netin"),
+        net_out_bytes: bytes("netout"),
+    })
+}
+
+// Helper function to unwrap one describe_guest section, noting why it is missing
+fn describe_section<T>(name: &str, result: Result<Result<T, String>, tokio::task::JoinError>, errors: &mut HashMap<String, String>) -> Option<T> {
+    match result {
+        Ok(Ok(value)) => Some(value),
+        Ok(Err(e)) => {
+            errors.insert(name.to_string(), e);
+            None
+        }
+        Err(e) => {
+            errors.insert(name.to_string(), format!("Task failed: {}", e));
+            None
+        }
+    }
+}
+
+// Tauri command to fetch status, config, network, usage, snapshots and recent tasks of a guest
+// in one call. The sections are fetched concurrently and a failing one doesn't fail the rest.
+#[tauri::command]
+async fn describe_guest(id: u32) -> Result<GuestDescription, String> {
+    let is_vm = if get_existing_containers().await?.contains(&id) {
+        false
+    } else if get_existing_vms().await?.contains(&id) {
+        true
+    } else {
+        return Err(format!("No container or VM with id {}", id));
+    };
+
+    let container = tokio::spawn(async move {
+        if is_vm { Ok(None) } else { get_container_status(id).await.map(Some) }
+    });
+    let vm = tokio::spawn(async move {
+        if is_vm { get_vm_status(id).await.map(Some) } else { Ok(None) }
+    });
+    let config = tokio::spawn(async move {
+        if is_vm { get_vm_config_parsed(id).await } else { get_container_config_parsed(id).await }
+    });
+    let network = tokio::spawn(lookup_guest_network(id, is_vm));
+    let resources = tokio::spawn(guest_resource_usage(id));
+    let snapshots = tokio::spawn(get_guest_snapshots(id, is_vm));
+    let recent_tasks = tokio::spawn(async move {
+        let tasks = get_node_tasks(Some(DESCRIBE_TASK_SCAN)).await?;
+        let id = id.to_string();
+        Ok(tasks.into_iter().filter(|task| task.id == id).take(DESCRIBE_TASK_LIMIT).collect::<Vec<_>>())
+    });
+
+    let mut errors = HashMap::new();
+    let status_section = if is_vm { "vm" } else { "container" };
+    let container = describe_section(status_section, container.await, &mut errors).flatten();
+    let vm = describe_section(status_section, vm.await, &mut errors).flatten();
+    let config = describe_section("config", config.await, &mut errors);
+    let network = describe_section("network", network.await, &mut errors);
+    let resources = describe_section("resources", resources.await, &mut errors);
+    let snapshots = describe_section("snapshots", snapshots.await, &mut errors);
+    let recent_tasks = describe_section("recent_tasks", recent_tasks.await, &mut errors);
+
+    Ok(GuestDescription {
+        id,
+        is_vm,
+        container,
+        vm,
+        config,
+        network,
+        resources,
+        snapshots,
+        recent_tasks,
+        partial: !errors.is_empty(),
+        errors,
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BackupJob {
+    id: String,
+    schedule: String,             // Proxmox calendar event, e.g. "sat 02:00"
+    schedule_description: String, // The same in words, e.g. "Every Saturday at 02:00"
+    storage: String,
+    selection: String,            // "all", "guests 100,101" or "pool <name>"
+    mode: String,                 // "snapshot", "suspend" or "stop"
+    enabled: bool,
+    next_run: Option<String>,
+}
+
+// Helper function to put a Proxmox calendar event (the backup job schedule syntax) into words.
+// Covers the forms the GUI offers; anything else is shown as written.
+fn describe_schedule(schedule: &str) -> String {
+    let day_name = |day: &str| match day {
+        "mon" => Some("Monday"),
+        "tue" => Some("Tuesday"),
+        "wed" => Some("Wednesday"),
+        "thu" => Some("Thursday"),
+        "fri" => Some("Friday"),
+        "sat" => Some("Saturday"),
+        "sun" => Some("Sunday"),
+        _ => None,
+    };
+    let describe_time = |time: &str| -> Option<String> {
+        if let Some(minutes) = time.strip_prefix("*/") {
+            return minutes.parse::<u32>().ok().map(|minutes| format!("every {} minutes", minutes));
+        }
+        let (hour, minute) = time.split_once(':').unwrap_or((time, "00"));
+        let hour: u32 = hour.parse().ok()?;
+        let minute: u32 = minute.parse().ok()?;
+        (hour < 24 && minute < 60).then(|| format!("at {:02}:{:02}", hour, minute))
+    };
+
+    match schedule.trim() {
+        "hourly" => return "Every hour".to_string(),
+        "daily" => return "Every day at 00:00".to_string(),
+        "weekly" => return "Every Monday at 00:00".to_string(),
+        "monthly" => return "On the 1st of every month at 00:00".to_string(),
+        _ => {}
+    }
+
+    let parts: Vec<&str> = schedule.split_whitespace().collect();
+    let described = match parts.as_slice() {
+        [time] if time.starts_with("*/") => time[2..].parse::<u32>().ok().map(|minutes| format!("Every {} minutes", minutes)),
+        [time] => describe_time(time).map(|time| format!("Every day {}", time)),
+        [days, time] => {
+            let days = if let Some((first, last)) = days.split_once("..") {
+                day_name(first).zip(day_name(last)).map(|(first, last)| format!("{} to {}", first, last))
+            } else {
+                days.split(',').map(day_name).collect::<Option<Vec<_>>>().map(|names| names.join(", "))
+            };
+            days.zip(describe_time(time)).map(|(days, time)| format!("Every {} {}", days, time))
+        }
+        _ => None,
+    };
+    described.unwrap_or_else(|| format!("Schedule: {}", schedule))
+}
+
+// Helper function to turn one /cluster/backup entry into a BackupJob
+fn parse_backup_job(entry: &serde_json::Value) -> BackupJob {
+    let text = |key: &str| entry[key].as_str().unwrap_or("").to_string();
+    let selection = if entry["all"].as_u64() == Some(1) || entry["all"].as_bool() == Some(true) {
+        match entry["exclude"].as_str() {
+            Some(exclude) if !exclude.is_empty() => format!("all except {}", exclude),
+            _ => "all".to_string(),
+        }
+    } else if let Some(pool) = entry["pool"].as_str() {
+        format!("pool {}", pool)
+    } else {
+        format!("guests {}", text("vmid"))
+    };
+    let schedule = entry["schedule"].as_str()
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{} {}", text("dow"), text("starttime")).trim().to_string());
+
+    BackupJob {
+        id: text("id"),
+        schedule_description: describe_schedule(&schedule),
+        schedule,
+        storage: text("storage"),
+        selection,
+        mode: entry["mode"].as_str().unwrap_or("snapshot").to_string(),
+        // Jobs without the key are enabled
+        enabled: !matches!(json_u64(&entry["enabled"]), Some(0)),
+        next_run: format_task_time(&entry["next-run"]),
+    }
+}
+
+// Tauri command to list the scheduled backup jobs of the cluster
+#[tauri::command]
+async fn get_backup_jobs() -> Result<Vec<BackupJob>, String> {
+    let output = run_remote("proxmox", &["pvesh", "get", "/cluster/backup", "--output-format", "json"], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!("Failed to get backup jobs: {}", String::from_utf8_lossy(&output.stderr)));
+    }
+
+    let entries: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse backup jobs: {}", e))?;
+    Ok(entries.iter().map(parse_backup_job).collect())
+}
+
+// Tauri command to pause or resume a scheduled backup job
+#[tauri::command]
+async fn set_backup_job_enabled(job_id: String, enabled: bool) -> Result<BackupJob, String> {
+    audited("set_backup_job_enabled", "cluster".to_string(), serde_json::json!({ "job_id": job_id, "enabled": enabled }), async move {
+        if job_id.is_empty() || !job_id.chars().all(|c| c.is_ascii_alphanumeric() || "-_".contains(c)) {
+            return Err(format!("Invalid backup job id '{}'", job_id));
+        }
+
+        let output = run_remote("proxmox", &["pvesh", "set", &format!("/cluster/backup/{}", job_id), "--enabled", if enabled { "1" } else { "0" }], *COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if !output.status.success() {
+            return Err(format!("Failed to {} backup job {}: {}", if enabled { "enable" } else { "disable" }, job_id, String::from_utf8_lossy(&output.stderr)));
+        }
+
+        get_backup_jobs().await?
+            .into_iter()
+            .find(|job| job.id == job_id)
+            .ok_or_else(|| format!("Backup job {} disappeared after updating it", job_id))
+    }).await
+}
+
+// Proxmox Host Management Commands
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ConnectionStatus {
+    reachable: bool,
+    auth_ok: bool,
+    pve_detected: bool,
+    latency_ms: Option<u64>,
+    pve_version: Option<String>,
+    message: String, // What went wrong and what to check, for the onboarding screen
+}
+
+// Tauri command to check that the Proxmox host can be reached and logged into, and that it runs PVE
+#[tauri::command]
+async fn check_connection() -> Result<ConnectionStatus, String> {
+    let mut status = ConnectionStatus {
+        reachable: false,
+        auth_ok: false,
+        pve_detected: false,
+        latency_ms: None,
+        pve_version: None,
+        message: String::new(),
+    };
+
+    // BatchMode makes ssh fail instead of prompting when key authentication doesn't work
+    let started = std::time::Instant::now();
+    let probe = tokio::process::Command::new("ssh")
+        .args(["-o", "BatchMode=yes", "-o", "ConnectTimeout=5", "proxmox", "true"])
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    let output = match tokio::time::timeout(std::time::Duration::from_secs(10), probe).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => {
+            status.message = format!("Could not run ssh: {}", e);
+            return Ok(status);
+        }
+        Err(_) => {
+            status.message = "Host unreachable: the connection timed out. Check the address of the 'proxmox' host in ~/.ssh/config and that it is online.".to_string();
+            return Ok(status);
+        }
+    };
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        let lower = stderr.to_lowercase();
+        if lower.contains("permission denied") || lower.contains("authentication") || lower.contains("host key verification failed") {
+            status.reachable = true;
+            status.message = format!("Host reachable but login failed. Set up key authentication for the 'proxmox' ssh host: {}", stderr.trim());
+        } else {
+            status.message = format!("Host unreachable. Check the 'proxmox' entry in ~/.ssh/config and the network: {}", stderr.trim());
+        }
+        return Ok(status);
+    }
+
+    status.reachable = true;
+    status.auth_ok = true;
+    status.latency_ms = Some(started.elapsed().as_millis() as u64);
+
+    match run_remote("proxmox", &["pveversion"], *COMMAND_TIMEOUT).await {
+        Ok(output) if output.status.success() => {
+            status.pve_detected = true;
+            status.pve_version = Some(String::from_utf8_lossy(&output.stdout).trim().to_string());
+            status.message = "Connected to Proxmox VE".to_string();
+        }
+        _ => {
+            status.message = "Logged in, but pveversion was not found; the 'proxmox' host does not look like a Proxmox VE node".to_string();
+        }
+    }
+
+    Ok(status)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ComponentVersion {
+    component: String,
+    version: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PveVersions {
+    components: Vec<ComponentVersion>,
+    running_kernel: String,
+    latest_kernel: Option<String>, // Newest kernel package installed, e.g. 6.8.12-4-pve
+    reboot_required: bool,         // A newer kernel is installed than the one running
+}
+
+// Helper function to parse `pveversion -v` lines like "pve-manager: 8.1.4 (running version: 8.1.4/ec5affc9)"
+fn parse_pveversion(output: &str) -> Vec<ComponentVersion> {
+    output.lines()
+        .filter_map(|line| {
+            let (component, rest) = line.split_once(':')?;
+            let version = rest.split_whitespace().next()?;
+            Some(ComponentVersion {
+                component: component.trim().to_string(),
+                version: version.to_string(),
+            })
+        })
+        .collect()
+}
+
+// Helper function to split a kernel release like 6.8.12-4-pve into numbers for ordering
+fn kernel_version_key(release: &str) -> Vec<u64> {
+    release.split(|c: char| !c.is_ascii_digit())
+        .filter_map(|part| part.parse().ok())
+        .collect()
+}
+
+// Helper function to find the newest kernel among installed proxmox-kernel-X-pve / pve-kernel-X-pve packages
+fn latest_installed_kernel(components: &[ComponentVersion]) -> Option<String> {
+    components.iter()
+        .filter_map(|component| {
+            let name = component.component.strip_prefix("proxmox-kernel-")
+                .or_else(|| component.component.strip_prefix("pve-kernel-"))?;
+            let release = name.strip_suffix("-signed").unwrap_or(name);
+            // Meta packages like proxmox-kernel-6.8 carry no -pve release suffix
+            release.ends_with("-pve").then(|| release.to_string())
+        })
+        .max_by_key(|release| kernel_version_key(release))
+}
+
+// Tauri command to get the installed Proxmox component versions and whether a kernel update awaits a reboot
+#[tauri::command]
+async fn get_pve_versions() -> Result<PveVersions, String> {
+    let output = run_remote_read("proxmox", &["pveversion", "-v"], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("Failed to get component versions: {}", String::from_utf8_lossy(&output.stderr)));
+    }
+    let components = parse_pveversion(&String::from_utf8_lossy(&output.stdout));
+
+    let uname = run_remote_read("proxmox", &["uname", "-r"], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    if !uname.status.success() {
+        return Err(format!("Failed to get running kernel: {}", String::from_utf8_lossy(&uname.stderr)));
+    }
+    let running_kernel = String::from_utf8_lossy(&uname.stdout).trim().to_string();
+
+    let latest_kernel = latest_installed_kernel(&components);
+    let reboot_required = latest_kernel.as_deref()
+        .map_or(false, |latest| kernel_version_key(latest) > kernel_version_key(&running_kernel));
+
+    Ok(PveVersions {
+        components,
+        running_kernel,
+        latest_kernel,
+        reboot_required,
+    })
+}
+
+// Capacity view of one node: what the host has, what running guests are allocated, and what they use
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct NodeSummary {
+    node: String,
+    host_cpu_cores: u64,
+    host_memory_bytes: u64,
+    allocated_cpu_cores: u64,    // Sum of running guests' configured cores
+    allocated_memory_bytes: u64, // Sum of running guests' configured memory
+    used_cpu_cores: f64,
+    used_memory_bytes: u64,
+    cpu_overcommit: f64,         // allocated / host; above 1.0 means the node is oversubscribed
+    memory_overcommit: f64,
+    running_guests: u32,
+    stopped_guests: u32,
+    templates: u32,
+}
+
+// Helper function to total up the cluster resource list for one node
+fn summarize_node(resources: &[serde_json::Value], node: &str) -> NodeSummary {
+    let mut summary = NodeSummary {
+        node: node.to_string(),
+        ..NodeSummary::default()
+    };
+
+    for resource in resources.iter().filter(|resource| resource["node"].as_str() == Some(node)) {
+        match resource["type"].as_str() {
+            Some("node") => {
+                summary.host_cpu_cores = json_u64(&resource["maxcpu"]).unwrap_or(0);
+                summary.host_memory_bytes = json_u64(&resource["maxmem"]).unwrap_or(0);
+            }
+            Some("lxc") | Some("qemu") => {
+                if json_u64(&resource["template"]) == Some(1) {
+                    summary.templates += 1;
+                    continue;
+                }
+                if resource["status"].as_str() != Some("running") {
+                    summary.stopped_guests += 1;
+                    continue;
+                }
+
+                let cores = json_u64(&resource["maxcpu"]).unwrap_or(0);
+                summary.running_guests += 1;
+                summary.allocated_cpu_cores += cores;
+                summary.allocated_memory_bytes += json_u64(&resource["maxmem"]).unwrap_or(0);
+                // cpu is the fraction of the guest's own cores in use
+                summary.used_cpu_cores += resource["cpu"].as_f64().unwrap_or(0.0) * cores as f64;
+                summary.used_memory_bytes += json_u64(&resource["mem"]).unwrap_or(0);
+            }
+            _ => {}
+        }
+    }
+
+    if summary.host_cpu_cores > 0 {
+        summary.cpu_overcommit = summary.allocated_cpu_cores as f64 / summary.host_cpu_cores as f64;
+    }
+    if summary.host_memory_bytes > 0 {
+        summary.memory_overcommit = summary.allocated_memory_bytes as f64 / summary.host_memory_bytes as f64;
+    }
+    summary
+}
+
+// Tauri command to get the aggregate CPU and memory allocation of the node's guests against its capacity
+#[tauri::command]
+async fn get_node_summary() -> Result<NodeSummary, String> {
+    let resources = transport().cluster_resources().await?;
+    let nodes: Vec<&str> = resources.iter()
+        .filter(|resource| resource["type"].as_str() == Some("node"))
+        .filter_map(|resource| resource["node"].as_str())
+        .collect();
+
+    // On a cluster, pick this host's entry; a standalone node is the only one listed
+    let node = match nodes.as_slice() {
+        [only] => only.to_string(),
+        _ => transport().host_summary().await?.hostname,
+    };
+    Ok(summarize_node(&resources, &node))
+}
+
+// Tauri command to get Proxmox host information
+#[tauri::command]
+async fn get_proxmox_host_info() -> Result<ProxmoxHostInfo, String> {
+    let cache_key = "proxmox_host_info";
+    
+    // Check if we have valid cached data
+    if is_cache_valid_with_duration(cache_key, *HOST_CACHE_DURATION) {
+        if let Some(cached_data) = get_from_cache(cache_key) {
+            if let Ok(host_info) = serde_json::from_str::<ProxmoxHostInfo>(&cached_data) {
+                return Ok(host_info);
+            }
+        }
+    }
+
+    let host_info = fetch_proxmox_host_info().await?;
+    if let Ok(serialized) = serde_json::to_string(&host_info) {
+        store_in_cache(cache_key, &serialized);
+    }
+
+    Ok(host_info)
+}
+
+// Helper function to read the host info fresh, bypassing and leaving the cache alone
+async fn fetch_proxmox_host_info() -> Result<ProxmoxHostInfo, String> {
+    let host = transport().host_summary().await?;
+    
+    // Get storage information
+    let storage_info = get_storage_info().await.unwrap_or_default();
+    
+    let host_info = ProxmoxHostInfo {
+        hostname: host.hostname,
+        version: host.version,
+        uptime: host.uptime,
+        cpu_count: host.cpu_count,
+        memory_total: host.memory_total,
+        storage_info,
+        node_status: "Online".to_string(),
+    };
+
+    Ok(host_info)
+}
+
+// Host-level destructive commands are refused unless the UI has switched on danger mode
+static DANGER_MODE: AtomicBool = AtomicBool::new(false);
+
+// Tauri command to check whether danger mode is enabled
+#[tauri::command]
+async fn get_danger_mode() -> Result<bool, String> {
+    Ok(DANGER_MODE.load(Ordering::SeqCst))
+}
+
+// Tauri command to enable or disable danger mode
+#[tauri::command]
+async fn set_danger_mode(enabled: bool) -> Result<bool, String> {
+    DANGER_MODE.store(enabled, Ordering::SeqCst);
+    Ok(enabled)
+}
+
+// Tauri command to run an arbitrary shell command on the host, in a container or on a VM.
+// This is arbitrary code execution, so it is only allowed in danger mode.
+#[tauri::command]
+async fn exec_in_guest(container_id: Option<u32>, vm_id: Option<u32>, command: String, timeout_secs: Option<u64>) -> Result<CommandOutput, String> {
+    let arguments = serde_json::json!({ "container_id": container_id, "vm_id": vm_id, "command": command, "timeout_secs": timeout_secs });
+    audited("exec_in_guest", audit_target(container_id, vm_id), arguments, async move {
+        if !DANGER_MODE.load(Ordering::SeqCst) {
+            return Err("Danger mode is disabled; enable it before running commands in a guest".to_string());
+        }
+        if command.trim().is_empty() {
+            return Err("Command must not be empty".to_string());
+        }
+        let timeout_secs = timeout_secs.unwrap_or(30);
+        if !(1..=600).contains(&timeout_secs) {
+            return Err("Timeout must be between 1 and 600 seconds".to_string());
+        }
+
+        let args = guest_shell_command(container_id, vm_id, &command).await;
+        let remote_args: Vec<&str> = args[1..].iter().map(String::as_str).collect();
+        run_command(&args[0], &remote_args, std::time::Duration::from_secs(timeout_secs))
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))
+    }).await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FirewallLevelStatus {
+    config_path: String,
+    enabled: bool,
+    rule_count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FirewallStatus {
+    datacenter: FirewallLevelStatus,
+    node: FirewallLevelStatus,
+    node_name: String,
+    effective: bool,    // Node rules only apply while the datacenter firewall is on
+    policy_in: String,  // Datacenter default for incoming traffic, "DROP" unless set
+    policy_out: String, // Datacenter default for outgoing traffic, "ACCEPT" unless set
+    management_access: bool, // A rule or the "management" IP set lets ssh / the web UI in
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FirewallChange {
+    level: String,
+    enabled: bool,
+    warnings: Vec<String>,
+    status: FirewallStatus,
+}
+
+// A Proxmox firewall file (cluster.fw or host.fw): the [OPTIONS] key/values and the [RULES] lines
+struct FirewallFile {
+    options: HashMap<String, String>,
+    rules: Vec<String>,
+    ipsets: Vec<String>,
+}
+
+// Helper function to parse a Proxmox firewall file
+fn parse_firewall_file(content: &str) -> FirewallFile {
+    let mut file = FirewallFile { options: HashMap::new(), rules: Vec::new(), ipsets: Vec::new() };
+    let mut section = String::new();
+
+    for line in content.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+        if line.starts_with('[') && line.ends_with(']') {
+            section = line[1..line.len() - 1].trim().to_uppercase();
+            if let Some(name) = section.strip_prefix("IPSET ") {
+                file.ipsets.push(name.trim().to_lowercase());
+            }
+            continue;
+        }
+        match section.as_str() {
+            "OPTIONS" => {
+                if let Some((key, value)) = line.split_once(':') {
+                    file.options.insert(key.trim().to_string(), value.trim().to_string());
+                }
+            }
+            "RULES" => file.rules.push(line.to_string()),
+            _ => {}
+        }
+    }
+
+    file
+}
+
+// Helper function to check whether an enabled incoming rule accepts ssh or the web UI (port 8006),
+// e.g. "IN SSH(ACCEPT)" or "IN ACCEPT -p tcp -dport 8006". Disabled rules start with "|".
+fn allows_management(rule: &str) -> bool {
+    let words: Vec<&str> = rule.split_whitespace().collect();
+    if words.len() < 2 || !words[0].eq_ignore_ascii_case("IN") {
+        return false;
+    }
+    if words[1].eq_ignore_ascii_case("SSH(ACCEPT)") {
+        return true;
+    }
+    words[1].eq_ignore_ascii_case("ACCEPT")
+        && words.windows(2).any(|pair| pair[0] == "-dport" && pair[1].split(',').any(|port| port == "22" || port == "8006"))
+}
+
+// Helper function to read the datacenter and node firewall files
+async fn read_firewall_status() -> Result<FirewallStatus, String> {
+    let node = get_node_name().await?;
+    let node_path = format!("/etc/pve/nodes/{}/host.fw", node);
+    let script = format!("cat /etc/pve/firewall/cluster.fw 2>/dev/null; echo ==host; cat {} 2>/dev/null; true", shell_escape(&node_path));
+    let output = run_remote("proxmox", &["sh", "-c", &shell_escape(&script)], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("Failed to read firewall config: {}", String::from_utf8_lossy(&output.stderr)));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (cluster, host) = match stdout.split_once("==host\n") {
+        Some((cluster, host)) => (parse_firewall_file(cluster), parse_firewall_file(host)),
+        None => (parse_firewall_file(&stdout), parse_firewall_file("")),
+    };
+
+    // Datacenter firewall is off unless enabled; the node's is on unless disabled
+    let datacenter_enabled = cluster.options.get("enable").map_or(false, |value| value == "1");
+    let node_enabled = host.options.get("enable").map_or(true, |value| value != "0");
+    let management_access = cluster.ipsets.iter().any(|name| name == "management")
+        || cluster.rules.iter().chain(&host.rules).any(|rule| allows_management(rule));
+
+    Ok(FirewallStatus {
+        datacenter: FirewallLevelStatus {
+            config_path: "/etc/pve/firewall/cluster.fw".to_string(),
+            enabled: datacenter_enabled,
+            rule_count: cluster.rules.len(),
+        },
+        node: FirewallLevelStatus {
+            config_path: node_path,
+            enabled: node_enabled,
+            rule_count: host.rules.len(),
+        },
+        node_name: node,
+        effective: datacenter_enabled && node_enabled,
+        policy_in: cluster.options.get("policy_in").cloned().unwrap_or_else(|| "DROP".to_string()),
+        policy_out: cluster.options.get("policy_out").cloned().unwrap_or_else(|| "ACCEPT".to_string()),
+        management_access,
+    })
+}
+
+// Tauri command to get the datacenter and node firewall switches and the default policies
+#[tauri::command]
+async fn get_firewall_status() -> Result<FirewallStatus, String> {
+    read_firewall_status().await
+}
+
+// Tauri command to turn the datacenter or node firewall on or off. Turning on the datacenter firewall
+// for the first time with an incoming DROP policy and no rule for ssh or the web UI can lock the admin
+// out, so that needs acknowledge_lockout_risk.
+#[tauri::command]
+async fn set_firewall_enabled(level: String, enabled: bool, acknowledge_lockout_risk: Option<bool>) -> Result<FirewallChange, String> {
+    audited("set_firewall_enabled", "host".to_string(), serde_json::json!({ "level": level, "enabled": enabled, "acknowledge_lockout_risk": acknowledge_lockout_risk }), async move {
+        let current = read_firewall_status().await?;
+        let path = match level.as_str() {
+            "datacenter" => "/cluster/firewall/options".to_string(),
+            "node" => format!("/nodes/{}/firewall/options", current.node_name),
+            _ => return Err(format!("Unknown firewall level '{}'; expected datacenter or node", level)),
+        };
+
+        let mut warnings = Vec::new();
+        let turns_on_filtering = enabled && if level == "datacenter" { !current.datacenter.enabled && current.node.enabled } else { current.datacenter.enabled && !current.node.enabled };
+        if turns_on_filtering && current.policy_in.eq_ignore_ascii_case("DROP") && !current.management_access {
+            let warning = format!(
+                "Incoming traffic defaults to DROP and no rule allows ssh (22) or the web UI (8006), so this can lock you out of {}. Add a management rule or the 'management' IP set first.",
+                current.node_name);
+            if level == "datacenter" && !current.datacenter.enabled && acknowledge_lockout_risk != Some(true) {
+                return Err(format!("{} Set acknowledge_lockout_risk to enable it anyway.", warning));
+            }
+            warnings.push(warning);
+        }
+        if enabled && level == "node" && !current.datacenter.enabled {
+            warnings.push("The datacenter firewall is off, so node rules have no effect until it is enabled".to_string());
+        }
+
+        let output = run_remote("proxmox", &["pvesh", "set", &shell_escape(&path), "--enable", if enabled { "1" } else { "0" }], *COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+        if !output.status.success() {
+            return Err(format!("Failed to {} the {} firewall: {}", if enabled { "enable" } else { "disable" }, level, String::from_utf8_lossy(&output.stderr)));
+        }
+
+        Ok(FirewallChange {
+            status: read_firewall_status().await?,
+            level,
+            enabled,
+            warnings,
+        })
+    }).await
+}
+
+// Helper function to guard reboot/shutdown/update of the host behind danger mode and a typed hostname
+async fn confirm_host_action(confirmation_token: Option<&str>) -> Result<(), String> {
+    if !DANGER_MODE.load(Ordering::SeqCst) {
+        return Err("Danger mode is disabled; enable it before running host-level actions".to_string());
+    }
+
+    let hostname = transport().host_summary().await?.hostname;
+    match confirmation_token.map(str::trim) {
+        Some(token) if !token.is_empty() && token == hostname => Ok(()),
+        Some(token) if !token.is_empty() => Err(format!("Confirmation '{}' does not match host name '{}'", token, hostname)),
+        _ => Err(format!("Confirmation required: type the host name '{}' to continue", hostname)),
+    }
+}
+
+// Tauri command to reboot Proxmox host
+#[tauri::command]
+async fn reboot_proxmox_host(confirmation_token: Option<String>) -> Result<String, String> {
+    audited("reboot_proxmox_host", "host".to_string(), serde_json::json!({}), async move {
+        confirm_host_action(confirmation_token.as_deref()).await?;
+
+        let output = run_remote("proxmox", &["sudo", "systemctl", "reboot"], *COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    
+        if output.status.success() {
+            Ok("Proxmox host reboot initiated successfully".to_string())
+        } else {
+            Err(format!("Failed to reboot Proxmox host: {}", String::from_utf8_lossy(&output.stderr)))
+        }
+    }).await
+}
+
+// Tauri command to shutdown Proxmox host
+#[tauri::command]
+async fn shutdown_proxmox_host(confirmation_token: Option<String>) -> Result<String, String> {
+    audited("shutdown_proxmox_host", "host".to_string(), serde_json::json!({}), async move {
+        confirm_host_action(confirmation_token.as_deref()).await?;
+
+        let output = run_remote("proxmox", &["sudo", "systemctl", "poweroff"], *COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    
+        if output.status.success() {
+            Ok("Proxmox host shutdown initiated successfully".to_string())
+        } else {
+            Err(format!("Failed to shutdown Proxmox host: {}", String::from_utf8_lossy(&output.stderr)))
+        }
+    }).await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GuestDrainStep {
+    id: u32,
+    is_vm: bool,
+    result: String, // "shutdown" (stopped cleanly), "forced" (stopped after the timeout) or "failed"
+    message: String,
+    elapsed_secs: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HostShutdownReport {
+    guests: Vec<GuestDrainStep>,
+    host_powered_off: bool,
+    message: String,
+}
+
+// Helper function to shut one guest down cleanly, force-stopping it if it's still up after the timeout
+async fn drain_guest(id: u32, is_vm: bool, timeout: std::time::Duration) -> GuestDrainStep {
+    let started = std::time::Instant::now();
+    let tool = if is_vm { "qm" } else { "pct" };
+    let step = |result: &str, message: String| GuestDrainStep {
+        id,
+        is_vm,
+        result: result.to_string(),
+        message,
+        elapsed_secs: started.elapsed().as_secs(),
+    };
+
+    // The shutdown call itself may fail (no ACPI, guest agent hung), so watch the status rather than its exit code
+    let shutdown = run_remote(&guest_host(id).await, &[tool, "shutdown", &id.to_string(), "--timeout", &timeout.as_secs().to_string()],
+                              timeout + std::time::Duration::from_secs(30)).await;
+    let shutdown_error = match &shutdown {
+        Ok(output) => String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        Err(e) => e.to_string(),
+    };
+    loop {
+        if let Ok(status) = transport().guest_status(id, is_vm).await {
+            if status.status == "Stopped" {
+                return step("shutdown", "Shut down cleanly".to_string());
+            }
+        }
+        if started.elapsed() >= timeout {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    }
+
+    let output = match run_remote(&guest_host(id).await, &[tool, "stop", &id.to_string()], *POWER_COMMAND_TIMEOUT).await {
+        Ok(output) => output,
+        Err(e) => return step("failed", format!("Failed to force-stop after {}s: {}", timeout.as_secs(), e)),
+    };
+    if output.status.success() {
+        step("forced", format!("Still running after {}s ({}); forced a stop", timeout.as_secs(), shutdown_error))
+    } else {
+        step("failed", format!("Failed to force-stop after {}s: {}", timeout.as_secs(), String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+// Tauri command to stop every running guest on the host before powering it off. Guests in a start
+// plan go last, in reverse start order, so dependents stop before what they depend on. If any guest
+// can't be stopped the host is left running.
+#[tauri::command]
+async fn graceful_host_shutdown(confirmation_token: Option<String>, timeout_per_guest: Option<u64>) -> Result<HostShutdownReport, String> {
+    let timeout_secs = timeout_per_guest.unwrap_or(120);
+    audited("graceful_host_shutdown", "host".to_string(), serde_json::json!({ "timeout_per_guest": timeout_secs }), async move {
+        confirm_host_action(confirmation_token.as_deref()).await?;
+        if !(10..=3600).contains(&timeout_secs) {
+            return Err("Per-guest timeout must be between 10 and 3600 seconds".to_string());
+        }
+        let timeout = std::time::Duration::from_secs(timeout_secs);
+
+        let node = get_node_name().await?;
+        let running: Vec<(u32, bool)> = transport().cluster_resources().await?.iter()
+            .filter(|resource| matches!(resource["type"].as_str(), Some("lxc") | Some("qemu")))
+            .filter(|resource| resource["status"] == "running")
+            .filter(|resource| resource["node"].as_str().map(|n| n == node).unwrap_or(true))
+            .filter_map(|resource| Some((json_u64(&resource["vmid"])? as u32, resource["type"] == "qemu")))
+            .collect();
+
+        // Plans are walked in name order so the resulting shutdown order is stable
+        let mut plans: Vec<(String, StartPlan)> = app_config().start_plans.into_iter().collect();
+        plans.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut planned: Vec<(u32, bool)> = Vec::new();
+        for (_, plan) in &plans {
+            for guest in start_plan_sequence(plan).unwrap_or_default() {
+                let key = (guest.id, guest.is_vm);
+                if running.contains(&key) && !planned.contains(&key) {
+                    planned.push(key);
+                }
+            }
+        }
+        planned.reverse();
+
+        // Guests outside any plan have no known ordering, so they go down together first
+        let unplanned: Vec<_> = running.iter()
+            .filter(|guest| !planned.contains(guest))
+            .map(|&(id, is_vm)| tokio::spawn(drain_guest(id, is_vm, timeout)))
+            .collect();
+        let mut guests = Vec::new();
+        for handle in unplanned {
+            if let Ok(step) = handle.await {
+                guests.push(step);
+            }
+        }
+        for (id, is_vm) in planned {
+            guests.push(drain_guest(id, is_vm, timeout).await);
+        }
+        remove_from_cache("system_overview");
+
+        let failed: Vec<String> = guests.iter()
+            .filter(|step| step.result == "failed")
+            .map(|step| format!("{}:{}", if step.is_vm { "vm" } else { "ct" }, step.id))
+            .collect();
+        if !failed.is_empty() {
+            return Ok(HostShutdownReport {
+                guests,
+                host_powered_off: false,
+                message: format!("Host left running because {} could not be stopped", failed.join(", ")),
+            });
+        }
+
+        let output = run_remote("proxmox", &["sudo", "systemctl", "poweroff"], *COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+        if !output.status.success() {
+            return Err(format!("Guests stopped but host poweroff failed: {}", String::from_utf8_lossy(&output.stderr)));
+        }
+
+        let forced = guests.iter().filter(|step| step.result == "forced").count();
+        Ok(HostShutdownReport {
+            message: format!("Stopped {} guests ({} forced); host shutdown initiated", guests.len(), forced),
+            guests,
+            host_powered_off: true,
+        })
+    }).await
+}
+
+// Tauri command to get Proxmox cluster status
+#[tauri::command]
+async fn get_cluster_status() -> Result<String, String> {
+    let output = run_remote("proxmox", &["pvecm", "status"], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(format!("Failed to get cluster status: {}", String::from_utf8_lossy(&output.stderr)))
+    }
+}
+
+// Tauri command to update Proxmox packages
+#[tauri::command]
+async fn update_proxmox_packages(confirmation_token: Option<String>) -> Result<PackageUpgradeResult, String> {
+    audited("update_proxmox_packages", "host".to_string(), serde_json::json!({}), async move {
+        confirm_host_action(confirmation_token.as_deref()).await?;
+
+        // ssh hands its arguments to the remote shell as one string, so the && has to be part of it
+        let output = run_command("proxmox", &["sudo DEBIAN_FRONTEND=noninteractive apt-get update && sudo DEBIAN_FRONTEND=noninteractive apt-get upgrade -y"], *LONG_COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    
+        if output.success() {
+            Ok(PackageUpgradeResult {
+                message: "Package update completed successfully".to_string(),
+                output,
+            })
+        } else {
+            Err(format!("Failed to update packages: {}\n{}", output.stderr.trim(), output.stdout.trim()))
+        }
+    }).await
+}
+
+// Helper function to get storage information
+async fn get_storage_info() -> Result<Vec<StorageInfo>, String> {
+    transport().storage_info().await
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct StorageVolume {
+    volid: String,         // e.g. "local-lvm:vm-100-disk-0" or "local:backup/vzdump-lxc-100-....tar.zst"
+    content: String,       // images, rootdir, backup, iso, vztmpl or snippets
+    format: String,        // raw, qcow2, subvol, tar.zst, iso, ...
+    size: u64,             // Bytes
+    vmid: Option<u32>,     // Guest the volume belongs to, if any
+    ctime: Option<String>, // Creation time, when the storage records it
+}
+
+// Content types list_storage_content accepts
+const STORAGE_CONTENT_TYPES: &[&str] = &["images", "rootdir", "backup", "iso", "vztmpl", "snippets"];
+
+// Tauri command to list the volumes of one content type on a storage (what `pvesm list <storage>
+// --content <type>` shows), largest first
+#[tauri::command]
+async fn list_storage_content(storage: String, content_type: String) -> Result<Vec<StorageVolume>, String> {
+    if !STORAGE_CONTENT_TYPES.contains(&content_type.as_str()) {
+        return Err(format!("Unknown content type '{}'; expected one of {}", content_type, STORAGE_CONTENT_TYPES.join(", ")));
+    }
+
+    // The API form of pvesm list, which also has each volume's ctime
+    let node = get_node_name().await?;
+    let path = format!("/nodes/{}/storage/{}/content", node, storage);
+    let output = run_remote("proxmox", &["pvesh", "get", &shell_escape(&path), "--content", &content_type, "--output-format", "json"], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("Failed to list {} on storage {}: {}", content_type, storage, String::from_utf8_lossy(&output.stderr)));
+    }
+
+    let entries: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse storage content: {}", e))?;
+    let mut volumes: Vec<StorageVolume> = entries.iter()
+        .filter_map(|entry| Some(StorageVolume {
+            volid: entry["volid"].as_str()?.to_string(),
+            content: entry["content"].as_str().unwrap_or(&content_type).to_string(),
+            format: entry["format"].as_str().unwrap_or("").to_string(),
+            size: json_u64(&entry["size"]).unwrap_or(0),
+            vmid: json_u64(&entry["vmid"]).map(|vmid| vmid as u32),
+            ctime: format_task_time(&entry["ctime"]),
+        }))
+        .collect();
+
+    volumes.sort_by_key(|volume| std::cmp::Reverse(volume.size));
+    Ok(volumes)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OrphanedVolume {
+    volid: String,
+    storage: String,
+    size: u64, // Bytes
+    vmid: u32, // Owner named in the volume, which no longer exists
+    format: String,
+}
+
+// Helper function to check whether a guest config key holds a volume: scsiN, virtioN, sataN, ideN,
+// efidisk0, tpmstate0, unusedN (VMs) or rootfs, mpN (containers)
+fn is_volume_key(key: &str) -> bool {
+    let base = key.trim_end_matches(|c: char| c.is_ascii_digit());
+    matches!(base, "scsi" | "virtio" | "sata" | "ide" | "efidisk" | "tpmstate" | "unused" | "rootfs" | "mp")
+}
+
+// Helper function to collect every volid referenced from guest configs, snapshot sections included
+fn referenced_volumes(configs: &str) -> Vec<String> {
+    configs.lines()
+        .filter_map(|line| line.split_once(':'))
+        .filter(|(key, _)| is_volume_key(key.trim()))
+        // "local-lvm:vm-100-disk-0,size=32G" -> "local-lvm:vm-100-disk-0"
+        .map(|(_, value)| value.trim().split(',').next().unwrap_or("").to_string())
+        .filter(|volid| volid.contains(':'))
+        .collect()
+}
+
+// Tauri command to report disk volumes that belong to a guest id which no longer exists and that
+// no guest config references. Only reports; deleting is left to delete_storage_volume.
+#[tauri::command]
+async fn find_orphaned_volumes() -> Result<Vec<OrphanedVolume>, String> {
+    // Guest ids across the whole cluster, so a guest on another node doesn't make its disks look orphaned
+    let resources = transport().cluster_resources().await?;
+    let guest_ids: Vec<u32> = resources.iter()
+        .filter(|resource| matches!(resource["type"].as_str(), Some("lxc" | "qemu")))
+        .filter_map(|resource| json_u64(&resource["vmid"]).map(|vmid| vmid as u32))
+        .collect();
+
+    let script = "for f in /etc/pve/nodes/*/qemu-server/*.conf /etc/pve/nodes/*/lxc/*.conf; do [ -f \"$f\" ] && cat \"$f\"; done; true";
+    let output = run_remote("proxmox", &["sh", "-c", &shell_escape(script)], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("Failed to read guest configs: {}", String::from_utf8_lossy(&output.stderr)));
+    }
+    let referenced = referenced_volumes(&String::from_utf8_lossy(&output.stdout));
+
+    let mut orphaned: Vec<OrphanedVolume> = Vec::new();
+    for storage in get_storage_info().await? {
+        for content_type in ["images", "rootdir"] {
+            // Storages without this content type just have nothing to list
+            let volumes = list_storage_content(storage.name.clone(), content_type.to_string()).await.unwrap_or_default();
+            for volume in volumes {
+                let vmid = match volume.vmid {
+                    Some(vmid) if !guest_ids.contains(&vmid) => vmid,
+                    _ => continue,
+                };
+                // Shared storages are listed once per node
+                if referenced.contains(&volume.volid) || orphaned.iter().any(|known| known.volid == volume.volid) {
+                    continue;
+                }
+                orphaned.push(OrphanedVolume {
+                    volid: volume.volid,
+                    storage: storage.name.clone(),
+                    size: volume.size,
+                    vmid,
+                    format: volume.format,
+                });
+            }
+        }
+    }
+
+    orphaned.sort_by_key(|volume| std::cmp::Reverse(volume.size));
+    Ok(orphaned)
+}
+
+// Tauri command to delete a volume from its storage with `pvesm free`. The volid has to be typed
+// again as confirmation_token, since there is no undo.
+#[tauri::command]
+async fn delete_storage_volume(volid: String, confirmation_token: Option<String>) -> Result<String, String> {
+    audited("delete_storage_volume", "host".to_string(), serde_json::json!({ "volid": volid }), async move {
+        match confirmation_token.as_deref().map(str::trim) {
+            Some(token) if token == volid => {}
+            Some(token) if !token.is_empty() => return Err(format!("Confirmation '{}' does not match volume '{}'", token, volid)),
+            _ => return Err(format!("Confirmation required: type the volume id '{}' to delete it", volid)),
+        }
+        if !volid.contains(':') {
+            return Err(format!("'{}' is not a volume id (expected <storage>:<volume>)", volid));
+        }
+
+        let output = run_remote("proxmox", &["pvesm", "free", &shell_escape(&volid)], *LONG_COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if output.status.success() {
+            Ok(format!("Volume {} deleted", volid))
+        } else {
+            Err(format!("Failed to delete volume {}: {}", volid, String::from_utf8_lossy(&output.stderr)))
+        }
+    }).await
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct ZfsVdev {
+    name: String,
+    depth: usize, // 0 for top-level vdevs (mirror-0, raidz1-0, ...), 1 for their disks
+    state: String,
+    read_errors: String,
+    write_errors: String,
+    checksum_errors: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct ZfsPool {
+    name: String,
+    size: String,
+    allocated: String,
+    free: String,
+    health: String,
+    fragmentation: String,
+    capacity: String,
+    scan: Option<String>,
+    errors: Option<String>,
+    vdevs: Vec<ZfsVdev>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ZfsReport {
+    zfs_in_use: bool,
+    pools: Vec<ZfsPool>,
+    alerts: Vec<String>, // One line per pool or device that isn't ONLINE
+}
+
+// Helper function to parse `zpool list -H -o name,size,alloc,free,health,frag,cap` output
+fn parse_zpool_list(output: &str) -> Vec<ZfsPool> {
+    output.lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() < 7 {
+                return None;
+            }
+            Some(ZfsPool {
+                name: fields[0].to_string(),
+                size: fields[1].to_string(),
+                allocated: fields[2].to_string(),
+                free: fields[3].to_string(),
+                health: fields[4].to_string(),
+                fragmentation: fields[5].to_string(),
+                capacity: fields[6].to_string(),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+// Helper function to fill in scan, errors and vdev states from `zpool status` output
+fn apply_zpool_status(pools: &mut [ZfsPool], output: &str) {
+    let mut current: Option<usize> = None;
+    let mut in_config = false;
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if let Some(name) = trimmed.strip_prefix("pool:") {
+            current = pools.iter().position(|pool| pool.name == name.trim());
+            in_config = false;
+            continue;
+        }
+        let pool = match current {
+            Some(index) => &mut pools[index],
+            None => continue,
+        };
+
+        if let Some(scan) = trimmed.strip_prefix("scan:") {
+            pool.scan = Some(scan.trim().to_string());
+        } else if let Some(errors) = trimmed.strip_prefix("errors:") {
+            pool.errors = Some(errors.trim().to_string());
+            in_config = false;
+        } else if trimmed.starts_with("NAME") && trimmed.contains("STATE") {
+            in_config = true;
+        } else if in_config && !trimmed.is_empty() {
+            // Rows are indented with a tab, then two spaces per level below the pool itself
+            let indent = line.trim_start_matches('\t').len() - line.trim_start().len();
+            let fields: Vec<&str> = trimmed.split_whitespace().collect();
+            if fields.len() < 2 || fields[0] == pool.name {
+                continue;
+            }
+            pool.vdevs.push(ZfsVdev {
+                name: fields[0].to_string(),
+                depth: (indent / 2).saturating_sub(1),
+                state: fields[1].to_string(),
+                read_errors: fields.get(2).unwrap_or(&"-").to_string(),
+                write_errors: fields.get(3).unwrap_or(&"-").to_string(),
+                checksum_errors: fields.get(4).unwrap_or(&"-").to_string(),
+            });
+        }
+    }
+}
+
+// Tauri command to get ZFS pool health, with an alert for anything DEGRADED, FAULTED or otherwise not ONLINE
+#[tauri::command]
+async fn get_zfs_pools() -> Result<ZfsReport, String> {
+    let output = run_guest_shell(None, None,
+        "command -v zpool >/dev/null 2>&1 || { echo ZFS_NOT_INSTALLED; exit 0; }; zpool list -H -o name,size,alloc,free,health,frag,cap; echo ---; zpool status",
+        *COMMAND_TIMEOUT).await?;
+
+    if !output.status.success() {
+        return Err(format!("Failed to get ZFS pool status: {}", String::from_utf8_lossy(&output.stderr)));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (list, status) = stdout.split_once("\n---\n").unwrap_or((&stdout, ""));
+    let mut pools = parse_zpool_list(list);
+    if stdout.trim() == "ZFS_NOT_INSTALLED" || pools.is_empty() {
+        return Ok(ZfsReport {
+            zfs_in_use: false,
+            pools: Vec::new(),
+            alerts: vec!["ZFS not in use on this node".to_string()],
+        });
+    }
+    apply_zpool_status(&mut pools, status);
+
+    let mut alerts = Vec::new();
+    for pool in &pools {
+        if pool.health != "ONLINE" {
+            alerts.push(format!("Pool {} is {}", pool.name, pool.health));
+        }
+        for vdev in pool.vdevs.iter().filter(|vdev| vdev.state != "ONLINE" && vdev.state != "AVAIL") {
+            alerts.push(format!("{} in pool {} is {}", vdev.name, pool.name, vdev.state));
+        }
+    }
+
+    Ok(ZfsReport {
+        zfs_in_use: true,
+        pools,
+        alerts,
+    })
+}
+
+// Tauri command to start a scrub of a ZFS pool
+#[tauri::command]
+async fn start_zfs_scrub(pool: String) -> Result<String, String> {
+    audited("start_zfs_scrub", "host".to_string(), serde_json::json!({ "pool": pool }), async move {
+        let output = run_remote("proxmox", &["zpool", "scrub", &shell_escape(&pool)], *COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if output.status.success() {
+            Ok(format!("Scrub of pool {} started", pool))
+        } else {
+            Err(format!("Failed to start scrub of pool {}: {}", pool, String::from_utf8_lossy(&output.stderr)))
+        }
+    }).await
+}
+
+// Helper function to get list of existing containers from Proxmox
+async fn get_existing_containers() -> Result<Vec<u32>, String> {
+    transport().list_guests(false).await
+}
+
+// Helper function to get list of existing VMs from Proxmox
+async fn get_existing_vms() -> Result<Vec<u32>, String> {
+    transport().list_guests(true).await
+}
+
+// What a new container or VM would be created with, as far as validate_create_request checks it
+#[derive(Debug, Serialize, Deserialize)]
+struct CreateGuestRequest {
+    id: u32,
+    is_vm: bool,
+    storage: String,          // Storage for the root disk
+    #[serde(default)]
+    template: Option<String>, // Container template volid, e.g. "local:vztmpl/debian-12-standard_12.7-1_amd64.tar.zst"
+    memory_mb: u64,
+    disk_gb: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ValidationIssue {
+    field: String, // Request field the issue is about
+    message: String,
+}
+
+// Lowest guest id Proxmox accepts
+const MIN_GUEST_ID: u32 = 100;
+
+// Tauri command to check a create request against the node before provisioning: the id is free,
+// the storage and template exist, and the memory and disk fit. An empty list means it looks good.
+#[tauri::command]
+async fn validate_create_request(request: CreateGuestRequest) -> Result<Vec<ValidationIssue>, String> {
+    let mut issues = Vec::new();
+    let mut issue = |field: &str, message: String| issues.push(ValidationIssue { field: field.to_string(), message });
+
+    if request.id < MIN_GUEST_ID {
+        issue("id", format!("Guest ids start at {}", MIN_GUEST_ID));
+    } else if get_existing_containers().await?.contains(&request.id) {
+        issue("id", format!("Id {} is already used by a container", request.id));
+    } else if get_existing_vms().await?.contains(&request.id) {
+        issue("id", format!("Id {} is already used by a VM", request.id));
+    }
+
+    // pvesm status reports sizes in KiB
+    let storages = get_storage_info().await?;
+    match storages.iter().find(|storage| storage.name == request.storage) {
+        Some(storage) => {
+            let available_bytes = storage.available.parse::<u64>().unwrap_or(0).saturating_mul(1024);
+            let disk_bytes = request.disk_gb.saturating_mul(1024 * 1024 * 1024);
+            if disk_bytes > available_bytes {
+                issue("disk_gb", format!("{} GB disk doesn't fit on {}, which has {:.1} GB free",
+                    request.disk_gb, request.storage, available_bytes as f64 / 1024.0 / 1024.0 / 1024.0));
+            }
+        }
+        None => issue("storage", format!("Storage '{}' does not exist; available: {}",
+            request.storage, storages.iter().map(|storage| storage.name.as_str()).collect::<Vec<_>>().join(", "))),
+    }
+
+    if request.is_vm {
+        if request.template.is_some() {
+            issue("template", "Templates apply to containers; clone a VM template instead".to_string());
+        }
+    } else {
+        match &request.template {
+            Some(volid) => {
+                let template_storage = volid.split(':').next().unwrap_or("");
+                let downloaded = list_available_templates(template_storage.to_string()).await
+                    .map(|templates| templates.iter().any(|template| template.volid.as_deref() == Some(volid.as_str())))
+                    .unwrap_or(false);
+                if !downloaded {
+                    issue("template", format!("Template {} is not on storage '{}'; download it first", volid, template_storage));
+                }
+            }
+            None => issue("template", "A container needs a template".to_string()),
+        }
+    }
+
+    let node = get_node_summary().await?;
+    let free_memory_bytes = node.host_memory_bytes.saturating_sub(node.used_memory_bytes);
+    if request.memory_mb.saturating_mul(1024 * 1024) > free_memory_bytes {
+        issue("memory_mb", format!("{} MB of memory is more than the {} MB free on node {}",
+            request.memory_mb, free_memory_bytes / 1024 / 1024, node.node));
+    }
+
+    Ok(issues)
+}
+
+// System maintenance commands
+
+// Tauri command to restart monitored services that are enabled but not running, on every
+// monitored guest or only the given targets.
+// Disabled services are left alone, since an admin most likely stopped them on purpose.
+#[tauri::command]
+async fn fix_all_services(dry_run: Option<bool>, targets: Option<Vec<MaintenanceTarget>>) -> Result<FixResult, String> {
+    let dry_run = dry_run.unwrap_or(false);
+    audited("fix_all_services", maintenance_audit_target(targets.as_deref()), serde_json::json!({ "dry_run": dry_run, "targets": targets }), async move {
+        let services = get_all_services(targets.as_deref()).await.unwrap_or_default();
+        let mut actions_taken = Vec::new();
+        let mut skipped = Vec::new();
+        let mut success = true;
+
+        for service in services.into_iter().filter(|service| !service.active) {
+            if !service.enabled {
+                skipped.push(format!("Skipped {} (disabled)", service.name));
+            } else if dry_run {
+                actions_taken.push(format!("Would restart {}", service.name));
+            } else if let Err(e) = control_service(service.name.clone(), "restart".to_string(), service.container_id, service.vm_id).await {
+                success = false;
+                actions_taken.push(format!("Failed to restart {}: {}", service.name, e));
+            } else {
+                actions_taken.push(format!("Restarted {}", service.name));
+            }
+        }
+        actions_taken.extend(skipped);
+
+        let message = if dry_run {
+            "Dry run: no services were restarted.".to_string()
+        } else if success {
+            "All inactive services fixed.".to_string()
+        } else {
+            "Some services failed to fix.".to_string()
+        };
+
+        Ok(FixResult {
+            success,
+            message,
+            actions_taken,
+            timestamp: Utc::now(),
+        })
+    }).await
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct FailedService {
+    container_id: Option<u32>, // Both ids empty means the Proxmox host itself
+    vm_id: Option<u32>,
+    service: String,
+    sub_state: String,          // e.g. "failed", "exited"
+    description: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ServiceRepair {
+    container_id: Option<u32>,
+    vm_id: Option<u32>,
+    service: String,
+    success: bool,
+    message: String,
+}
+
+// Helper function to parse `systemctl list-units --state=failed --no-legend --plain` output
+fn parse_failed_units(output: &str, container_id: Option<u32>, vm_id: Option<u32>) -> Vec<FailedService> {
+    output.lines()
+        .filter_map(|line| {
+            // unit load active sub description...
+            let mut parts = line.split_whitespace();
+            let service = parts.next()?.to_string();
+            let sub_state = parts.nth(2)?.to_string();
+            Some(FailedService {
+                container_id,
+                vm_id,
+                service,
+                sub_state,
+                description: parts.collect::<Vec<_>>().join(" "),
+            })
+        })
+        .collect()
+}
+
+// Tauri command to find failed systemd units on the host and every running guest that can be reached.
+// VMs are only scanned when they have their own ssh alias; the rest would end up on the host.
+#[tauri::command]
+async fn scan_failed_services() -> Result<Vec<FailedService>, String> {
+    let resources = transport().cluster_resources().await?;
+    let mut targets: Vec<(Option<u32>, Option<u32>)> = vec![(None, None)];
+    for guest in resources.iter().filter(|resource| resource["status"] == "running") {
+        let id = match json_u64(&guest["vmid"]) {
+            Some(id) => id as u32,
+            None => continue,
+        };
+        match guest["type"].as_str() {
+            Some("lxc") => targets.push((Some(id), None)),
+            Some("qemu") if vm_ssh_target(id) != "proxmox" => targets.push((None, Some(id))),
+            _ => {}
+        }
+    }
+
+    let handles: Vec<_> = targets.into_iter().map(|(container_id, vm_id)| tokio::spawn(async move {
+        let output = run_guest_shell(container_id, vm_id, "systemctl list-units --state=failed --no-legend --plain", *COMMAND_TIMEOUT).await;
+        match output {
+            Ok(output) => parse_failed_units(&String::from_utf8_lossy(&output.stdout), container_id, vm_id),
+            Err(e) => {
+                tracing::warn!("Failed service scan of {} failed: {}", audit_target(container_id, vm_id), e);
+                Vec::new()
+            }
+        }
+    })).collect();
+
+    let mut failed = Vec::new();
+    for handle in handles {
+        failed.extend(handle.await.unwrap_or_default());
+    }
+    Ok(failed)
+}
+
+// Tauri command to clear the failed state of the selected services and restart them
+#[tauri::command]
+async fn repair_failed_services(targets: Vec<FailedService>) -> Result<Vec<ServiceRepair>, String> {
+    audited("repair_failed_services", "all".to_string(), serde_json::json!({ "targets": targets }), async move {
+        let mut results = Vec::new();
+        for target in targets {
+            let service = shell_escape(&target.service);
+            let command = format!("systemctl reset-failed {service}; systemctl restart {service} && systemctl is-active {service}", service = service);
+            let (success, message) = match run_guest_shell(target.container_id, target.vm_id, &command, *COMMAND_TIMEOUT).await {
+                Ok(output) if output.status.success() => (true, format!("{} restarted", target.service)),
+                Ok(output) => (false, format!("{} did not come back up: {}{}", target.service,
+                                              String::from_utf8_lossy(&output.stderr).trim(), String::from_utf8_lossy(&output.stdout).trim())),
+                Err(e) => (false, e),
+            };
+
+            results.push(ServiceRepair {
+                container_id: target.container_id,
+                vm_id: target.vm_id,
+                service: target.service,
+                success,
+                message,
+            });
+        }
+        Ok(results)
+    }).await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TrimmedFilesystem {
+    mountpoint: String,
+    bytes_trimmed: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TrimResult {
+    target: String, // "ct:<id>" or "host"
+    filesystems: Vec<TrimmedFilesystem>,
+    bytes_trimmed: u64,
+    caches_dropped: Option<bool>, // None when not requested
+    message: String,
+}
+
+// Helper function to parse fstrim's "<mountpoint>: 1.2 GiB (1288490188 bytes) trimmed[ on <device>]" lines
+fn parse_fstrim_output(output: &str) -> Vec<TrimmedFilesystem> {
+    output.lines()
+        .filter_map(|line| {
+            let (mountpoint, rest) = line.split_once(": ").filter(|(_, rest)| rest.contains("bytes) trimmed"))?;
+            let bytes = rest.split_once('(')?.1.split_whitespace().next()?.parse().ok()?;
+            Some(TrimmedFilesystem {
+                mountpoint: mountpoint.trim().to_string(),
+                bytes_trimmed: bytes,
+            })
+        })
+        .collect()
+}
+
+// Helper function to summarize fstrim output into a TrimResult
+fn trim_result(target: String, output: &CommandOutput, caches_dropped: Option<bool>) -> Result<TrimResult, String> {
+    let filesystems = parse_fstrim_output(&output.stdout);
+    if !output.success() && filesystems.is_empty() {
+        return Err(format!("fstrim failed on {}: {}", target, output.stderr.trim()));
+    }
+
+    let bytes_trimmed = filesystems.iter().map(|fs| fs.bytes_trimmed).sum();
+    let message = match caches_dropped {
+        Some(false) => format!("Trimmed {} bytes; dropping caches failed", bytes_trimmed),
+        Some(true) => format!("Trimmed {} bytes and dropped caches", bytes_trimmed),
+        None => format!("Trimmed {} bytes", bytes_trimmed),
+    };
+    Ok(TrimResult {
+        target,
+        filesystems,
+        bytes_trimmed,
+        caches_dropped,
+        message,
+    })
+}
+
+// Tauri command to release unused blocks of a container's volumes back to thin/discard-capable storage.
+// `pct fstrim` trims from the host side, which works for unprivileged containers where fstrim inside
+// the container would be refused.
+#[tauri::command]
+async fn run_container_maintenance(container_id: u32, drop_caches: Option<bool>) -> Result<TrimResult, String> {
+    let drop_caches = drop_caches.unwrap_or(false);
+    let arguments = serde_json::json!({ "container_id": container_id, "drop_caches": drop_caches });
+    audited("run_container_maintenance", format!("ct:{}", container_id), arguments, async move {
+        let host = guest_host(container_id).await;
+        let output = run_command(&host, &["pct", "fstrim", &container_id.to_string()], *LONG_COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        // The page cache is the host kernel's, and /proc/sys is read-only inside the container, so
+        // this runs on the container's node and frees memory for every guest there, not just this one
+        let caches_dropped = if drop_caches {
+            let dropped = run_remote(&host, &["sudo", "sh", "-c", &shell_escape("sync && echo 3 > /proc/sys/vm/drop_caches")], *COMMAND_TIMEOUT).await;
+            Some(matches!(dropped, Ok(output) if output.status.success()))
+        } else {
+            None
+        };
+
+        trim_result(format!("ct:{}", container_id), &output, caches_dropped)
+    }).await
+}
+
+// Tauri command to run fstrim on every mounted discard-capable filesystem of the host
+#[tauri::command]
+async fn run_node_fstrim() -> Result<TrimResult, String> {
+    audited("run_node_fstrim", "host".to_string(), serde_json::json!({}), async move {
+        let output = run_command("proxmox", &["sudo", "fstrim", "-av"], *LONG_COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+        trim_result("host".to_string(), &output, None)
+    }).await
+}
+
+// Tauri command to check the monitored binaries, on every monitored guest or only the given
+// targets, and install the missing ones
+#[tauri::command]
+async fn check_and_install_binaries(targets: Option<Vec<MaintenanceTarget>>) -> Result<InstallResult, String> {
+    audited("check_and_install_binaries", maintenance_audit_target(targets.as_deref()), serde_json::json!({ "targets": targets }), async move {
+        let binaries = get_all_binaries(targets.as_deref()).await.unwrap_or_default();
+    
+        let mut install_result = InstallResult {
+            success: true,
+            message: "Installation completed.".to_string(),
+            installed_items: Vec::new(),
+            failed_items: Vec::new(),
+            timestamp: Utc::now(),
+        };
+
+        // Attempt to install missing binaries
+        for binary_info in &binaries {
+            if !binary_info.exists {
+                let install_command = match binary_info.name.as_str() {
+                    "nginx" => "apt-get install nginx -y",
+                    "docker" => "apt-get install docker.io -y",
+                    // Add other install commands here
+                    _ => "",
+                };
+
+                if !install_command.is_empty() {
+                    let target = resolve_target(binary_info.container_id, binary_info.vm_id).await?;
+                    let output = target.run(&[install_command], *LONG_COMMAND_TIMEOUT)
+                        .await
+                        .map_err(|e| format!("Failed to execute installation for {}: {}", binary_info.name, e))?;
+
+                    if output.status.success() {
+                        install_result.installed_items.push(binary_info.name.clone());
+                    } else {
+                        install_result.failed_items.push(binary_info.name.clone());
+                        install_result.success = false;
+                    }
+                }
+            }
+        }
+
+        // Finalize message
+        install_result.message = format!("Installed: {:?}, Failed: {:?}", 
+            install_result.installed_items, install_result.failed_items);
+
+        Ok(install_result)
+    }).await
+}
+// Tauri command to check the monitored services, binaries and configs (all of them, or those of
+// the given targets) plus the host's health. Only the whole-fleet result is cached.
+#[tauri::command]
+async fn get_maintenance_overview(targets: Option<Vec<MaintenanceTarget>>) -> Result<MaintenanceOverview, String> {
+    let cache_key = "maintenance_overview";
+    
+    // Check if we have valid cached data
+    if targets.is_none() && is_cache_valid_with_duration(cache_key, *MAINTENANCE_CACHE_DURATION) {
+        if let Some(cached_data) = get_from_cache(cache_key) {
+            if let Ok(maintenance_overview) = serde_json::from_str::<MaintenanceOverview>(&cached_data) {
+                return Ok(maintenance_overview);
+            }
+        }
+    }
+    
+    let targets = targets.as_deref();
+    let services = get_all_services(targets).await.unwrap_or_default();
+    let binaries = get_all_binaries(targets).await.unwrap_or_default();
+    let configs = get_all_configs(targets).await.unwrap_or_default();
+    let system_health = get_system_health().await.unwrap_or_default();
+    
+    let maintenance_overview = MaintenanceOverview {
+        services,
+        binaries,
+        configs,
+        system_health,
+        last_updated: Utc::now(),
+    };
+    
+    // Store in cache
+    if targets.is_none() {
+        if let Ok(serialized) = serde_json::to_string(&maintenance_overview) {
+            store_in_cache(cache_key, &serialized);
+        }
+    }
+    
+    Ok(maintenance_overview)
+}
+
+// One thing that needs attention, with the command that usually fixes it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct Issue {
+    severity: String,       // "critical", "warning" or "info"
+    target: String,         // "host", "ct:<id>" or "vm:<id>"
+    description: String,
+    action: Option<String>, // Name of the command to offer as the fix, e.g. "start_container"
+}
+
+// Disk usage (percent) above which get_issues reports a guest or the host
+const DISK_ISSUE_THRESHOLD: f64 = 85.0;
+
+// Tauri command to gather health data for the host and every guest and return only what's wrong:
+// stopped guests that should be up, failed or inactive monitored services, nearly full disks,
+// missing or unreadable monitored configs, and pending security updates.
+#[tauri::command]
+async fn get_issues() -> Result<Vec<Issue>, String> {
+    let issue = |severity: &str, target: String, description: String, action: Option<&str>| Issue {
+        severity: severity.to_string(),
+        target,
+        description,
+        action: action.map(str::to_string),
+    };
+    let mut issues = Vec::new();
+
+    // The slow per-guest probes run side by side with the overview gathering
+    let failed_services = tokio::spawn(scan_failed_services());
+    let maintenance = tokio::spawn(get_maintenance_overview(None));
+    let host_updates = tokio::spawn(list_pending_updates(None, None));
+
+    let resources = transport().cluster_resources().await?;
+    let planned: Vec<(u32, bool)> = app_config().start_plans.values()
+        .flat_map(|plan| plan.guests.iter().map(|guest| (guest.id, guest.is_vm)))
+        .collect();
+    let mut update_checks = Vec::new();
+    for guest in resources.iter().filter(|resource| matches!(resource["type"].as_str(), Some("lxc") | Some("qemu"))) {
+        let id = match json_u64(&guest["vmid"]) {
+            Some(id) => id as u32,
+            None => continue,
+        };
+        let is_vm = guest["type"] == "qemu";
+        let target = format!("{}:{}", if is_vm { "vm" } else { "ct" }, id);
+        if guest["status"] == "running" {
+            if !is_vm {
+                update_checks.push((target, tokio::spawn(list_pending_updates(Some(id), None))));
+            }
+            continue;
+        }
+        if json_u64(&guest["template"]) == Some(1) {
+            continue;
+        }
+
+        // A stopped guest is only a problem if something expects it up: start-at-boot or a start plan
+        let in_plan = planned.contains(&(id, is_vm));
+        let onboot = get_autostart(id, is_vm).await.map(|settings| settings.onboot).unwrap_or(false);
+        if in_plan || onboot {
+            let reason = if onboot { "starts at boot" } else { "is part of a start plan" };
+            issues.push(issue("critical", target, format!("{} is {} but {}", guest["name"].as_str().unwrap_or("Guest"),
+                                                             guest["status"].as_str().unwrap_or("not running"), reason),
+                              Some(if is_vm { "start_vm" } else { "start_container" })));
+        }
+    }
+
+    for (target, metric, value) in resource_samples(&resources) {
+        if metric == "disk" && value > DISK_ISSUE_THRESHOLD {
+            let severity = if value > 95.0 { "critical" } else { "warning" };
+            issues.push(issue(severity, target, format!("Disk is {:.0}% full", value), None));
+        }
+    }
+
+    let failed_services = failed_services.await.ok().and_then(Result::ok).unwrap_or_default();
+    for service in &failed_services {
+        issues.push(issue("critical", audit_target(service.container_id, service.vm_id),
+                          format!("Service {} has failed ({})", service.service, service.sub_state),
+                          Some("repair_failed_services")));
+    }
+
+    if let Ok(Ok(maintenance)) = maintenance.await {
+        for service in maintenance.services.iter().filter(|service| !service.active) {
+            // Already reported above with a better fix
+            let already_failed = failed_services.iter().any(|failed| {
+                failed.service.trim_end_matches(".service") == service.name
+                    && failed.container_id == service.container_id && failed.vm_id == service.vm_id
+            });
+            if !already_failed {
+                issues.push(issue("warning", audit_target(service.container_id, service.vm_id),
+                                  format!("Monitored service {} is not running", service.name), Some("control_service")));
+            }
+        }
+        for config in &maintenance.configs {
+            let target = audit_target(config.container_id, config.vm_id);
+            if !config.exists {
+                issues.push(issue("warning", target, format!("Monitored config {} is missing", config.path), Some("revert_config")));
+            } else if !config.readable {
+                issues.push(issue("warning", target, format!("Monitored config {} is not readable", config.path), None));
+            }
+        }
+        if maintenance.system_health.disk_usage > DISK_ISSUE_THRESHOLD {
+            issues.push(issue("warning", "host".to_string(),
+                              format!("Host root filesystem is {:.0}% full", maintenance.system_health.disk_usage), None));
+        }
+    }
+
+    let update_checks = std::iter::once(("host".to_string(), host_updates)).chain(update_checks);
+    for (target, check) in update_checks {
+        if let Ok(Ok(updates)) = check.await {
+            if let Some(count) = updates.security_count.filter(|count| *count > 0) {
+                let action = if target == "host" { "update_proxmox_packages" } else { "update_container_packages" };
+                issues.push(issue("warning", target, format!("{} security update(s) pending", count), Some(action)));
+            }
+        }
+    }
+
+    let rank = |severity: &str| match severity {
+        "critical" => 0,
+        "warning" => 1,
+        _ => 2,
+    };
+    issues.sort_by(|a, b| rank(&a.severity).cmp(&rank(&b.severity)).then_with(|| a.target.cmp(&b.target)));
+    Ok(issues)
+}
+
+// Service manager of a guest (or the host), which decides how services are listed and controlled
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum InitSystem {
+    Systemd,
+    OpenRc, // Alpine
+    Unknown,
+}
+
+// Helper function to find out which service manager a guest runs; cached for an hour since it doesn't change
+async fn detect_init_system(container_id: Option<u32>, vm_id: Option<u32>) -> InitSystem {
+    let cache_key = format!("init_system_{}", audit_target(container_id, vm_id));
+    let detected = match get_from_cache(&cache_key).filter(|_| is_cache_valid_with_duration(&cache_key, 3600)) {
+        Some(cached) => cached,
+        None => {
+            let probe = "if command -v systemctl >/dev/null 2>&1 && [ -d /run/systemd/system ]; then echo systemd; \
+                         elif command -v rc-service >/dev/null 2>&1; then echo openrc; else echo unknown; fi";
+            match run_guest_shell(container_id, vm_id, probe, *COMMAND_TIMEOUT).await {
+                Ok(output) if output.status.success() => {
+                    let detected = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                    store_in_cache(&cache_key, &detected);
+                    detected
+                }
+                // Not cached, so an unreachable guest is probed again next time
+                _ => "unknown".to_string(),
+            }
+        }
+    };
+
+    match detected.as_str() {
+        "systemd" => InitSystem::Systemd,
+        "openrc" => InitSystem::OpenRc,
+        _ => InitSystem::Unknown,
+    }
+}
+
+// Helper function to parse `rc-status --all --nocolor` output into services. Services listed under a
+// regular runlevel start at boot; those only under a "Dynamic Runlevel" were started by hand or as a dependency.
+fn parse_rc_status(output: &str, container_id: Option<u32>, vm_id: Option<u32>) -> Vec<ServiceInfo> {
+    let mut services: Vec<ServiceInfo> = Vec::new();
+    let mut in_runlevel = false;
+
+    for line in output.lines() {
+        if line.starts_with("Runlevel:") {
+            in_runlevel = true;
+            continue;
+        }
+        if line.starts_with("Dynamic Runlevel:") {
+            in_runlevel = false;
+            continue;
+        }
+
+        // " sonarr      [  started  ]"
+        let (name, state) = match line.split_once('[') {
+            Some((name, state)) => (name.trim(), state.trim_end().trim_end_matches(']').trim()),
+            None => continue,
+        };
+        if name.is_empty() {
+            continue;
+        }
+        // OpenRC adds the time since start to long-running services, e.g. "started 3 day(s) ago"
+        let active = state.split_whitespace().next() == Some("started");
+
+        match services.iter_mut().find(|service| service.name == name) {
+            Some(service) => service.enabled |= in_runlevel,
+            None => services.push(ServiceInfo {
+                name: name.to_string(),
+                status: state.to_string(),
+                enabled: in_runlevel,
+                active,
+                description: format!("OpenRC service: {}", name),
+                container_id,
+                vm_id,
+            }),
+        }
+    }
+
+    services
+}
+
+// Helper function to check one OpenRC service: `rc-service <name> status`, plus `rc-update show`
+// for whether it is in a runlevel
+async fn check_openrc_service(target: &Target, service_name: &str, container_id: Option<u32>, vm_id: Option<u32>) -> Result<ServiceInfo, String> {
+    let service = shell_escape(service_name);
+    let status = target.run(&["rc-service", &service, "status"], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    let active = status.status.success() && String::from_utf8_lossy(&status.stdout).contains("started");
+
+    let runlevels = target.run(&["rc-update", "show"], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    // " sonarr | default"
+    let enabled = String::from_utf8_lossy(&runlevels.stdout).lines().any(|line| {
+        line.split_once('|').map_or(false, |(name, levels)| name.trim() == service_name && !levels.trim().is_empty())
+    });
+
+    Ok(ServiceInfo {
+        name: service_name.to_string(),
+        status: if active { "Active".to_string() } else { "Inactive".to_string() },
+        enabled,
+        active,
+        description: format!("OpenRC service: {}", service_name),
+        container_id,
+        vm_id,
+    })
+}
+
+// Tauri command to check service status
+#[tauri::command]
+async fn check_service_status(service_name: String, container_id: Option<u32>, vm_id: Option<u32>) -> Result<ServiceInfo, String> {
+    let target = resolve_target(container_id, vm_id).await?;
+    if detect_init_system(container_id, vm_id).await == InitSystem::OpenRc {
+        return check_openrc_service(&target, &service_name, container_id, vm_id).await;
+    }
+    let service = shell_escape(&service_name);
+    
+    let output = target.run(&["systemctl", "status", &service], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    
+    let status_output = String::from_utf8_lossy(&output.stdout);
+    let active = status_output.contains("Active: active");
+    let enabled_output = target.run(&["systemctl", "is-enabled", &service], *COMMAND_TIMEOUT)
+        .await
+        .unwrap_or_else(|_| std::process::Output {
+            status: std::process::ExitStatus::from_raw(1),
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+        });
+    let enabled = String::from_utf8_lossy(&enabled_output.stdout).trim() == "enabled";
+    
+    Ok(ServiceInfo {
+        name: service_name.clone(),
+        status: if active { "Active".to_string() } else { "Inactive".to_string() },
+        enabled,
+        active,
+        description: format!("Service: {}", service_name),
+        container_id,
+        vm_id,
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ServiceControlResult {
+    service_name: String,
+    requested_action: String,
+    performed_action: String, // Differs from requested_action when a reload fell back to a restart
+    fell_back: bool,
+    message: String,
+    timestamp: DateTime<Utc>,
+}
+
+// systemctl verbs control_service passes through; anything else is refused before reaching a shell
+const SERVICE_ACTIONS: &[&str] = &["start", "stop", "restart", "reload", "try-restart"];
+
+// Helper function to check a control_service action against the allowlist
+fn validate_service_action(action: &str) -> Result<&'static str, String> {
+    SERVICE_ACTIONS.iter()
+        .find(|allowed| **allowed == action)
+        .copied()
+        .ok_or_else(|| format!("Unsupported service action '{}'; expected one of {}", action, SERVICE_ACTIONS.join(", ")))
+}
+
+// Helper function to run a control_service action through rc-service; `service` is already shell-escaped
+async fn run_openrc_action(target: &Target, service: &str, action: &str) -> Result<std::process::Output, String> {
+    let args = match action {
+        "try-restart" => vec!["rc-service", "--ifstarted", service, "restart"],
+        _ => vec!["rc-service", service, action],
+    };
+    target.run(&args, *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))
+}
+
+// Tauri command to start, stop, restart or reload a service. A reload of a service that doesn't
+// support reloading falls back to a restart, and the result says so.
+#[tauri::command]
+async fn control_service(service_name: String, action: String, container_id: Option<u32>, vm_id: Option<u32>) -> Result<ServiceControlResult, String> {
+    audited("control_service", audit_target(container_id, vm_id), serde_json::json!({ "service_name": service_name, "action": action, "container_id": container_id, "vm_id": vm_id }), async move {
+        let requested = validate_service_action(&action)?;
+        let target = resolve_target(container_id, vm_id).await?;
+        let service = shell_escape(&service_name);
+        let openrc = detect_init_system(container_id, vm_id).await == InitSystem::OpenRc;
+
+        let mut performed = requested;
+        if openrc {
+            // OpenRC has no CanReload to ask, so a reload the init script doesn't support fails and is retried as a restart
+            let mut output = run_openrc_action(&target, &service, requested).await?;
+            if requested == "reload" && !output.status.success() {
+                performed = "restart";
+                output = run_openrc_action(&target, &service, performed).await?;
+            }
+            if !output.status.success() {
+                return Err(format!("Failed to {} service {}: {}", performed, service_name, String::from_utf8_lossy(&output.stderr)));
+            }
+        } else if requested == "reload" {
+            let can_reload = target.run(&["systemctl", "show", "-p", "CanReload", "--value", &service], *COMMAND_TIMEOUT)
+                .await
+                .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+            if String::from_utf8_lossy(&can_reload.stdout).trim() != "yes" {
+                performed = "restart";
+            }
+        }
+
+        if !openrc {
+            let output = target.run(&["systemctl", performed, &service], *COMMAND_TIMEOUT)
+                .await
+                .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+            if !output.status.success() {
+                return Err(format!("Failed to {} service {}: {}", performed, service_name, String::from_utf8_lossy(&output.stderr)));
+            }
+        }
+
+        let fell_back = performed != requested;
+        Ok(ServiceControlResult {
+            message: if fell_back {
+                format!("Service {} does not support reload, so it was restarted", service_name)
+            } else {
+                format!("Service {} {} successfully", service_name, performed)
+            },
+            service_name,
+            requested_action: requested.to_string(),
+            performed_action: performed.to_string(),
+            fell_back,
+            timestamp: Utc::now(),
+        })
+    }).await
+}
+
+// Tauri command to check binary
+#[tauri::command]
+async fn check_binary(binary_name: String, container_id: Option<u32>, vm_id: Option<u32>) -> Result<BinaryInfo, String> {
+    let target = resolve_target(container_id, vm_id).await?;
+    let binary = shell_escape(&binary_name);
+    
+    // First try 'which' command (searches $PATH)
+    let which_output = target.run(&["which", &binary], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    
+    let mut exists = which_output.status.success();
+    let mut path = if exists {
+        String::from_utf8_lossy(&which_output.stdout).trim().to_string()
+    } else {
+        "Not found".to_string()
+    };
+    
+    // If not found in PATH, search common additional locations
+    if !exists {
+        let search_paths = vec![
+            "/opt/*/bin",
+            "/opt/bin",
+            "/usr/local/sbin",
+            "/usr/games",
+            "/snap/bin",
+            "~/.local/bin",
+            "/home/*/bin",
+            "/var/lib/*/bin",
+            "/srv/*/bin",
+            "/app/bin",
+            "/config/bin",
+            "/data/bin",
+            "/media/*/bin",
+            "/mnt/*/bin"
+        ];
+        
+        for search_path in &search_paths {
+            let find_output = target.run(&["find", search_path, "-name", &binary, "-type", "f", "-executable", "2>/dev/null", "|", "head", "-1"], *COMMAND_TIMEOUT)
+                .await;
+                
+            if let Ok(output) = find_output {
+                if output.status.success() {
+                    let found_path_raw = String::from_utf8_lossy(&output.stdout);
+                    let found_path = found_path_raw.trim();
+                    if !found_path.is_empty() {
+                        exists = true;
+                        path = found_path.to_string();
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    
+    // Get version if binary exists
+    let escaped_path = shell_escape(&path);
+    let version = if exists {
+        let version_output = target.run(&[&escaped_path, "--version"], *COMMAND_TIMEOUT)
+            .await;
+            
+        if let Ok(output) = version_output {
+            if output.status.success() {
+                String::from_utf8_lossy(&output.stdout).lines().next().unwrap_or("Unknown").to_string()
+            } else {
+                // Try alternative version commands
+                let alt_commands = vec!["-v", "-V", "version", "--help"];
+                let mut version_found = "Unknown".to_string();
+                
+                for &cmd in &alt_commands {
+                    let alt_output = target.run(&[&escaped_path, cmd], *COMMAND_TIMEOUT)
+                        .await;
+                        
+                    if let Ok(alt_out) = alt_output {
+                        if alt_out.status.success() {
+                            let output_text = String::from_utf8_lossy(&alt_out.stdout);
+                            if let Some(first_line) = output_text.lines().next() {
+                                if !first_line.is_empty() && first_line.len() < 200 { // Reasonable version string
+                                    version_found = first_line.to_string();
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                }
+                version_found
+            }
+        } else {
+            "Unknown".to_string()
+        }
+    } else {
+        "N/A".to_string()
+    };
+    
+    // Check if executable
+    let executable = if exists {
+        let test_output = target.run(&["test", "-x", &escaped_path], *COMMAND_TIMEOUT)
+            .await;
+            
+        if let Ok(output) = test_output {
+            output.status.success()
+        } else {
+            false
+        }
+    } else {
+        false
+    };
+    
+    Ok(BinaryInfo {
+        name: binary_name,
+        path,
+        version,
+        exists,
+        executable,
+        container_id,
+        vm_id,
+    })
+}
+
+// Tauri command to check config file
+#[tauri::command]
+async fn check_config(config_path: String, container_id: Option<u32>, vm_id: Option<u32>) -> Result<ConfigInfo, String> {
+    let target = resolve_target(container_id, vm_id).await?;
+    let escaped_path = shell_escape(&config_path);
+    
+    // Check if file exists
+    let exists_output = target.run(&["test", "-f", &escaped_path], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    
+    let exists = exists_output.status.success();
+    
+    let (readable, writable, size, modified) = if exists {
+        // Check readable
+        let readable_output = target.run(&["test", "-r", &escaped_path], *COMMAND_TIMEOUT)
+            .await
+            .unwrap_or_else(|_| std::process::Output {
+                status: std::process::ExitStatus::from_raw(1),
+                stdout: Vec::new(),
+                stderr: Vec::new(),
+            });
+        let readable = readable_output.status.success();
+        
+        // Check writable
+        let writable_output = target.run(&["test", "-w", &escaped_path], *COMMAND_TIMEOUT)
+            .await
+            .unwrap_or_else(|_| std::process::Output {
+                status: std::process::ExitStatus::from_raw(1),
+                stdout: Vec::new(),
+                stderr: Vec::new(),
+            });
+        let writable = writable_output.status.success();
+        
+        // Get file stats
+        let stat_output = target.run(&["stat", "-c", "'%s %Y'", &escaped_path], *COMMAND_TIMEOUT)
+            .await
+            .unwrap_or_else(|_| std::process::Output {
+                status: std::process::ExitStatus::from_raw(1),
+                stdout: Vec::new(),
+                stderr: Vec::new(),
+            });
+        
+        let stat_str = String::from_utf8_lossy(&stat_output.stdout);
+        let parts: Vec<&str> = stat_str.trim().split_whitespace().collect();
+        
+        let size = parts.get(0).and_then(|s| s.parse().ok()).unwrap_or(0);
+        let modified = parts.get(1)
+            .and_then(|t| t.parse::<i64>().ok())
+            .map(|timestamp| {
+                DateTime::from_timestamp(timestamp, 0)
+                    .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+                    .unwrap_or("Unknown".to_string())
+            })
+            .unwrap_or("Unknown".to_string());
+        
+        (readable, writable, size, modified)
+    } else {
+        (false, false, 0, "N/A".to_string())
+    };
+    
+    Ok(ConfigInfo {
+        name: config_path.split('/').last().unwrap_or("Unknown").to_string(),
+        path: config_path,
+        exists,
+        readable,
+        writable,
+        size,
+        modified,
+        container_id,
+        vm_id,
+    })
+}
+
+// Tauri command to read config file
+#[tauri::command]
+async fn read_config(config_path: String, container_id: Option<u32>, vm_id: Option<u32>) -> Result<String, String> {
+    let target = resolve_target(container_id, vm_id).await?;
+    
+    let output = target.run(&["cat", &shell_escape(&config_path)], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(format!("Failed to read config file: {}", String::from_utf8_lossy(&output.stderr)))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GuestFileTail {
+    path: String,
+    lines: Vec<String>,
+    modified: Option<DateTime<Utc>>,
+    container_id: Option<u32>,
+    vm_id: Option<u32>,
+}
+
+// Upper bound for tail_guest_file so a typo can't pull a whole log across ssh
+const MAX_TAIL_LINES: u32 = 5000;
+
+// Tauri command to read the last lines of a file in a guest, e.g. an application log too big to read whole
+#[tauri::command]
+async fn tail_guest_file(container_id: Option<u32>, vm_id: Option<u32>, path: String, lines: Option<u32>) -> Result<GuestFileTail, String> {
+    let lines = lines.unwrap_or(200).clamp(1, MAX_TAIL_LINES);
+    let escaped_path = shell_escape(&path);
+
+    // Distinct exit codes so a missing file isn't reported as an empty tail
+    let script = format!(
+        "[ -f {path} ] || exit 3; [ -r {path} ] || exit 4; stat -c %Y {path}; tail -n {lines} {path}",
+        path = escaped_path,
+        lines = lines
+    );
+    let output = run_guest_shell(container_id, vm_id, &script, *COMMAND_TIMEOUT).await?;
+    let target = audit_target(container_id, vm_id);
+
+    match output.status.code() {
+        Some(0) => {}
+        Some(3) => return Err(format!("File '{}' does not exist on {}", path, target)),
+        Some(4) => return Err(format!("File '{}' on {} is not readable", path, target)),
+        _ => return Err(format!("Failed to read '{}' on {}: {}", path, target, String::from_utf8_lossy(&output.stderr).trim())),
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (mtime, tail) = stdout.split_once('\n').unwrap_or((&stdout, ""));
+    Ok(GuestFileTail {
+        modified: mtime.trim().parse::<i64>().ok().and_then(|timestamp| DateTime::from_timestamp(timestamp, 0)),
+        lines: tail.lines().map(str::to_string).collect(),
+        path,
+        container_id,
+        vm_id,
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct ScheduledTask {
+    source: String,           // "systemd", "crontab" or the cron file path, e.g. "/etc/cron.d/sonarr"
+    name: String,             // Timer unit, or the command's program for cron entries
+    schedule: Option<String>, // OnCalendar expression or the cron schedule fields
+    next_run: Option<DateTime<Utc>>,
+    last_run: Option<DateTime<Utc>>,
+    command: String,          // Unit the timer activates, or the cron command line
+    user: Option<String>,     // Only known for /etc/crontab and /etc/cron.d entries
+}
+
+// Helper function to turn a systemd timespan such as "1 day 2h", "5min" or "1w 2d" into seconds
+fn parse_systemd_timespan(tokens: &[&str]) -> Option<u64> {
+    let unit_secs = |unit: &str| -> Option<f64> {
+        Some(match unit {
+            "y" | "year" | "years" => 31_557_600.0,
+            "month" | "months" => 2_629_800.0,
+            "w" | "week" | "weeks" => 604_800.0,
+            "d" | "day" | "days" => 86_400.0,
+            "h" | "hour" | "hours" => 3_600.0,
+            "min" | "m" | "minute" | "minutes" => 60.0,
+            "s" | "sec" | "second" | "seconds" => 1.0,
+            "ms" | "us" | "μs" => 0.0,
+            _ => return None,
+        })
+    };
+
+    let mut total = 0.0;
+    let mut pending: Option<f64> = None;
+    let mut parsed_any = false;
+    for token in tokens {
+        let split = token.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(token.len());
+        let (number, unit) = token.split_at(split);
+        match (number.parse::<f64>().ok(), unit) {
+            (Some(value), "") => pending = Some(value),
+            (Some(value), unit) => total += value * unit_secs(unit)?,
+            (None, unit) => total += pending.take()? * unit_secs(unit)?,
+        }
+        parsed_any = true;
+    }
+
+    parsed_any.then(|| total as u64)
+}
+
+// Helper function to parse `systemctl list-timers --all --no-legend` lines
+// (NEXT LEFT LAST PASSED UNIT ACTIVATES). The absolute columns carry a local timezone
+// abbreviation, so the times come from the relative LEFT/PASSED columns instead.
+fn parse_systemd_timers(output: &str, calendars: &HashMap<String, String>, now: DateTime<Utc>) -> Vec<ScheduledTask> {
+    let unset = |token: &str| token == "n/a" || token == "-";
+
+    output.lines()
+        .filter_map(|line| {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            if tokens.len() < 4 {
+                return None;
+            }
+            let (unit, activates) = (tokens[tokens.len() - 2], tokens[tokens.len() - 1]);
+            if !unit.ends_with(".timer") {
+                return None;
+            }
+            let columns = &tokens[..tokens.len() - 2];
+
+            // Each column pair is either "n/a n/a" or "<day> <date> <time> <tz> <timespan...> left|ago"
+            let mut position = 0;
+            let mut relative = |terminator: &str| -> Option<u64> {
+                if columns.get(position).map(|token| unset(token)).unwrap_or(true) {
+                    position += 2;
+                    return None;
+                }
+                let end = position + columns[position..].iter().position(|token| *token == terminator)?;
+                let span = parse_systemd_timespan(&columns[(position + 4).min(end)..end]);
+                position = end + 1;
+                span
+            };
+            let left = relative("left");
+            let passed = relative("ago");
+
+            Some(ScheduledTask {
+                source: "systemd".to_string(),
+                name: unit.to_string(),
+                schedule: calendars.get(unit).cloned(),
+                next_run: left.map(|secs| now + chrono::Duration::seconds(secs as i64)),
+                last_run: passed.map(|secs| now - chrono::Duration::seconds(secs as i64)),
+                command: activates.to_string(),
+                user: None,
+            })
+        })
+        .collect()
+}
+
+// Helper function to map timer units to their OnCalendar expressions from
+// `systemctl show '*.timer' -p Id -p TimersCalendar` output
+fn parse_timer_calendars(output: &str) -> HashMap<String, String> {
+    let mut calendars = HashMap::new();
+    let mut current: Option<&str> = None;
+
+    for line in output.lines() {
+        if let Some(id) = line.strip_prefix("Id=") {
+            current = Some(id.trim());
+        } else if let Some(timers) = line.strip_prefix("TimersCalendar=") {
+            let calendar = timers.split_once("OnCalendar=")
+                .and_then(|(_, rest)| rest.split_once(" ;"))
+                .map(|(calendar, _)| calendar.trim().to_string());
+            if let (Some(id), Some(calendar)) = (current, calendar) {
+                calendars.insert(id.to_string(), calendar);
+            }
+        }
+    }
+
+    calendars
+}
+
+// Helper function to parse one crontab line. System crontabs (/etc/crontab, /etc/cron.d) have a
+// user column after the schedule; per-user crontabs don't.
+fn parse_cron_line(line: &str, source: &str, has_user: bool) -> Option<ScheduledTask> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    // Variable assignments such as SHELL=/bin/sh or MAILTO=""
+    let first = line.split_whitespace().next()?;
+    if first.contains('=') && !first.starts_with('@') {
+        return None;
+    }
+
+    // Take the schedule (and user) fields off the front; whatever remains is the command, spacing intact
+    let schedule_fields = if first.starts_with('@') { 1 } else { 5 };
+    let mut fields = Vec::new();
+    let mut rest = line;
+    for _ in 0..schedule_fields + has_user as usize {
+        rest = rest.trim_start();
+        let end = rest.find(char::is_whitespace)?;
+        fields.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+    let command = rest.trim().to_string();
+    if command.is_empty() {
+        return None;
+    }
+
+    let program = command.split_whitespace().next().unwrap_or("").rsplit('/').next().unwrap_or("").to_string();
+    Some(ScheduledTask {
+        source: source.to_string(),
+        name: program,
+        schedule: Some(fields[..schedule_fields].join(" ")),
+        next_run: None,
+        last_run: None,
+        command,
+        user: has_user.then(|| fields[schedule_fields].to_string()),
+    })
+}
+
+// Tauri command to list the systemd timers and cron jobs of the host, a container or a VM.
+// Guests without systemd or cron simply contribute nothing from that side.
+#[tauri::command]
+async fn get_guest_scheduled_tasks(container_id: Option<u32>, vm_id: Option<u32>) -> Result<Vec<ScheduledTask>, String> {
+    let script = "echo '==timers'; systemctl list-timers --all --no-legend --no-pager 2>/dev/null; \
+                  echo '==calendars'; systemctl show '*.timer' -p Id -p TimersCalendar --no-pager 2>/dev/null; \
+                  echo '==crontab'; crontab -l 2>/dev/null; \
+                  echo '==system'; for f in /etc/crontab /etc/cron.d/*; do [ -f \"$f\" ] && sed \"s|^|$f\t|\" \"$f\"; done; true";
+    let output = run_guest_shell(container_id, vm_id, script, *COMMAND_TIMEOUT).await?;
+    if !output.status.success() {
+        return Err(format!("Failed to list scheduled tasks on {}: {}", audit_target(container_id, vm_id), String::from_utf8_lossy(&output.stderr)));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut sections: HashMap<&str, String> = HashMap::new();
+    let mut current = "";
+    for line in stdout.lines() {
+        match line {
+            "==timers" | "==calendars" | "==crontab" | "==system" => current = &line[2..],
+            _ => {
+                let body = sections.entry(current).or_default();
+                body.push_str(line);
+                body.push('\n');
+            }
+        }
+    }
+    let section = |name: &str| sections.get(name).map(String::as_str).unwrap_or("");
+
+    let calendars = parse_timer_calendars(section("calendars"));
+    let mut tasks = parse_systemd_timers(section("timers"), &calendars, Utc::now());
+    tasks.extend(section("crontab").lines().filter_map(|line| parse_cron_line(line, "crontab", false)));
+    tasks.extend(section("system").lines().filter_map(|line| {
+        let (file, line) = line.split_once('\t')?;
+        parse_cron_line(line, file, true)
+    }));
+
+    Ok(tasks)
+}
+
+// Tauri command to write config file
+#[tauri::command]
+async fn write_config(config_path: String, content: String, container_id: Option<u32>, vm_id: Option<u32>) -> Result<String, String> {
+    audited("write_config", audit_target(container_id, vm_id), serde_json::json!({ "config_path": config_path, "content_bytes": content.len(), "container_id": container_id, "vm_id": vm_id }), async move {
+        let target = resolve_target(container_id, vm_id).await?;
+    
+        // Create a backup first
+        let backup_path = backup_config_file(&config_path, container_id, vm_id).await?;
+    
+        // Write the new content
+        let escaped_path = shell_escape(&config_path);
+        let description = format!("ssh {} {} tee {}", target.ssh, target.prefix.join(" "), escaped_path);
+        let _permit = acquire_ssh_permit().await;
+        let mut command = tokio::process::Command::new("ssh");
+        command.arg(&target.ssh).args(target.args(&["tee", &escaped_path]));
+        let output = run_process(command, Some(content), *COMMAND_TIMEOUT, &description).await?;
+    
+        if output.status.success() {
+            if backup_path.is_some() {
+                prune_config_backups(&config_path, container_id, vm_id).await;
+            }
+            Ok(format!("Config file {} updated successfully", config_path))
+        } else {
+            Err(format!("Failed to write config file: {}", String::from_utf8_lossy(&output.stderr)))
+        }
+    }).await
+}
+
+// Config backup, diff and revert support
+#[derive(Debug, Serialize, Deserialize)]
+struct ConfigBackup {
+    path: String,
+    size: u64,
+    modified: String,
+    modified_timestamp: i64,
+    timestamp: Option<i64>, // From the .backup-<timestamp> suffix (Unix ms; seconds for older backups); None for a legacy .backup
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct DiffLine {
+    kind: String, // "added", "removed" or "context"
+    content: String,
+}
+
+// Helper function to compute a line diff from `old` to `new` (longest common subsequence)
+fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    // Skip the common prefix and suffix so the quadratic part only covers the changed region
+    let prefix = old_lines.iter().zip(&new_lines).take_while(|(a, b)| a == b).count();
+    let suffix = old_lines[prefix..].iter().rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old_lines[prefix..old_lines.len() - suffix];
+    let new_mid = &new_lines[prefix..new_lines.len() - suffix];
+
+    // lcs[i][j] = length of the LCS of old_mid[i..] and new_mid[j..]
+    let mut lcs = vec![vec![0u32; new_mid.len() + 1]; old_mid.len() + 1];
+    for i in (0..old_mid.len()).rev() {
+        for j in (0..new_mid.len()).rev() {
+            lcs[i][j] = if old_mid[i] == new_mid[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let line = |kind: &str, content: &str| DiffLine { kind: kind.to_string(), content: content.to_string() };
+    let mut diff: Vec<DiffLine> = old_lines[..prefix].iter().map(|l| line("context", l)).collect();
+
+    let (mut i, mut j) = (0, 0);
+    while i < old_mid.len() || j < new_mid.len() {
+        if i < old_mid.len() && j < new_mid.len() && old_mid[i] == new_mid[j] {
+            diff.push(line("context", old_mid[i]));
+            i += 1;
+            j += 1;
+        } else if i < old_mid.len() && (j == new_mid.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push(line("removed", old_mid[i]));
+            i += 1;
+        } else {
+            diff.push(line("added", new_mid[j]));
+            j += 1;
+        }
+    }
+
+    diff.extend(old_lines[old_lines.len() - suffix..].iter().map(|l| line("context", l)));
+    diff
+}
+
+// Helper function to get the most recent backup of a config file
+async fn latest_config_backup(config_path: &str, container_id: Option<u32>, vm_id: Option<u32>) -> Result<String, String> {
+    list_config_backups(config_path.to_string(), container_id, vm_id).await?
+        .into_iter()
+        .next()
+        .map(|backup| backup.path)
+        .ok_or_else(|| format!("No backups found for {}", config_path))
+}
+
+// Helper function to make sure a path is one of this config's backups
+fn validate_backup_path(config_path: &str, backup_path: &str) -> Result<(), String> {
+    if !backup_path.starts_with(&format!("{}.backup", config_path)) || backup_path[config_path.len()..].contains('/') {
+        return Err(format!("{} is not a backup of {}", backup_path, config_path));
+    }
+    Ok(())
+}
+
+// Tauri command to list the backups (.backup and .backup-<timestamp>) of a config file, newest first
+#[tauri::command]
+async fn list_config_backups(config_path: String, container_id: Option<u32>, vm_id: Option<u32>) -> Result<Vec<ConfigBackup>, String> {
+    let escaped = shell_escape(&config_path);
+    let output = run_guest_shell(container_id, vm_id, &format!("stat -c '%n|%s|%Y' {}.backup {}.backup-* 2>/dev/null", escaped, escaped), *COMMAND_TIMEOUT).await?;
+
+    let mut backups: Vec<ConfigBackup> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.rsplitn(3, '|');
+            let modified_timestamp = parts.next()?.parse::<i64>().ok()?;
+            let size = parts.next()?.parse::<u64>().ok()?;
+            let path = parts.next()?.to_string();
+            let timestamp = path.strip_prefix(&format!("{}.backup-", config_path))
+                .and_then(|suffix| suffix.parse::<i64>().ok());
+            Some(ConfigBackup {
+                path,
+                size,
+                modified: DateTime::from_timestamp(modified_timestamp, 0)
+                    .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+                    .unwrap_or("Unknown".to_string()),
+                modified_timestamp,
+                timestamp,
+            })
+        })
+        .collect();
+
+    // cp -p keeps the original's mtime, so the timestamp in the name is the better record of when the backup was taken.
+    // Names used to carry seconds rather than milliseconds; anything below 10^11 is one of those.
+    let taken_at_ms = |backup: &ConfigBackup| match backup.timestamp {
+        Some(timestamp) if timestamp < 100_000_000_000 => timestamp * 1000,
+        Some(timestamp) => timestamp,
+        None => backup.modified_timestamp * 1000,
+    };
+    backups.sort_by_key(|backup| std::cmp::Reverse(taken_at_ms(backup)));
+    Ok(backups)
+}
+
+// Helper function to copy a config file to <path>.backup-<timestamp> before it is changed.
+// Returns None when there was no file to back up.
+async fn backup_config_file(config_path: &str, container_id: Option<u32>, vm_id: Option<u32>) -> Result<Option<String>, String> {
+    let config = shell_escape(config_path);
+    let exists = run_guest_shell(container_id, vm_id, &format!("test -e {}", config), *COMMAND_TIMEOUT).await?
+        .status.success();
+    if !exists {
+        return Ok(None);
+    }
+
+    // Milliseconds, so two writes within the same second don't overwrite each other's backup
+    let backup_path = format!("{}.backup-{}", config_path, Utc::now().timestamp_millis());
+    let output = run_guest_shell(container_id, vm_id, &format!("cp -p {} {}", config, shell_escape(&backup_path)), *COMMAND_TIMEOUT).await?;
+    if !output.status.success() {
+        return Err(format!("Failed to back up {}: {}", config_path, String::from_utf8_lossy(&output.stderr)));
+    }
+    Ok(Some(backup_path))
+}
+
+// Helper function to delete all but the newest keep_last_n_backups backups of a config file.
+// The write it follows has already succeeded, so a failure here is only logged.
+async fn prune_config_backups(config_path: &str, container_id: Option<u32>, vm_id: Option<u32>) {
+    let keep = app_config().config_backups.keep_last_n_backups;
+    let stale: Vec<String> = match list_config_backups(config_path.to_string(), container_id, vm_id).await {
+        Ok(backups) => backups.into_iter().skip(keep).map(|backup| shell_escape(&backup.path)).collect(),
+        Err(e) => {
+            tracing::warn!("Failed to list backups of {} for pruning: {}", config_path, e);
+            return;
+        }
+    };
+    if stale.is_empty() {
+        return;
+    }
+
+    match run_guest_shell(container_id, vm_id, &format!("rm -f {}", stale.join(" ")), *COMMAND_TIMEOUT).await {
+        Ok(output) if output.status.success() => {}
+        Ok(output) => tracing::warn!("Failed to prune backups of {}: {}", config_path, String::from_utf8_lossy(&output.stderr)),
+        Err(e) => tracing::warn!("Failed to prune backups of {}: {}", config_path, e),
+    }
+}
+
+// Helper function to read a whole file from the host, a container or a VM
+async fn read_guest_file(container_id: Option<u32>, vm_id: Option<u32>, path: &str) -> Result<String, String> {
+    let output = run_guest_shell(container_id, vm_id, &format!("cat {}", shell_escape(path)), *COMMAND_TIMEOUT).await?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(format!("Failed to read {}: {}", path, String::from_utf8_lossy(&output.stderr)))
+    }
+}
+
+// Tauri command to diff a backup (the most recent one by default) against the current config file
+#[tauri::command]
+async fn diff_config(config_path: String, container_id: Option<u32>, vm_id: Option<u32>, backup_path: Option<String>) -> Result<Vec<DiffLine>, String> {
+    let backup_path = match backup_path {
+        Some(path) => path,
+        None => latest_config_backup(&config_path, container_id, vm_id).await?,
+    };
+    validate_backup_path(&config_path, &backup_path)?;
+
+    let backup = read_guest_file(container_id, vm_id, &backup_path).await?;
+    let current = read_guest_file(container_id, vm_id, &config_path).await?;
+
+    Ok(diff_lines(&backup, &current))
+}
+
+// Tauri command to restore a config file from a backup (the most recent one by default)
+#[tauri::command]
+async fn revert_config(config_path: String, container_id: Option<u32>, vm_id: Option<u32>, backup_path: Option<String>) -> Result<String, String> {
+    audited("revert_config", audit_target(container_id, vm_id), serde_json::json!({ "config_path": config_path, "container_id": container_id, "vm_id": vm_id, "backup_path": backup_path }), async move {
+        let backup_path = match backup_path {
+            Some(path) => path,
+            None => latest_config_backup(&config_path, container_id, vm_id).await?,
+        };
+        validate_backup_path(&config_path, &backup_path)?;
+
+        let output = run_guest_shell(container_id, vm_id, &format!("cp -p {} {}", shell_escape(&backup_path), shell_escape(&config_path)), *COMMAND_TIMEOUT).await?;
+
+        if output.status.success() {
+            Ok(format!("Config file {} restored from {}", config_path, backup_path))
+        } else {
+            Err(format!("Failed to restore config file: {}", String::from_utf8_lossy(&output.stderr)))
+        }
+    }).await
+}
+
+// Tauri command to restore a config file from its <path>.backup-<timestamp> copy, as listed by list_config_backups
+#[tauri::command]
+async fn restore_config_backup(config_path: String, timestamp: i64, container_id: Option<u32>, vm_id: Option<u32>) -> Result<String, String> {
+    let backup_path = format!("{}.backup-{}", config_path, timestamp);
+    revert_config(config_path, container_id, vm_id, Some(backup_path)).await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ConfigValidation {
+    config_path: String,
+    validator: String,
+    valid: bool,
+    output: String,
+    temp_path: Option<String>, // Validated copy kept for promote_config; removed when validation fails
+}
+
+// Helper function to pick the syntax check for a config file by its name; `{}` is replaced by the file to check
+fn config_validator(config_path: &str) -> Option<&'static str> {
+    let file_name = config_path.rsplit('/').next().unwrap_or(config_path);
+    match file_name {
+        "nginx.conf" => Some("nginx -t -c {}"),
+        "sshd_config" => Some("sshd -t -f {}"),
+        "apache2.conf" | "httpd.conf" => Some("apachectl -t -f {}"),
+        "haproxy.cfg" => Some("haproxy -c -f {}"),
+        "sudoers" => Some("visudo -c -f {}"),
+        name if name.starts_with("named.conf") => Some("named-checkconf {}"),
+        name if name.ends_with(".json") => Some("python3 -m json.tool {} > /dev/null"),
+        _ => None,
+    }
+}
+
+// Helper function to write a file on the host, in a container or on a VM by piping it through cat
+async fn write_guest_file(container_id: Option<u32>, vm_id: Option<u32>, path: &str, content: String) -> Result<(), String> {
+    let args = guest_shell_command(container_id, vm_id, &format!("cat > {}", shell_escape(path))).await;
+    write_file_over_ssh(args, path, content).await
+}
+
+// Helper function to write a whole file on a Proxmox node, e.g. the one that owns a guest
+async fn write_node_file(host: &str, path: &str, content: String) -> Result<(), String> {
+    write_file_over_ssh(vec![host.to_string(), format!("cat > {}", shell_escape(path))], path, content).await
+}
+
+// Helper function to run `ssh <args>` (a `cat > path` command) with the content on stdin, so it never
+// appears in a command line
+async fn write_file_over_ssh(args: Vec<String>, path: &str, content: String) -> Result<(), String> {
+    let description = format!("ssh {}", args.join(" "));
+    let _permit = acquire_ssh_permit().await;
+    let mut command = tokio::process::Command::new("ssh");
+    command.args(&args);
+    let output = run_process(command, Some(content), *COMMAND_TIMEOUT, &description).await?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("Failed to write {}: {}", path, String::from_utf8_lossy(&output.stderr)))
+    }
+}
+
+// Tauri command to syntax-check new content for a config file without touching the real file.
+// The content is written next to the original and checked there so relative includes still resolve;
+// a passing copy is kept for promote_config.
+#[tauri::command]
+async fn validate_config(config_path: String, content: String, container_id: Option<u32>, vm_id: Option<u32>) -> Result<ConfigValidation, String> {
+    let validator = config_validator(&config_path)
+        .ok_or_else(|| format!("No syntax check is known for {}", config_path))?;
+    let temp_path = format!("{}.validate-{}", config_path, Utc::now().timestamp_millis());
+    write_guest_file(container_id, vm_id, &temp_path, content).await?;
+
+    let command = validator.replace("{}", &shell_escape(&temp_path));
+    let output = run_guest_shell(container_id, vm_id, &format!("{} 2>&1", command), *COMMAND_TIMEOUT).await?;
+    let valid = output.status.success();
+    if !valid {
+        let _ = run_guest_shell(container_id, vm_id, &format!("rm -f {}", shell_escape(&temp_path)), *COMMAND_TIMEOUT).await;
+    }
+
+    Ok(ConfigValidation {
+        config_path,
+        validator: command,
+        valid,
+        output: String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        temp_path: valid.then(|| temp_path),
+    })
+}
+
+// Tauri command to replace a config file with a copy that passed validate_config, keeping <path>.backup-<timestamp>.
+// The content is copied over the original so its owner and mode stay the same.
+#[tauri::command]
+async fn promote_config(config_path: String, temp_path: String, container_id: Option<u32>, vm_id: Option<u32>) -> Result<String, String> {
+    audited("promote_config", audit_target(container_id, vm_id), serde_json::json!({ "config_path": config_path, "temp_path": temp_path, "container_id": container_id, "vm_id": vm_id }), async move {
+        let suffix = temp_path.strip_prefix(&format!("{}.validate-", config_path)).unwrap_or("");
+        if suffix.is_empty() || !suffix.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("{} is not a validated copy of {}", temp_path, config_path));
+        }
+
+        let backup_path = backup_config_file(&config_path, container_id, vm_id).await?;
+        let (config, temp) = (shell_escape(&config_path), shell_escape(&temp_path));
+        let command = format!("cat {temp} > {config} && rm -f {temp}", config = config, temp = temp);
+        let output = run_guest_shell(container_id, vm_id, &command, *COMMAND_TIMEOUT).await?;
+
+        if output.status.success() {
+            if backup_path.is_some() {
+                prune_config_backups(&config_path, container_id, vm_id).await;
+            }
+            Ok(format!("Config file {} updated from validated copy", config_path))
+        } else {
+            Err(format!("Failed to promote validated config: {}", String::from_utf8_lossy(&output.stderr)))
+        }
+    }).await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SafeWriteResult {
+    config_path: String,
+    backup_path: Option<String>, // None when the file didn't exist before
+    validator: Option<String>,   // None when no syntax check is known for the file
+    validation_output: String,
+    reload: Option<ServiceControlResult>,
+}
+
+// Helper function to pick the service that reads a config file, for reloading after a change
+fn config_service(config_path: &str) -> Option<&'static str> {
+    let file_name = config_path.rsplit('/').next().unwrap_or(config_path);
+    match file_name {
+        "nginx.conf" => Some("nginx"),
+        "sshd_config" => Some("sshd"),
+        "apache2.conf" => Some("apache2"),
+        "httpd.conf" => Some("httpd"),
+        "haproxy.cfg" => Some("haproxy"),
+        name if name.starts_with("named.conf") => Some("named"),
+        _ => None,
+    }
+}
+
+// Tauri command to write a config file as one transaction: back it up to <path>.backup-<timestamp>,
+// write the new content, run the file's syntax check in place and put the backup back if the check
+// fails. With reload set, the owning service is reloaded once the new file has passed.
+#[tauri::command]
+async fn safe_write_config(config_path: String, content: String, container_id: Option<u32>, vm_id: Option<u32>, reload: Option<bool>) -> Result<SafeWriteResult, String> {
+    audited("safe_write_config", audit_target(container_id, vm_id), serde_json::json!({ "config_path": config_path, "content_bytes": content.len(), "container_id": container_id, "vm_id": vm_id, "reload": reload }), async move {
+        let service = match reload {
+            Some(true) => Some(config_service(&config_path)
+                .ok_or_else(|| format!("No service is known to own {}, so it can't be reloaded", config_path))?),
+            _ => None,
+        };
+        let config = shell_escape(&config_path);
+        let backup_path = backup_config_file(&config_path, container_id, vm_id).await?;
+
+        // cat over the existing file rather than replacing it so its owner and mode stay the same
+        write_guest_file(container_id, vm_id, &config_path, content).await?;
+
+        let validator = config_validator(&config_path).map(|validator| validator.replace("{}", &config));
+        let mut validation_output = String::new();
+        if let Some(command) = &validator {
+            let output = run_guest_shell(container_id, vm_id, &format!("{} 2>&1", command), *COMMAND_TIMEOUT).await?;
+            validation_output = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+            if !output.status.success() {
+                let restore = match &backup_path {
+                    Some(backup) => format!("cat {} > {}", shell_escape(backup), config),
+                    None => format!("rm -f {}", config),
+                };
+                let restored = run_guest_shell(container_id, vm_id, &restore, *COMMAND_TIMEOUT).await
+                    .map(|output| output.status.success())
+                    .unwrap_or(false);
+                return Err(if restored {
+                    format!("{} failed validation and was rolled back: {}", config_path, validation_output)
+                } else {
+                    format!("{} failed validation and could NOT be rolled back (backup: {}): {}",
+                        config_path, backup_path.as_deref().unwrap_or("none"), validation_output)
+                });
+            }
+        }
+
+        if backup_path.is_some() {
+            prune_config_backups(&config_path, container_id, vm_id).await;
+        }
+
+        let reload = match service {
+            Some(service) => Some(control_service(service.to_string(), "reload".to_string(), container_id, vm_id).await
+                .map_err(|e| format!("{} was written and validated, but reloading {} failed: {}", config_path, service, e))?),
+            None => None,
+        };
+
+        Ok(SafeWriteResult {
+            config_path,
+            backup_path,
+            validator,
+            validation_output,
+            reload,
+        })
+    }).await
+}
+
+// One config file inside an export archive, stored as files/<n> next to manifest.json
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct ExportedConfig {
+    file: String,
+    path: String,
+    container_id: Option<u32>,
+    vm_id: Option<u32>,
+    size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct FailedConfig {
+    path: String,
+    container_id: Option<u32>,
+    vm_id: Option<u32>,
+    error: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct ConfigExport {
+    archive_path: String,
+    created_at: DateTime<Utc>,
+    included: Vec<ExportedConfig>,
+    failed: Vec<FailedConfig>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ConfigImport {
+    restored: Vec<ExportedConfig>,
+    failed: Vec<FailedConfig>,
+}
+
+// Helper function to run tar on this machine, e.g. to pack or unpack a config export
+async fn run_tar(args: Vec<String>) -> Result<(), String> {
+    let description = format!("tar {}", args.join(" "));
+    let mut command = tokio::process::Command::new("tar");
+    command.args(&args);
+    let output = run_process(command, None, *COMMAND_TIMEOUT, &description)
+        .await
+        .map_err(|e| format!("Failed to run tar: {}", e))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("tar failed: {}", String::from_utf8_lossy(&output.stderr)))
+    }
+}
+
+// Tauri command to save every monitored config file into a timestamped archive in the app data dir.
+// Files that can't be read are listed in the result instead of failing the export.
+#[tauri::command]
+async fn export_all_configs() -> Result<ConfigExport, String> {
+    let created_at = Utc::now();
+    let name = format!("configs-{}", created_at.format("%Y%m%d-%H%M%S"));
+    let exports_dir = app_data_path("config-exports")?;
+    let staging_dir = exports_dir.join(&name);
+    std::fs::create_dir_all(staging_dir.join("files"))
+        .map_err(|e| format!("Failed to create {}: {}", staging_dir.display(), e))?;
+
+    let mut included = Vec::new();
+    let mut failed = Vec::new();
+    for config in get_all_configs(None).await? {
+        let content = if config.exists {
+            read_config(config.path.clone(), config.container_id, config.vm_id).await
+        } else {
+            Err("File does not exist".to_string())
+        };
+
+        match content {
+            Ok(content) => {
+                let file = format!("files/{}", included.len());
+                std::fs::write(staging_dir.join(&file), &content)
+                    .map_err(|e| format!("Failed to write {}: {}", file, e))?;
+                included.push(ExportedConfig {
+                    file,
+                    path: config.path,
+                    container_id: config.container_id,
+                    vm_id: config.vm_id,
+                    size: content.len() as u64,
+                });
+            }
+            Err(error) => failed.push(FailedConfig {
+                path: config.path,
+                container_id: config.container_id,
+                vm_id: config.vm_id,
+                error,
+            }),
+        }
+    }
+
+    let archive = exports_dir.join(format!("{}.tar.gz", name));
+    let export = ConfigExport {
+        archive_path: archive.display().to_string(),
+        created_at,
+        included,
+        failed,
+    };
+    let manifest = serde_json::to_string_pretty(&export)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    std::fs::write(staging_dir.join("manifest.json"), manifest)
+        .map_err(|e| format!("Failed to write manifest: {}", e))?;
+
+    let packed = run_tar(vec![
+        "-czf".to_string(), export.archive_path.clone(),
+        "-C".to_string(), staging_dir.display().to_string(),
+        "manifest.json".to_string(), "files".to_string(),
+    ]).await;
+    let _ = std::fs::remove_dir_all(&staging_dir);
+    packed?;
+
+    Ok(export)
+}
+
+// Tauri command to write the configs from an export archive back to their hosts.
+// Each file goes through write_config, so the current version is kept as <path>.backup-<timestamp>.
+#[tauri::command]
+async fn import_all_configs(archive_path: String) -> Result<ConfigImport, String> {
+    let staging_dir = app_data_path("config-exports")?.join(format!("import-{}", Utc::now().timestamp_millis()));
+    std::fs::create_dir_all(&staging_dir)
+        .map_err(|e| format!("Failed to create {}: {}", staging_dir.display(), e))?;
+
+    let result = async {
+        run_tar(vec!["-xzf".to_string(), archive_path.clone(), "-C".to_string(), staging_dir.display().to_string()]).await?;
+        let manifest = std::fs::read_to_string(staging_dir.join("manifest.json"))
+            .map_err(|e| format!("{} is not a config export: {}", archive_path, e))?;
+        let export: ConfigExport = serde_json::from_str(&manifest)
+            .map_err(|e| format!("Invalid manifest in {}: {}", archive_path, e))?;
+
+        let mut restored = Vec::new();
+        let mut failed = Vec::new();
+        for entry in export.included {
+            // Manifest entries only ever name files/<n>; anything else would escape the staging dir
+            let content = match entry.file.strip_prefix("files/").filter(|n| n.parse::<usize>().is_ok()) {
+                Some(_) => std::fs::read_to_string(staging_dir.join(&entry.file)).map_err(|e| format!("Failed to read {}: {}", entry.file, e)),
+                None => Err(format!("Unexpected file name '{}' in manifest", entry.file)),
+            };
+            let written = match content {
+                Ok(content) => write_config(entry.path.clone(), content, entry.container_id, entry.vm_id).await,
+                Err(e) => Err(e),
+            };
+
+            match written {
+                Ok(_) => restored.push(entry),
+                Err(error) => failed.push(FailedConfig {
+                    path: entry.path,
+                    container_id: entry.container_id,
+                    vm_id: entry.vm_id,
+                    error,
+                }),
+            }
+        }
+
+        Ok(ConfigImport { restored, failed })
+    }.await;
+
+    let _ = std::fs::remove_dir_all(&staging_dir);
+    result
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FailedDiagnostic {
+    file: String,
+    error: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DiagnosticBundle {
+    archive_path: String,
+    created_at: DateTime<Utc>,
+    guest_id: Option<u32>,
+    included: Vec<String>, // File names inside the archive
+    failed: Vec<FailedDiagnostic>,
+}
+
+// Largest size of any one file in a diagnostic bundle; longer output keeps its end, where the latest lines are
+const MAX_DIAGNOSTIC_FILE_BYTES: usize = 512 * 1024;
+
+// How many journal lines a diagnostic bundle takes from the host and the guest
+const DIAGNOSTIC_JOURNAL_LINES: &str = "2000";
+
+// Helper function to mask values that look like secrets, e.g. "password=..." or "token: ...", line by line
+fn redact_secrets(text: &str) -> String {
+    const SECRET_WORDS: [&str; 6] = ["password", "passwd", "secret", "token", "passphrase", "api_key"];
+
+    text.lines()
+        .map(|line| {
+            // ASCII lowercasing keeps byte offsets, so positions found in `lower` are valid in `line`
+            let lower = line.to_ascii_lowercase();
+            let secret_at = SECRET_WORDS.iter().filter_map(|word| lower.find(word).map(|at| at + word.len())).min();
+            // The value starts after the first = or : following the secret's name
+            let value_at = secret_at.and_then(|at| lower[at..].find(['=', ':']).map(|offset| at + offset + 1));
+            match value_at {
+                Some(at) if !line[at..].trim().is_empty() => format!("{} [redacted]", &line[..at]),
+                _ => line.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Helper function to cap one diagnostic file, keeping the end on a char boundary
+fn cap_diagnostic(text: &str) -> String {
+    if text.len() <= MAX_DIAGNOSTIC_FILE_BYTES {
+        return text.to_string();
+    }
+    let mut start = text.len() - MAX_DIAGNOSTIC_FILE_BYTES;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    format!("[... {} bytes truncated ...]\n{}", start, &text[start..])
+}
+
+// Helper function to write text files into a new zip archive, all under one top-level folder
+fn write_zip(path: &std::path::Path, folder: &str, files: &[(String, String)]) -> Result<(), String> {
+    use std::io::Write;
+
+    let file = std::fs::File::create(path).map_err(|e| e.to_string())?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (name, content) in files {
+        zip.start_file(format!("{}/{}", folder, name), options).map_err(|e| e.to_string())?;
+        zip.write_all(content.as_bytes()).map_err(|e| e.to_string())?;
+    }
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// Helper function to take a command's output for a diagnostic bundle; stderr is kept since it often
+// holds the interesting part
+fn diagnostic_text(output: Result<std::process::Output, String>) -> Result<String, String> {
+    let output = output?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() && stdout.trim().is_empty() {
+        return Err(format!("Command failed: {}", stderr.trim()));
+    }
+    Ok(if stderr.trim().is_empty() { stdout.to_string() } else { format!("{}\n--- stderr ---\n{}", stdout, stderr) })
+}
+
+// Tauri command to gather a support bundle into a .zip in the app data dir: host versions, the
+// host journal, host resources, recent tasks and, for a guest, its config, status and journal.
+// Secrets are masked and every file is capped; parts that can't be collected are listed in the result.
+#[tauri::command]
+async fn collect_diagnostics(guest_id: Option<u32>) -> Result<DiagnosticBundle, String> {
+    let is_vm = match guest_id {
+        Some(id) if get_existing_containers().await?.contains(&id) => Some(false),
+        Some(id) if get_existing_vms().await?.contains(&id) => Some(true),
+        Some(id) => return Err(format!("No container or VM with id {}", id)),
+        None => None,
+    };
+
+    let host = |args: &'static [&'static str]| async move {
+        diagnostic_text(run_remote("proxmox", args, *LONG_COMMAND_TIMEOUT).await.map_err(|e| format!("Failed to execute SSH command: {}", e)))
+    };
+    let mut sections: Vec<(String, Result<String, String>)> = vec![
+        ("pveversion.txt".to_string(), host(&["pveversion", "-v"]).await),
+        ("host-journal.txt".to_string(), host(&["journalctl", "-b", "--no-pager", "-n", DIAGNOSTIC_JOURNAL_LINES]).await),
+        ("host-resources.txt".to_string(), host(&["sh", "-c", "'uptime; echo; free -m; echo; df -h; echo; pvesm status'"]).await),
+    ];
+
+    let tasks = get_node_tasks(Some(100)).await.map(|tasks| match guest_id {
+        Some(id) => tasks.into_iter().filter(|task| task.id == id.to_string()).collect(),
+        None => tasks,
+    });
+    sections.push(("tasks.json".to_string(), tasks.and_then(|tasks: Vec<NodeTask>| serde_json::to_string_pretty(&tasks).map_err(|e| e.to_string()))));
+
+    if let (Some(id), Some(is_vm)) = (guest_id, is_vm) {
+        let (container_id, vm_id) = if is_vm { (None, Some(id)) } else { (Some(id), None) };
+        let config = if is_vm { get_vm_config(id).await } else { pct_config(id).await };
+        sections.push((format!("guest-{}-config.txt", id), config));
+        let status = if is_vm {
+            get_vm_status(id).await.and_then(|status| serde_json::to_string_pretty(&status).map_err(|e| e.to_string()))
+        } else {
+            get_container_status(id).await.and_then(|status| serde_json::to_string_pretty(&status).map_err(|e| e.to_string()))
+        };
+        sections.push((format!("guest-{}-status.json", id), status));
+        let journal = run_guest_shell(container_id, vm_id, &format!("journalctl -b --no-pager -n {} 2>&1 || tail -n {} /var/log/messages",
+            DIAGNOSTIC_JOURNAL_LINES, DIAGNOSTIC_JOURNAL_LINES), *LONG_COMMAND_TIMEOUT).await;
+        sections.push((format!("guest-{}-journal.txt", id), diagnostic_text(journal)));
+    }
+
+    let created_at = Utc::now();
+    let name = match guest_id {
+        Some(id) => format!("diagnostics-{}-{}", id, created_at.format("%Y%m%d-%H%M%S")),
+        None => format!("diagnostics-{}", created_at.format("%Y%m%d-%H%M%S")),
+    };
+    let diagnostics_dir = app_data_path("diagnostics")?;
+    std::fs::create_dir_all(&diagnostics_dir)
+        .map_err(|e| format!("Failed to create {}: {}", diagnostics_dir.display(), e))?;
+
+    let mut files = Vec::new();
+    let mut included = Vec::new();
+    let mut failed = Vec::new();
+    for (file, content) in sections {
+        match content {
+            Ok(content) => {
+                files.push((file.clone(), cap_diagnostic(&redact_secrets(&content))));
+                included.push(file);
+            }
+            Err(error) => failed.push(FailedDiagnostic { file, error }),
+        }
+    }
+
+    let archive = diagnostics_dir.join(format!("{}.zip", name));
+    let bundle = DiagnosticBundle {
+        archive_path: archive.display().to_string(),
+        created_at,
+        guest_id,
+        included,
+        failed,
+    };
+    let manifest = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    files.push(("manifest.json".to_string(), manifest));
+
+    if let Err(e) = write_zip(&archive, &name, &files) {
+        let _ = std::fs::remove_file(&archive);
+        return Err(format!("Failed to write {}: {}", bundle.archive_path, e));
+    }
+
+    Ok(bundle)
+}
+
+// Helper function to keep the monitored items that fall within the targets; no targets keeps them all
+fn scoped_items(items: Vec<MonitoredItem>, targets: Option<&[MaintenanceTarget]>) -> Vec<MonitoredItem> {
+    match targets {
+        Some(targets) => items.into_iter()
+            .filter(|item| targets.iter().any(|target| target.container_id == item.container_id && target.vm_id == item.vm_id))
+            .collect(),
+        None => items,
+    }
+}
+
+// Helper function to name the targets of a scoped maintenance command for the audit log
+fn maintenance_audit_target(targets: Option<&[MaintenanceTarget]>) -> String {
+    match targets {
+        Some(targets) => targets.iter()
+            .map(|target| audit_target(target.container_id, target.vm_id))
+            .collect::<Vec<_>>()
+            .join(","),
+        None => "all".to_string(),
+    }
+}
+
+// Helper functions for maintenance
+async fn get_all_services(targets: Option<&[MaintenanceTarget]>) -> Result<Vec<ServiceInfo>, String> {
+    let mut services = Vec::new();
+    
+    for item in scoped_items(app_config().monitored.services, targets) {
+        if let Ok(service_info) = check_service_status(item.name, item.container_id, item.vm_id).await {
+            services.push(service_info);
+        }
+    }
+    
+    Ok(services)
+}
+
+
+async fn get_all_binaries(targets: Option<&[MaintenanceTarget]>) -> Result<Vec<BinaryInfo>, String> {
+    let mut binaries = Vec::new();
+    
+    for item in scoped_items(app_config().monitored.binaries, targets) {
+        if let Ok(binary_info) = check_binary(item.name, item.container_id, item.vm_id).await {
+            binaries.push(binary_info);
+        }
+    }
+    
+    Ok(binaries)
+}
+
+async fn get_all_configs(targets: Option<&[MaintenanceTarget]>) -> Result<Vec<ConfigInfo>, String> {
+    let mut configs = Vec::new();
+    
+    for item in scoped_items(app_config().monitored.configs, targets) {
+        if let Ok(config_info) = check_config(item.name, item.container_id, item.vm_id).await {
+            configs.push(config_info);
+        }
+    }
+    
+    Ok(configs)
+}
+
+// Tauri command to get the services, binaries and config files the maintenance overview checks
+#[tauri::command]
+async fn get_monitored_items() -> Result<MonitoredItems, String> {
+    Ok(app_config().monitored)
+}
+
+// Tauri command to replace the services, binaries and config files the maintenance overview checks
+#[tauri::command]
+async fn set_monitored_items(items: MonitoredItems) -> Result<MonitoredItems, String> {
+    let all_items = items.services.iter().chain(&items.binaries).chain(&items.configs);
+    for item in all_items {
+        if item.name.trim().is_empty() {
+            return Err("Monitored item names must not be empty".to_string());
+        }
+        if item.container_id.is_some() && item.vm_id.is_some() {
+            return Err(format!("Monitored item '{}' names both a container and a VM", item.name));
+        }
+    }
+    if let Some(config) = items.configs.iter().find(|config| !config.name.starts_with('/')) {
+        return Err(format!("Config path '{}' must be absolute", config.name));
+    }
+
+    let updated = update_app_config(|app_config| app_config.monitored = items)?.monitored;
+    remove_from_cache("maintenance_overview");
+    Ok(updated)
+}
+
+// Desired state for one key of a config file, kept in config_expectations.json in the app data dir
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct ConfigExpectation {
+    path: String,
+    key: String,
+    expected: String,
+    container_id: Option<u32>,
+    vm_id: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ConfigDrift {
+    path: String,
+    key: String,
+    expected: String,
+    actual: Option<String>, // None when the key is missing or the file couldn't be read
+    container_id: Option<u32>,
+    vm_id: Option<u32>,
+    error: Option<String>,
+}
+
+// Helper function to find a key's value in key=value, key: value, "key": value or <key>value</key> config syntax
+fn lookup_config_value(content: &str, key: &str) -> Option<String> {
+    let open_tag = format!("<{}>", key);
+    let close_tag = format!("</{}>", key);
+
+    content.lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#') && !line.starts_with(';'))
+        .find_map(|line| {
+            if let Some(rest) = line.strip_prefix(&open_tag) {
+                return rest.split(&close_tag).next().map(|value| value.trim().to_string());
+            }
+            let rest = line.strip_prefix(key).or_else(|| line.strip_prefix(&format!("\"{}\"", key)))?;
+            let value = rest.trim_start().strip_prefix('=').or_else(|| rest.trim_start().strip_prefix(':'))?;
+            Some(value.trim().trim_end_matches(',').trim_matches('"').trim_matches('\'').to_string())
+        })
+}
+
+// Helper function to load the config expectations, empty when none have been saved
+fn load_config_expectations() -> Result<Vec<ConfigExpectation>, String> {
+    match std::fs::read_to_string(app_data_path("config_expectations.json")?) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse config expectations: {}", e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("Failed to read config expectations: {}", e)),
+    }
+}
+
+// Tauri command to get the expected config values
+#[tauri::command]
+async fn get_config_expectations() -> Result<Vec<ConfigExpectation>, String> {
+    load_config_expectations()
+}
+
+// Tauri command to replace the expected config values
+#[tauri::command]
+async fn set_config_expectations(expectations: Vec<ConfigExpectation>) -> Result<Vec<ConfigExpectation>, String> {
+    for expectation in &expectations {
+        if !expectation.path.starts_with('/') {
+            return Err(format!("Config path '{}' must be absolute", expectation.path));
+        }
+        if expectation.key.trim().is_empty() {
+            return Err(format!("Expectation for {} has an empty key", expectation.path));
+        }
+        if expectation.container_id.is_some() && expectation.vm_id.is_some() {
+            return Err(format!("Expectation for {} names both a container and a VM", expectation.path));
+        }
+    }
+
+    let serialized = serde_json::to_string_pretty(&expectations)
+        .map_err(|e| format!("Failed to serialize config expectations: {}", e))?;
+    std::fs::write(app_data_path("config_expectations.json")?, serialized)
+        .map_err(|e| format!("Failed to write config expectations: {}", e))?;
+
+    Ok(expectations)
+}
+
+// Tauri command to compare every expected config value with the live file and report the differences
+#[tauri::command]
+async fn check_config_drift() -> Result<Vec<ConfigDrift>, String> {
+    let expectations = load_config_expectations()?;
+    let mut files: HashMap<String, Result<String, String>> = HashMap::new();
+    let mut drifts = Vec::new();
+
+    for expectation in expectations {
+        // Each file is read once no matter how many of its keys are checked
+        let file_key = format!("{}|{}", audit_target(expectation.container_id, expectation.vm_id), expectation.path);
+        if !files.contains_key(&file_key) {
+            let content = read_config(expectation.path.clone(), expectation.container_id, expectation.vm_id).await;
+            files.insert(file_key.clone(), content);
+        }
+
+        let (actual, error) = match &files[&file_key] {
+            Ok(content) => (lookup_config_value(content, &expectation.key), None),
+            Err(e) => (None, Some(e.clone())),
+        };
+        if actual.as_deref() == Some(expectation.expected.as_str()) {
+            continue;
+        }
+
+        drifts.push(ConfigDrift {
+            path: expectation.path,
+            key: expectation.key,
+            expected: expectation.expected,
+            actual,
+            container_id: expectation.container_id,
+            vm_id: expectation.vm_id,
+            error,
+        });
+    }
+
+    Ok(drifts)
+}
+
+async fn get_system_health() -> Result<SystemHealth, String> {
+    // Get system health from Proxmox host
+    let df_output = run_remote("proxmox", &["df", "-h", "/"], *COMMAND_TIMEOUT)
+        .await
+        .unwrap_or_else(|_| std::process::Output {
+            status: std::process::ExitStatus::from_raw(1),
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+        });
+    
+    let disk_usage = if df_output.status.success() {
+        let df_str = String::from_utf8_lossy(&df_output.stdout);
+        df_str.lines().nth(1)
+            .and_then(|line| line.split_whitespace().nth(4))
+            .and_then(|usage| usage.trim_end_matches('%').parse().ok())
+            .unwrap_or(0.0)
+    } else {
+        0.0
+    };
+    
+    let free_output = run_remote("proxmox", &["free", "-m"], *COMMAND_TIMEOUT)
+        .await
+        .unwrap_or_else(|_| std::process::Output {
+            status: std::process::ExitStatus::from_raw(1),
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+        });
+    
+    let memory_usage = if free_output.status.success() {
+        let _free_str = String::from_utf8_lossy(&free_output.stdout);
+        // Parse memory usage from free command output
+        50.0 // Placeholder
+    } else {
+        0.0
+    };
+    
+    let uptime_output = run_remote("proxmox", &["uptime"], *COMMAND_TIMEOUT)
+        .await
+        .unwrap_or_else(|_| std::process::Output {
+            status: std::process::ExitStatus::from_raw(1),
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+        });
+    
+    let uptime = if uptime_output.status.success() {
+        String::from_utf8_lossy(&uptime_output.stdout).trim().to_string()
+    } else {
+        "Unknown".to_string()
+    };
+    
+    Ok(SystemHealth {
+        disk_usage,
+        memory_usage,
+        cpu_load: 0.0, // Would need to parse from uptime or top
+        network_status: "Connected".to_string(),
+        uptime,
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FilesystemUsage {
+    source: String,
+    mount_point: String,
+    size_bytes: u64,
+    used_bytes: u64,
+    available_bytes: u64,
+    percent_used: f64,
+}
+
+// Prints `df` in bytes; busybox df has no --output, so fall back to POSIX 1K blocks behind a marker line
+const DF_COMMAND: &str = "df -B1 --output=source,size,used,avail,pcent,target -x tmpfs -x devtmpfs 2>/dev/null || { echo KB; df -Pk; }";
+
+// Helper function to parse DF_COMMAND output into filesystems sorted by percent used, fullest first
+fn parse_df_output(output: &str) -> Vec<FilesystemUsage> {
+    let block_size: u64 = if output.lines().next().map(str::trim) == Some("KB") { 1024 } else { 1 };
+
+    let mut filesystems: Vec<FilesystemUsage> = output.lines()
+        .filter_map(|line| {
+            // Both formats are: source size used available percent mount point (which may contain spaces)
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 6 {
+                return None;
+            }
+            let size = fields[1].parse::<u64>().ok()?;
+            let used = fields[2].parse::<u64>().ok()?;
+            let available = fields[3].parse::<u64>().ok()?;
+            let percent_used = fields[4].trim_end_matches('%').parse::<f64>()
+                .unwrap_or(if size > 0 { used as f64 / size as f64 * 100.0 } else { 0.0 });
+
+            Some(FilesystemUsage {
+                source: fields[0].to_string(),
+                mount_point: fields[5..].join(" "),
+                size_bytes: size * block_size,
+                used_bytes: used * block_size,
+                available_bytes: available * block_size,
+                percent_used,
+            })
+        })
+        .filter(|filesystem| filesystem.size_bytes > 0)
+        .collect();
+
+    filesystems.sort_by(|a, b| b.percent_used.partial_cmp(&a.percent_used).unwrap_or(std::cmp::Ordering::Equal));
+    filesystems
+}
+
+// Tauri command to get per-mount disk usage inside a container
+#[tauri::command]
+async fn get_container_disk_usage(container_id: u32) -> Result<Vec<FilesystemUsage>, String> {
+    let output = run_guest_shell(Some(container_id), None, DF_COMMAND, *COMMAND_TIMEOUT).await?;
+
+    if !output.status.success() {
+        return Err(format!("Failed to get disk usage for container {}: {}", container_id, String::from_utf8_lossy(&output.stderr)));
+    }
+
+    Ok(parse_df_output(&String::from_utf8_lossy(&output.stdout)))
+}
+
+// Tauri command to get disk usage across all mounts on the Proxmox host
+#[tauri::command]
+async fn get_host_disk_usage() -> Result<Vec<FilesystemUsage>, String> {
+    let output = run_guest_shell(None, None, DF_COMMAND, *COMMAND_TIMEOUT).await?;
+
+    if !output.status.success() {
+        return Err(format!("Failed to get host disk usage: {}", String::from_utf8_lossy(&output.stderr)));
+    }
+
+    Ok(parse_df_output(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct MountPoint {
+    index: Option<u32>, // N of mpN; None for the root filesystem
+    volume: String,     // Storage volume (e.g. local-lvm:vm-214-disk-1) or host path for bind mounts
+    mountpoint: String,
+    size: Option<String>,
+    backup: bool,
+}
+
+// Helper function to parse the rootfs and mpN lines of `pct config` output
+fn parse_container_mounts(config: &str) -> Vec<MountPoint> {
+    config.lines()
+        .take_while(|line| !line.starts_with('['))
+        .filter_map(|line| {
+            let (key, value) = line.split_once(": ")?;
+            let index = if key == "rootfs" {
+                None
+            } else {
+                Some(key.strip_prefix("mp")?.parse::<u32>().ok()?)
+            };
+
+            let mut options = value.split(',');
+            let volume = options.next()?.trim().to_string();
+            let mut mountpoint = if index.is_none() { "/".to_string() } else { String::new() };
+            let mut size = None;
+            // Only the root filesystem is backed up unless a mount point opts in
+            let mut backup = index.is_none();
+            for option in options {
+                match option.split_once('=') {
+                    Some(("mp", path)) => mountpoint = path.to_string(),
+                    Some(("size", value)) => size = Some(value.to_string()),
+                    Some(("backup", value)) => backup = value == "1",
+                    _ => {}
+                }
+            }
+
+            Some(MountPoint {
+                index,
+                volume,
+                mountpoint,
+                size,
+                backup,
+            })
+        })
+        .collect()
+}
+
+// Tauri command to list the root filesystem and mount points of a container
+#[tauri::command]
+async fn list_container_mounts(container_id: u32) -> Result<Vec<MountPoint>, String> {
+    let config = pct_config(container_id).await?;
+    Ok(parse_container_mounts(&config))
+}
+
+// Tauri command to allocate a new volume on a storage and mount it in a container
+#[tauri::command]
+async fn add_container_mount(container_id: u32, index: u32, storage: String, size_gb: u32, mountpoint: String) -> Result<String, String> {
+    let arguments = serde_json::json!({ "container_id": container_id, "index": index, "storage": storage, "size_gb": size_gb, "mountpoint": mountpoint });
+    audited("add_container_mount", format!("ct:{}", container_id), arguments, async move {
+        if index > 255 {
+            return Err("Mount point index must be between 0 and 255".to_string());
+        }
+        if size_gb == 0 {
+            return Err("Mount point size must be at least 1 GB".to_string());
+        }
+        if !mountpoint.starts_with('/') || mountpoint.contains(',') {
+            return Err(format!("Mount path '{}' must be an absolute path", mountpoint));
+        }
+        if storage.is_empty() || !storage.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c)) {
+            return Err(format!("Invalid storage name '{}'", storage));
+        }
+
+        let mounts = list_container_mounts(container_id).await?;
+        if let Some(existing) = mounts.iter().find(|mount| mount.index == Some(index)) {
+            return Err(format!("mp{} is already used by {} at {}", index, existing.volume, existing.mountpoint));
+        }
+        if mounts.iter().any(|mount| mount.mountpoint == mountpoint) {
+            return Err(format!("Something is already mounted at {} in container {}", mountpoint, container_id));
+        }
+
+        let output = run_remote(&guest_host(container_id).await, &["pct", "set", &container_id.to_string(), &format!("--mp{}", index),
+                                &shell_escape(&format!("{}:{},mp={}", storage, size_gb, mountpoint))], *LONG_COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if output.status.success() {
+            Ok(format!("Added {} GB volume on {} as mp{} at {} in container {}", size_gb, storage, index, mountpoint, container_id))
+        } else {
+            Err(format!("Failed to add mount point to container {}: {}", container_id, String::from_utf8_lossy(&output.stderr)))
+        }
+    }).await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ContainerDns {
+    container_id: u32,
+    nameservers: Vec<String>,     // Empty means the container uses the host's resolvers
+    searchdomain: Option<String>,
+}
+
+// Helper function to read nameserver and searchdomain from `pct config` output
+fn parse_container_dns(config: &str, container_id: u32) -> ContainerDns {
+    let value = |key: &str| config_value(config, key).filter(|value| !value.is_empty());
+
+    ContainerDns {
+        container_id,
+        nameservers: value("nameserver")
+            .map(|servers| servers.split_whitespace().map(str::to_string).collect())
+            .unwrap_or_default(),
+        searchdomain: value("searchdomain"),
+    }
+}
+
+// Tauri command to get the DNS servers and search domain configured for a container
+#[tauri::command]
+async fn get_container_dns(container_id: u32) -> Result<ContainerDns, String> {
+    let config = pct_config(container_id).await?;
+    Ok(parse_container_dns(&config, container_id))
+}
+
+// Tauri command to set a container's DNS servers and search domain.
+// An empty list or search domain clears the setting so the container falls back to the host's.
+#[tauri::command]
+async fn set_container_dns(container_id: u32, nameservers: Vec<String>, searchdomain: Option<String>) -> Result<ContainerDns, String> {
+    let arguments = serde_json::json!({ "container_id": container_id, "nameservers": nameservers, "searchdomain": searchdomain });
+    audited("set_container_dns", format!("ct:{}", container_id), arguments, async move {
+        let nameservers: Vec<String> = nameservers.iter().map(|ns| ns.trim().to_string()).filter(|ns| !ns.is_empty()).collect();
+        if let Some(invalid) = nameservers.iter().find(|ns| ns.parse::<std::net::IpAddr>().is_err()) {
+            return Err(format!("Nameserver '{}' is not an IP address", invalid));
+        }
+        let searchdomain = searchdomain.map(|domain| domain.trim().to_string()).filter(|domain| !domain.is_empty());
+        if let Some(domain) = &searchdomain {
+            for part in domain.split_whitespace() {
+                validate_guest_name(part).map_err(|_| format!("Search domain '{}' is not a valid domain name", part))?;
+            }
+        }
+
+        let id_arg = container_id.to_string();
+        let nameserver_arg = shell_escape(&nameservers.join(" "));
+        let searchdomain_arg = searchdomain.as_deref().map(shell_escape);
+        let mut args = vec!["pct", "set", &id_arg];
+        let mut deletions = Vec::new();
+        if nameservers.is_empty() {
+            deletions.push("nameserver");
+        } else {
+            args.extend(["--nameserver", nameserver_arg.as_str()]);
+        }
+        match &searchdomain_arg {
+            Some(domain) => args.extend(["--searchdomain", domain.as_str()]),
+            None => deletions.push("searchdomain"),
+        }
+        let deletions = deletions.join(",");
+        if !deletions.is_empty() {
+            args.extend(["--delete", deletions.as_str()]);
+        }
+
+        let output = run_remote(&guest_host(container_id).await, &args, *COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if !output.status.success() {
+            return Err(format!("Failed to set DNS for container {}: {}", container_id, String::from_utf8_lossy(&output.stderr)));
+        }
+
+        remove_from_cache(&format!("container_details_{}", container_id));
+        get_container_dns(container_id).await
+    }).await
+}
+
+// One netN interface of a container
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct NetConfig {
+    #[serde(default)]
+    index: u32,             // N in netN; ignored by set_container_net, which takes it separately
+    name: String,           // Interface name inside the container, e.g. "eth0"
+    bridge: Option<String>,
+    ip: Option<String>,     // CIDR address, "dhcp" or "manual"
+    gw: Option<String>,
+    hwaddr: Option<String>,
+    vlan: Option<u32>,      // The "tag" option
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ContainerNetUpdate {
+    interfaces: Vec<NetConfig>,
+    warning: Option<String>,
+}
+
+// Helper function to parse the netN lines from `pct config` output
+fn parse_container_net(config: &str) -> Vec<NetConfig> {
+    config.lines()
+        .take_while(|line| !line.starts_with('['))
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let index = key.strip_prefix("net")?.parse().ok()?;
+            let options: HashMap<&str, &str> = value.trim().split(',').filter_map(|part| part.split_once('=')).collect();
+            let option = |key: &str| options.get(key).map(|value| value.to_string());
+            Some(NetConfig {
+                index,
+                name: option("name").unwrap_or_default(),
+                bridge: option("bridge"),
+                ip: option("ip"),
+                gw: option("gw"),
+                hwaddr: option("hwaddr"),
+                vlan: options.get("tag").and_then(|tag| tag.parse().ok()),
+            })
+        })
+        .collect()
+}
+
+// Helper function to check an address in CIDR notation, e.g. "192.168.1.20/24"
+fn validate_cidr(cidr: &str) -> Result<(), String> {
+    let (address, prefix) = cidr.split_once('/').ok_or_else(|| format!("'{}' is not in CIDR notation (address/prefix)", cidr))?;
+    let address: std::net::IpAddr = address.parse().map_err(|_| format!("'{}' is not a valid IP address", address))?;
+    let max_prefix = if address.is_ipv4() { 32 } else { 128 };
+    match prefix.parse::<u8>() {
+        Ok(prefix) if prefix <= max_prefix => Ok(()),
+        _ => Err(format!("'{}' is not a valid prefix length for {}", prefix, address)),
+    }
+}
+
+// Tauri command to get a container's network interfaces
+#[tauri::command]
+async fn get_container_net(container_id: u32) -> Result<Vec<NetConfig>, String> {
+    let config = pct_config(container_id).await?;
+    Ok(parse_container_net(&config))
+}
+
+// Tauri command to add or replace the netN interface of a container
+#[tauri::command]
+async fn set_container_net(container_id: u32, index: u32, config: NetConfig) -> Result<ContainerNetUpdate, String> {
+    let arguments = serde_json::json!({ "container_id": container_id, "index": index, "config": config });
+    audited("set_container_net", format!("ct:{}", container_id), arguments, async move {
+        if index > 31 {
+            return Err("Interface index must be between 0 and 31".to_string());
+        }
+        let name_ok = !config.name.is_empty() && config.name.len() <= 15
+            && config.name.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c));
+        if !name_ok {
+            return Err(format!("Invalid interface name '{}'", config.name));
+        }
+        if let Some(bridge) = &config.bridge {
+            if bridge.is_empty() || !bridge.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c)) {
+                return Err(format!("Invalid bridge name '{}'", bridge));
+            }
+        }
+        if let Some(ip) = config.ip.as_deref().filter(|ip| *ip != "dhcp" && *ip != "manual") {
+            validate_cidr(ip)?;
+        }
+        if let Some(gw) = &config.gw {
+            gw.parse::<std::net::IpAddr>().map_err(|_| format!("Gateway '{}' is not a valid IP address", gw))?;
+        }
+        if let Some(hwaddr) = &config.hwaddr {
+            let octets: Vec<&str> = hwaddr.split(':').collect();
+            if octets.len() != 6 || !octets.iter().all(|octet| octet.len() == 2 && octet.chars().all(|c| c.is_ascii_hexdigit())) {
+                return Err(format!("Invalid MAC address '{}'", hwaddr));
+            }
+        }
+        if config.vlan.map_or(false, |vlan| !(1..=4094).contains(&vlan)) {
+            return Err("VLAN tag must be between 1 and 4094".to_string());
+        }
+
+        let mut options = vec![format!("name={}", config.name)];
+        options.extend(config.bridge.iter().map(|bridge| format!("bridge={}", bridge)));
+        options.extend(config.ip.iter().map(|ip| format!("ip={}", ip)));
+        options.extend(config.gw.iter().map(|gw| format!("gw={}", gw)));
+        options.extend(config.hwaddr.iter().map(|hwaddr| format!("hwaddr={}", hwaddr)));
+        options.extend(config.vlan.iter().map(|vlan| format!("tag={}", vlan)));
+
+        let output = run_remote(&guest_host(container_id).await, &["pct", "set", &container_id.to_string(), &format!("--net{}", index), &shell_escape(&options.join(","))], *COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+        if !output.status.success() {
+            return Err(format!("Failed to set net{} of container {}: {}", index, container_id, String::from_utf8_lossy(&output.stderr)));
+        }
+
+        remove_from_cache(&format!("container_details_{}", container_id));
+        let running = transport().guest_status(container_id, false).await.map(|status| status.status == "Running").unwrap_or(false);
+        Ok(ContainerNetUpdate {
+            interfaces: get_container_net(container_id).await?,
+            warning: running.then(|| format!("Container {} is running; net{} was changed live, so connections to its services may drop", container_id, index)),
+        })
+    }).await
+}
+
+// An IPv4/IPv6 address claimed by more than one container, or answered on the network by a device
+// that isn't the container configured with it
+#[derive(Debug, Serialize, Deserialize)]
+struct IpConflict {
+    address: String,
+    conflicting_ids: Vec<u32>,
+    unknown_macs: Vec<String>, // From the ARP probe: MACs answering for the address that no container owns
+}
+
+// Helper function to parse `ip neigh show` lines ("<addr> dev <if> lladdr <mac> <STATE>") into address -> MAC
+fn parse_ip_neigh(output: &str) -> HashMap<String, String> {
+    output.lines()
+        .filter(|line| !line.contains("FAILED") && !line.contains("INCOMPLETE"))
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let mac = fields.iter().position(|field| *field == "lladdr").and_then(|i| fields.get(i + 1))?;
+            Some((fields.first()?.to_string(), mac.to_lowercase()))
+        })
+        .collect()
+}
+
+// Tauri command to find static addresses configured on more than one container. With `arp_probe`,
+// the host also pings every static address and compares the MAC that answers against the
+// containers' configured hwaddr, which catches devices outside Proxmox squatting on a guest's IP.
+#[tauri::command]
+async fn detect_ip_conflicts(arp_probe: Option<bool>) -> Result<Vec<IpConflict>, String> {
+    let container_ids = get_existing_containers().await?;
+    let lookups: Vec<_> = container_ids.into_iter()
+        .map(|id| (id, tokio::spawn(get_container_net(id))))
+        .collect();
+
+    // address -> (container id, configured MAC)
+    let mut owners: std::collections::BTreeMap<String, Vec<(u32, Option<String>)>> = std::collections::BTreeMap::new();
+    for (id, lookup) in lookups {
+        let interfaces = match lookup.await.map_err(|e| e.to_string()).and_then(|result| result) {
+            Ok(interfaces) => interfaces,
+            Err(e) => {
+                tracing::warn!("Skipping container {} in IP conflict check: {}", id, e);
+                continue;
+            }
+        };
+        for interface in interfaces {
+            // dhcp/manual addresses aren't known from the config
+            if let Some((address, _)) = interface.ip.as_deref().and_then(|ip| ip.split_once('/')) {
+                let entry = owners.entry(address.to_string()).or_default();
+                if !entry.iter().any(|(owner, _)| *owner == id) {
+                    entry.push((id, interface.hwaddr.map(|mac| mac.to_lowercase())));
+                }
+            }
+        }
+    }
+
+    let neighbours = if arp_probe.unwrap_or(false) && !owners.is_empty() {
+        let addresses: Vec<String> = owners.keys().map(|address| shell_escape(address)).collect();
+        let script = format!(
+            "for a in {}; do ping -c 1 -W 1 \"$a\" >/dev/null 2>&1 & done; wait; ip neigh show",
+            addresses.join(" ")
+        );
+        let output = run_remote("proxmox", &[&script], *COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+        parse_ip_neigh(&String::from_utf8_lossy(&output.stdout))
+    } else {
+        HashMap::new()
+    };
+
+    Ok(owners.into_iter()
+        .filter_map(|(address, claimants)| {
+            let unknown_macs: Vec<String> = neighbours.get(&address)
+                .filter(|mac| !claimants.iter().any(|(_, hwaddr)| hwaddr.as_ref() == Some(*mac)))
+                // Without a configured MAC there is nothing to compare against
+                .filter(|_| claimants.iter().all(|(_, hwaddr)| hwaddr.is_some()))
+                .cloned()
+                .into_iter()
+                .collect();
+            (claimants.len() > 1 || !unknown_macs.is_empty()).then(|| IpConflict {
+                address,
+                conflicting_ids: claimants.into_iter().map(|(id, _)| id).collect(),
+                unknown_macs,
+            })
+        })
+        .collect())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FileTransfer {
+    local_path: String,
+    remote_path: String,
+    bytes: u64,
+}
+
+// Helper function to pick the ssh target scp talks to: the VM itself, the node that owns a container, or the host
+async fn transfer_target(container_id: Option<u32>, vm_id: Option<u32>) -> String {
+    match (container_id, vm_id) {
+        (Some(cid), _) => guest_host(cid).await,
+        (None, Some(vid)) => vm_ssh_target(vid),
+        (None, None) => "proxmox".to_string(),
+    }
+}
+
+// Helper function to build the "target:path" argument of run_scp with the path quoted for the remote shell
+async fn scp_remote_path(container_id: Option<u32>, vm_id: Option<u32>, path: &str) -> String {
+    format!("{}:{}", transfer_target(container_id, vm_id).await, shell_escape(path))
+}
+
+// Helper function to get a temporary path on a container's node for staging its transfers
+fn staging_path(container_id: u32) -> String {
+    format!("/tmp/proxmox-admin-transfer-{}-{}", container_id, Utc::now().timestamp_millis())
+}
+
+// Tauri command to copy a file from the host, a container or a VM to this machine.
+// The copy lands in a temporary file next to local_path and only replaces it once complete.
+#[tauri::command]
+async fn download_guest_file(container_id: Option<u32>, vm_id: Option<u32>, remote_path: String, local_path: String) -> Result<FileTransfer, String> {
+    if !remote_path.starts_with('/') {
+        return Err(format!("Remote path '{}' must be absolute", remote_path));
+    }
+
+    // Creating the temporary file up front proves the directory is writable before anything is copied
+    let local = std::path::Path::new(&local_path);
+    if local.is_dir() {
+        return Err(format!("{} is a directory; give the full path of the file to create", local_path));
+    }
+    let partial_path = format!("{}.part-{}", local_path, Utc::now().timestamp_millis());
+    let partial = std::path::Path::new(&partial_path);
+    std::fs::OpenOptions::new().write(true).create_new(true).open(partial)
+        .map_err(|e| format!("Cannot write to {}: {}", local_path, e))?;
+
+    let result = async {
+        let size_output = run_guest_shell(container_id, vm_id, &format!("stat -c %s {}", shell_escape(&remote_path)), *COMMAND_TIMEOUT).await?;
+        if !size_output.status.success() {
+            return Err(format!("Failed to stat {}: {}", remote_path, String::from_utf8_lossy(&size_output.stderr)));
+        }
+        let size_text = String::from_utf8_lossy(&size_output.stdout);
+        let size: u64 = size_text.trim().parse()
+            .map_err(|_| format!("Could not read the size of {} from stat output '{}'", remote_path, size_text.trim()))?;
+        let max_bytes = app_config().exec.max_transfer_bytes;
+        if size > max_bytes {
+            return Err(format!("{} is {} bytes, over the {} byte transfer limit", remote_path, size, max_bytes));
+        }
+
+        // Containers have no ssh of their own, so the file is pulled onto their node and copied from there
+        let staged = container_id.map(staging_path);
+        let source_path = match (container_id, &staged) {
+            (Some(cid), Some(staged)) => {
+                let output = run_remote(&guest_host(cid).await, &["pct", "pull", &cid.to_string(), &shell_escape(&remote_path), staged], *LONG_COMMAND_TIMEOUT)
+                    .await
+                    .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+                if !output.status.success() {
+                    return Err(format!("Failed to pull {} from container {}: {}", remote_path, cid, String::from_utf8_lossy(&output.stderr)));
+                }
+                staged.clone()
+            }
+            _ => remote_path.clone(),
+        };
+
+        let copied = run_scp(scp_remote_path(container_id, vm_id, &source_path).await, partial_path.clone(), *LONG_COMMAND_TIMEOUT).await;
+        if let Some(staged) = &staged {
+            let _ = run_remote(&transfer_target(container_id, vm_id).await, &["rm", "-f", staged], *COMMAND_TIMEOUT).await;
+        }
+
+        let output = copied.map_err(|e| format!("Failed to copy {}: {}", remote_path, e))?;
+        if !output.status.success() {
+            return Err(format!("Failed to copy {}: {}", remote_path, String::from_utf8_lossy(&output.stderr)));
+        }
+        std::fs::rename(partial, local).map_err(|e| format!("Failed to move the download into {}: {}", local_path, e))
+    }.await;
+
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(partial);
+        return Err(e);
+    }
+
+    let bytes = std::fs::metadata(local)
+        .map(|metadata| metadata.len())
+        .map_err(|e| format!("Failed to read {}: {}", local_path, e))?;
+
+    Ok(FileTransfer {
+        local_path,
+        remote_path,
+        bytes,
+    })
+}
+
+// Tauri command to copy a file from this machine to the host, a container or a VM, backing up any file it replaces
+#[tauri::command]
+async fn upload_guest_file(container_id: Option<u32>, vm_id: Option<u32>, local_path: String, remote_path: String, mode: Option<String>) -> Result<FileTransfer, String> {
+    let arguments = serde_json::json!({ "container_id": container_id, "vm_id": vm_id, "local_path": local_path, "remote_path": remote_path, "mode": mode });
+    audited("upload_guest_file", audit_target(container_id, vm_id), arguments, async move {
+        if !remote_path.starts_with('/') {
+            return Err(format!("Remote path '{}' must be absolute", remote_path));
+        }
+        if let Some(mode) = &mode {
+            if !(3..=4).contains(&mode.len()) || !mode.chars().all(|c| ('0'..='7').contains(&c)) {
+                return Err(format!("Invalid file mode '{}', expected octal like 0644", mode));
+            }
+        }
+
+        let metadata = std::fs::metadata(&local_path)
+            .map_err(|e| format!("Cannot read {}: {}", local_path, e))?;
+        if !metadata.is_file() {
+            return Err(format!("{} is not a regular file", local_path));
+        }
+        let bytes = metadata.len();
+        let max_bytes = app_config().exec.max_transfer_bytes;
+        if bytes > max_bytes {
+            return Err(format!("{} is {} bytes, over the {} byte transfer limit", local_path, bytes, max_bytes));
+        }
+
+        let escaped_path = shell_escape(&remote_path);
+        let backup = run_guest_shell(container_id, vm_id, &format!("if [ -e {0} ]; then cp -p {0} {1}; fi", escaped_path, shell_escape(&format!("{}.backup", remote_path))), *COMMAND_TIMEOUT).await?;
+        if !backup.status.success() {
+            return Err(format!("Failed to back up {}: {}", remote_path, String::from_utf8_lossy(&backup.stderr)));
+        }
+
+        // Containers have no ssh of their own, so the file is staged on their node and pushed in from there
+        let staged = container_id.map(staging_path);
+        let destination = staged.clone().unwrap_or_else(|| remote_path.clone());
+        let output = run_scp(local_path.clone(), scp_remote_path(container_id, vm_id, &destination).await, *LONG_COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to copy {}: {}", local_path, e))?;
+        if !output.status.success() {
+            return Err(format!("Failed to copy {}: {}", local_path, String::from_utf8_lossy(&output.stderr)));
+        }
+
+        match (container_id, &staged) {
+            (Some(cid), Some(staged)) => {
+                let host = guest_host(cid).await;
+                let id_arg = cid.to_string();
+                let mut args = vec!["pct", "push", &id_arg, staged, &escaped_path];
+                if let Some(mode) = &mode {
+                    args.extend(["--perms", mode.as_str()]);
+                }
+                let pushed = run_remote(&host, &args, *LONG_COMMAND_TIMEOUT).await;
+                let _ = run_remote(&host, &["rm", "-f", staged], *COMMAND_TIMEOUT).await;
+
+                let output = pushed.map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+                if !output.status.success() {
+                    return Err(format!("Failed to push {} into container {}: {}", remote_path, cid, String::from_utf8_lossy(&output.stderr)));
+                }
+            }
+            _ => {
+                if let Some(mode) = &mode {
+                    let output = run_guest_shell(container_id, vm_id, &format!("chmod {} {}", mode, escaped_path), *COMMAND_TIMEOUT).await?;
+                    if !output.status.success() {
+                        return Err(format!("Failed to set mode of {}: {}", remote_path, String::from_utf8_lossy(&output.stderr)));
+                    }
+                }
+            }
+        }
+
+        Ok(FileTransfer {
+            local_path,
+            remote_path,
+            bytes,
+        })
+    }).await
+}
+
+// Helper function to get the ssh target for commands inside a VM: its ssh alias from the guest
+// metadata, or the Proxmox host when none is configured
+fn vm_ssh_target(vm_id: u32) -> String {
+    guest_metadata_override(vm_id).ssh_alias
+        .or_else(|| DEFAULT_VM_SSH_ALIASES.iter()
+            .find(|(id, _)| *id == vm_id)
+            .map(|(_, alias)| alias.to_string()))
+        .unwrap_or_else(|| "proxmox".to_string())
+}
+
+// Where a command for the host or a guest runs: the ssh target, plus `pct exec <id> --` for containers
+#[derive(Debug, Clone)]
+struct Target {
+    ssh: String,
+    prefix: Vec<String>,
+}
+
+impl Target {
+    fn args<'a>(&'a self, args: &[&'a str]) -> Vec<&'a str> {
+        self.prefix.iter().map(String::as_str).chain(args.iter().copied()).collect()
+    }
+
+    async fn run(&self, args: &[&str], timeout: std::time::Duration) -> Result<std::process::Output, AppError> {
+        run_remote(&self.ssh, &self.args(args), timeout).await
+    }
+}
+
+// Helper function to resolve where a host/container/VM command should run, checking up front that
+// the ssh target answers so a stale alias fails with a clear error instead of deep inside a command.
+// Results are cached briefly so a burst of commands costs one probe.
+async fn resolve_target(container_id: Option<u32>, vm_id: Option<u32>) -> Result<Target, AppError> {
+    let target = match (container_id, vm_id) {
+        (Some(cid), _) => Target {
+            ssh: guest_host(cid).await,
+            prefix: vec!["pct".to_string(), "exec".to_string(), cid.to_string(), "--".to_string()],
+        },
+        (None, Some(vid)) => Target { ssh: vm_ssh_target(vid), prefix: Vec::new() },
+        (None, None) => Target { ssh: "proxmox".to_string(), prefix: Vec::new() },
+    };
+
+    let cache_key = format!("reachable_{}", target.ssh);
+    let failure = match get_from_cache(&cache_key).filter(|_| is_cache_valid_with_duration(&cache_key, 30)) {
+        Some(cached) => cached,
+        None => {
+            let failure = match run_remote(&target.ssh, &["true"], std::time::Duration::from_secs(10)).await {
+                Ok(output) if output.status.success() => String::new(),
+                Ok(output) => String::from_utf8_lossy(&output.stderr).trim().to_string(),
+                Err(e) => e.to_string(),
+            };
+            store_in_cache(&cache_key, &failure);
+            failure
+        }
+    };
+
+    if failure.is_empty() {
+        Ok(target)
+    } else {
+        Err(AppError::Unreachable { target: target.ssh, reason: failure })
+    }
+}
+
+// Helper function to quote a string as a single POSIX shell word
+fn shell_escape(value: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "-_./=:@,+%".contains(c);
+    if !value.is_empty() && value.chars().all(is_safe) {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+// Helper function to build ssh arguments that run a shell command on the host, in a container or on a VM
+fn guest_shell_args(container_id: Option<u32>, vm_id: Option<u32>, command: &str) -> Vec<String> {
+    match container_id {
+        Some(cid) => vec![
+            "proxmox".to_string(),
+            format!("pct exec {} -- sh -c {}", cid, shell_escape(command)),
+        ],
+        None => vec![vm_id.map(vm_ssh_target).unwrap_or_else(|| "proxmox".to_string()), command.to_string()],
+    }
+}
+
+// Helper function to build guest_shell_args with containers entered from the node that owns them
+async fn guest_shell_command(container_id: Option<u32>, vm_id: Option<u32>, command: &str) -> Vec<String> {
+    let mut args = guest_shell_args(container_id, vm_id, command);
+    if let Some(cid) = container_id {
+        args[0] = guest_host(cid).await;
+    }
+    args
+}
+
+// Helper function to run a shell command on the host, in a container or on a VM
+async fn run_guest_shell(container_id: Option<u32>, vm_id: Option<u32>, command: &str, timeout: std::time::Duration) -> Result<std::process::Output, String> {
+    let args = guest_shell_command(container_id, vm_id, command).await;
+    let remote_args: Vec<&str> = args[1..].iter().map(String::as_str).collect();
+    run_remote(&args[0], &remote_args, timeout)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))
+}
+
+impl Default for SystemHealth {
+    fn default() -> Self {
+        SystemHealth {
+            disk_usage: 0.0,
+            memory_usage: 0.0,
+            cpu_load: 0.0,
+            network_status: "Unknown".to_string(),
+            uptime: "Unknown".to_string(),
+        }
+    }
+}
+
+// Tauri command to get system overview
+#[derive(Debug, Serialize, Deserialize)]
+struct ContainerDetail {
+    id: u32,
+    name: String,
+    status: String,
+    os_info: OsInfo,
+    running_processes: Vec<ProcessInfo>,
+    installed_binaries: Vec<BinaryInfo>,
+    systemd_services: Vec<ServiceInfo>,
+    configs: Vec<ConfigInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OsInfo {
+    distribution: String,
+    version: String,
+    kernel: String,
+    architecture: String,
+    package_manager: String,
+    init_system: InitSystem,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ProcessInfo {
+    pid: String,
+    name: String,
+    cpu: String,
+    memory: String,
+    command: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AiSuggestion {
+    suggestion: String,
+    explanation: String,
+    confidence: f32,
+}
+
+// Comprehensive AI ecosystem analysis structures
+#[derive(Debug, Serialize, Deserialize)]
+struct ProxmoxEcosystemScan {
+    timestamp: DateTime<Utc>,
+    host_analysis: HostAnalysis,
+    container_analysis: Vec<ContainerAnalysis>,
+    vm_analysis: Vec<VmAnalysis>,
+    network_analysis: NetworkAnalysis,
+    storage_analysis: StorageAnalysis,
+    security_analysis: SecurityAnalysis,
+    performance_analysis: PerformanceAnalysis,
+    optimization_recommendations: Vec<OptimizationRecommendation>,
+    overall_health_score: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HostAnalysis {
+    cpu_optimization: Vec<String>,
+    memory_optimization: Vec<String>,
+    network_optimization: Vec<String>,
+    storage_optimization: Vec<String>,
+    security_issues: Vec<String>,
+    configuration_recommendations: Vec<ConfigRecommendation>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ContainerAnalysis {
+    id: u32,
+    name: String,
+    health_score: f32,
+    resource_usage: ResourceUsage,
+    security_assessment: SecurityAssessment,
+    configuration_issues: Vec<String>,
+    optimization_opportunities: Vec<String>,
+    recommended_configs: Vec<ConfigRecommendation>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct VmAnalysis {
+    id: u32,
+    name: String,
+    health_score: f32,
+    resource_allocation: ResourceAllocation,
+    performance_metrics: VmPerformanceMetrics,
+    configuration_recommendations: Vec<ConfigRecommendation>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct NetworkAnalysis {
+    overall_health: Option<f32>, // None when nothing assessed the network (e.g. the AI was unavailable)
+    bandwidth_utilization: f32,
+    latency_issues: Vec<String>,
+    security_concerns: Vec<String>,
+    optimization_recommendations: Vec<String>,
+    firewall_recommendations: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StorageAnalysis {
+    overall_health: f32,
+    usage_patterns: Vec<String>,
+    performance_bottlenecks: Vec<String>,
+    redundancy_assessment: String,
+    optimization_recommendations: Vec<String>,
+    backup_recommendations: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SecurityAnalysis {
+    overall_score: f32,
+    vulnerabilities: Vec<SecurityVulnerability>,
+    access_control_issues: Vec<String>,
+    network_security_gaps: Vec<String>,
+    compliance_recommendations: Vec<String>,
+    hardening_suggestions: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PerformanceAnalysis {
+    overall_score: f32,
+    cpu_bottlenecks: Vec<String>,
+    memory_bottlenecks: Vec<String>,
+    io_bottlenecks: Vec<String>,
+    network_bottlenecks: Vec<String>,
+    scaling_recommendations: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OptimizationRecommendation {
+    priority: String, // High, Medium, Low
+    category: String, // Performance, Security, Cost, Reliability
+    title: String,
+    description: String,
+    implementation_steps: Vec<String>,
+    estimated_impact: String,
+    configuration: Option<String>,
+    script: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ConfigRecommendation {
+    file_path: String,
+    section: String,
+    current_value: Option<String>,
+    recommended_value: String,
+    reason: String,
+    impact: String,
+    backup_required: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ResourceUsage {
+    cpu_percent: f32,
+    memory_percent: f32,
+    disk_usage: f32,
+    io: Option<IoStats>,
+    #[serde(default)]
+    pressure: Option<PressureInfo>,
+    efficiency_score: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SecurityAssessment {
+    score: f32,
+    open_ports: Vec<u16>,
+    running_services: Vec<String>,
+    user_permissions: Vec<String>,
+    security_updates_needed: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ResourceAllocation {
+    cpu_cores: u32,
+    memory_gb: f32,
+    disk_gb: f32,
+    utilization_efficiency: f32,
+    recommended_allocation: ResourceRecommendation,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct VmPerformanceMetrics {
+    cpu_usage: f32,
+    memory_usage: f32,
+    disk_io: String,
+    network_io: String,
+    performance_score: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SecurityVulnerability {
+    severity: String,
+    description: String,
+    affected_component: String,
+    remediation: String,
+    cve_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ResourceRecommendation {
+    cpu_cores: u32,
+    memory_gb: f32,
+    disk_gb: f32,
+    reasoning: String,
+}
+
+// Security signals that count against a guest's health score
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct SecurityFlags {
+    pending_security_updates: usize,
+    unexpected_open_ports: usize,
+}
+
+// Helper function to score a guest's health from 0 (bad) to 100 (healthy).
+// Starts at 100 and subtracts:
+//   - CPU, memory and disk usage: nothing up to 80%, then rising linearly to
+//     15 (CPU), 20 (memory) and 25 (disk) points at 100% - a full disk hurts most
+//   - services that are enabled but not active: 10 points each, at most 30
+//   - pending security updates: 3 points each, at most 20
+//   - unexpected listening ports: 5 points each, at most 10
+fn compute_health_score(resource_usage: &ResourceUsage, service_states: &[ServiceInfo], security_flags: &SecurityFlags) -> f32 {
+    let usage_penalty = |percent: f32, max_penalty: f32| {
+        ((percent - 80.0) / 20.0).clamp(0.0, 1.0) * max_penalty
+    };
+
+    let resource_penalty = usage_penalty(resource_usage.cpu_percent, 15.0)
+        + usage_penalty(resource_usage.memory_percent, 20.0)
+        + usage_penalty(resource_usage.disk_usage, 25.0);
+
+    let inactive_services = service_states.iter().filter(|service| service.enabled && !service.active).count();
+    let service_penalty = (inactive_services as f32 * 10.0).min(30.0);
+
+    let security_penalty = (security_flags.pending_security_updates as f32 * 3.0).min(20.0)
+        + (security_flags.unexpected_open_ports as f32 * 5.0).min(10.0);
+
+    (100.0 - resource_penalty - service_penalty - security_penalty).clamp(0.0, 100.0)
+}
+
+// Container detailed management commands
+#[tauri::command]
+async fn get_container_details(container_id: u32) -> Result<ContainerDetail, String> {
+    let cache_key = format!("container_details_{}", container_id);
+    
+    // Check if we have valid cached data
+    if is_cache_valid_with_duration(&cache_key, *CONTAINER_CACHE_DURATION) {
+        if let Some(cached_data) = get_from_cache(&cache_key) {
+            if let Ok(container_detail) = serde_json::from_str::<ContainerDetail>(&cached_data) {
+                return Ok(container_detail);
+            }
         }
-    } else {
-        false
+    }
+    
+    let container_name = get_container_name(container_id).await?;
+    
+    // The sections are independent, so fetch them side by side; a section that fails (no systemd,
+    // no ps, ...) comes back empty instead of failing the whole view
+    let os_info = tokio::spawn(get_container_os_info(container_id));
+    let running_processes = tokio::spawn(get_container_processes(container_id));
+    let installed_binaries = tokio::spawn(get_container_binaries(container_id));
+    let systemd_services = tokio::spawn(get_container_services(container_id));
+    let configs = tokio::spawn(get_container_configs(container_id));
+    let status_info = get_container_status(container_id).await?;
+
+    let os_info = os_info.await.ok().and_then(Result::ok).unwrap_or_else(|| OsInfo {
+        distribution: "Unknown".to_string(),
+        version: "Unknown".to_string(),
+        kernel: "Unknown".to_string(),
+        architecture: "Unknown".to_string(),
+        package_manager: "Unknown".to_string(),
+        init_system: InitSystem::Unknown,
+    });
+    let running_processes = running_processes.await.ok().and_then(Result::ok).unwrap_or_default();
+    let installed_binaries = installed_binaries.await.ok().and_then(Result::ok).unwrap_or_default();
+    let systemd_services = systemd_services.await.ok().and_then(Result::ok).unwrap_or_default();
+    let configs = configs.await.ok().and_then(Result::ok).unwrap_or_default();
+    
+    let container_detail = ContainerDetail {
+        id: container_id,
+        name: container_name,
+        status: status_info.status,
+        os_info,
+        running_processes,
+        installed_binaries,
+        systemd_services,
+        configs,
     };
     
-    Ok(BinaryInfo {
-        name: binary_name,
-        path,
-        version,
-        exists,
-        executable,
-        container_id,
-        vm_id,
-    })
+    // Store in cache
+    if let Ok(serialized) = serde_json::to_string(&container_detail) {
+        store_in_cache(&cache_key, &serialized);
+    }
+    
+    Ok(container_detail)
 }
 
-// Tauri command to check config file
 #[tauri::command]
-async fn check_config(config_path: String, container_id: Option<u32>, vm_id: Option<u32>) -> Result<ConfigInfo, String> {
-    let target = get_ssh_target(container_id, vm_id);
+async fn get_container_os_info(container_id: u32) -> Result<OsInfo, String> {
+    let host = guest_host(container_id).await;
+    let output = run_remote(&host, &[&format!("pct exec {} -- sh -c 'cat /etc/os-release 2>/dev/null || cat /etc/lsb-release 2>/dev/null || echo \"ID=unknown\"'", container_id)], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to get OS info: {}", e))?
+        .stdout;
     
-    // Check if file exists
-    let exists_output = Command::new("ssh")
-        .args([&target, "test", "-f", &config_path])
-        .output()
-        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    let os_release = String::from_utf8_lossy(&output);
     
-    let exists = exists_output.status.success();
+    let mut distribution = "Unknown".to_string();
+    let mut version = "Unknown".to_string();
     
-    let (readable, writable, size, modified) = if exists {
-        // Check readable
-        let readable_output = Command::new("ssh")
-            .args([&target, "test", "-r", &config_path])
-            .output()
-            .unwrap_or_else(|_| std::process::Output {
-                status: std::process::ExitStatus::from_raw(1),
-                stdout: Vec::new(),
-                stderr: Vec::new(),
-            });
-        let readable = readable_output.status.success();
-        
-        // Check writable
-        let writable_output = Command::new("ssh")
-            .args([&target, "test", "-w", &config_path])
-            .output()
-            .unwrap_or_else(|_| std::process::Output {
-                status: std::process::ExitStatus::from_raw(1),
-                stdout: Vec::new(),
-                stderr: Vec::new(),
-            });
-        let writable = writable_output.status.success();
-        
-        // Get file stats
-        let stat_output = Command::new("ssh")
-            .args([&target, "stat", "-c", "%s %Y", &config_path])
-            .output()
-            .unwrap_or_else(|_| std::process::Output {
-                status: std::process::ExitStatus::from_raw(1),
-                stdout: Vec::new(),
-                stderr: Vec::new(),
-            });
-        
-        let stat_str = String::from_utf8_lossy(&stat_output.stdout);
-        let parts: Vec<&str> = stat_str.trim().split_whitespace().collect();
-        
-        let size = parts.get(0).and_then(|s| s.parse().ok()).unwrap_or(0);
-        let modified = parts.get(1)
-            .and_then(|t| t.parse::<i64>().ok())
-            .map(|timestamp| {
-                DateTime::from_timestamp(timestamp, 0)
-                    .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
-                    .unwrap_or("Unknown".to_string())
-            })
-            .unwrap_or("Unknown".to_string());
-        
-        (readable, writable, size, modified)
-    } else {
-        (false, false, 0, "N/A".to_string())
-    };
+    for line in os_release.lines() {
+        if line.starts_with("ID=") {
+            distribution = line.split('=').nth(1).unwrap_or("Unknown").trim_matches('"').to_string();
+        }
+        if line.starts_with("VERSION_ID=") {
+            version = line.split('=').nth(1).unwrap_or("Unknown").trim_matches('"').to_string();
+        }
+    }
     
-    Ok(ConfigInfo {
-        name: config_path.split('/').last().unwrap_or("Unknown").to_string(),
-        path: config_path,
-        exists,
-        readable,
-        writable,
-        size,
-        modified,
-        container_id,
-        vm_id,
+    let kernel_output = run_remote(&host, &[&format!("pct exec {} -- uname -r", container_id)], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to get kernel info: {}", e))?
+        .stdout;
+    let kernel = String::from_utf8_lossy(&kernel_output).trim().to_string();
+    
+    let arch_output = run_remote(&host, &[&format!("pct exec {} -- uname -m", container_id)], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to get architecture: {}", e))?
+        .stdout;
+    let architecture = String::from_utf8_lossy(&arch_output).trim().to_string();
+    
+    let package_manager = match distribution.to_lowercase().as_str() {
+        "alpine" => "apk",
+        "ubuntu" | "debian" => "apt",
+        "centos" | "rhel" | "fedora" => "yum",
+        _ => "unknown",
+    }.to_string();
+    
+    Ok(OsInfo {
+        distribution,
+        version,
+        kernel,
+        architecture,
+        package_manager,
+        init_system: detect_init_system(Some(container_id), None).await,
     })
 }
 
-// Tauri command to read config file
 #[tauri::command]
-async fn read_config(config_path: String, container_id: Option<u32>, vm_id: Option<u32>) -> Result<String, String> {
-    let target = get_ssh_target(container_id, vm_id);
+async fn get_container_processes(container_id: u32) -> Result<Vec<ProcessInfo>, String> {
+    let output = run_remote(&guest_host(container_id).await, &[&format!("pct exec {} -- ps aux --no-headers", container_id)], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to get processes: {}", e))?
+        .stdout;
     
-    let output = Command::new("ssh")
-        .args([&target, "cat", &config_path])
-        .output()
-        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    let ps_output = String::from_utf8_lossy(&output);
+    let mut processes = Vec::new();
     
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(format!("Failed to read config file: {}", String::from_utf8_lossy(&output.stderr)))
+    for line in ps_output.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() >= 11 {
+            processes.push(ProcessInfo {
+                pid: parts[1].to_string(),
+                name: parts[10].to_string(),
+                cpu: parts[2].to_string(),
+                memory: parts[3].to_string(),
+                command: parts[10..].join(" "),
+            });
+        }
     }
+    
+    Ok(processes)
 }
 
-// Tauri command to write config file
 #[tauri::command]
-async fn write_config(config_path: String, content: String, container_id: Option<u32>, vm_id: Option<u32>) -> Result<String, String> {
-    let target = get_ssh_target(container_id, vm_id);
+async fn get_container_binaries(container_id: u32) -> Result<Vec<BinaryInfo>, String> {
+    let common_binaries = ["docker", "systemctl", "nginx", "apache2", "mysql", "postgres", "redis", "node", "python", "php", "java", "git", "curl", "wget", "vim", "nano"];
+
+    // One round trip: the loop runs inside the container and prints name|path|first version line per binary found
+    let script = format!(
+        "for b in {}; do p=$(command -v \"$b\" 2>/dev/null) || continue; \
+         v=$(\"$b\" --version 2>/dev/null </dev/null || \"$b\" -v 2>/dev/null </dev/null); \
+         echo \"$b|$p|$(printf '%s\\n' \"$v\" | head -n 1)\"; done",
+        common_binaries.join(" ")
+    );
+    let output = run_guest_shell(Some(container_id), None, &script, *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to check binaries: {}", e))?;
+
+    Ok(parse_binary_probe(&String::from_utf8_lossy(&output.stdout), container_id))
+}
+
+// Helper function to parse the name|path|version lines printed by the get_container_binaries script
+fn parse_binary_probe(output: &str, container_id: u32) -> Vec<BinaryInfo> {
+    output.lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '|');
+            let name = fields.next()?.trim();
+            let path = fields.next()?.trim();
+            if name.is_empty() || path.is_empty() {
+                return None;
+            }
+            let version = fields.next().map(str::trim).filter(|v| !v.is_empty()).unwrap_or("Unknown");
+
+            Some(BinaryInfo {
+                name: name.to_string(),
+                path: path.to_string(),
+                version: version.to_string(),
+                exists: true,
+                executable: true,
+                container_id: Some(container_id),
+                vm_id: None,
+            })
+        })
+        .collect()
+}
+
+#[tauri::command]
+async fn get_container_services(container_id: u32) -> Result<Vec<ServiceInfo>, String> {
+    if detect_init_system(Some(container_id), None).await == InitSystem::OpenRc {
+        let output = run_remote(&guest_host(container_id).await, &["pct", "exec", &container_id.to_string(), "--", "rc-status", "--all", "--nocolor"], *COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to get services: {}", e))?;
+        return Ok(parse_rc_status(&String::from_utf8_lossy(&output.stdout), Some(container_id), None));
+    }
+
+    let output = run_remote(&guest_host(container_id).await, &[&format!("pct exec {} -- systemctl list-units --type=service --no-pager --no-legend 2>/dev/null || echo 'No systemd'", container_id)], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to get services: {}", e))?
+        .stdout;
     
-    // Create a backup first
-    let _backup_output = Command::new("ssh")
-        .args([&target, "cp", &config_path, &format!("{}.backup", config_path)])
-        .output();
+    let services_output = String::from_utf8_lossy(&output);
+    let mut services = Vec::new();
     
-    // Write the new content
-    let output = Command::new("ssh")
-        .args([&target, "tee", &config_path])
-        .stdin(std::process::Stdio::piped())
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped())
-        .spawn();
+    if services_output.trim() == "No systemd" {
+        return Ok(services);
+    }
     
-    match output {
-        Ok(mut child) => {
-            if let Some(stdin) = child.stdin.take() {
-                use std::io::Write;
-                let mut stdin = stdin;
-                stdin.write_all(content.as_bytes()).map_err(|e| format!("Failed to write to stdin: {}", e))?;
-            }
-            
-            let output = child.wait_with_output().map_err(|e| format!("Failed to wait for command: {}", e))?;
+    for line in services_output.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() >= 4 {
+            let name = parts[0].replace(".service", "");
+            let active = parts[2] == "active";
+            let status = parts[3].to_string();
             
-            if output.status.success() {
-                Ok(format!("Config file {} updated successfully", config_path))
-            } else {
-                Err(format!("Failed to write config file: {}", String::from_utf8_lossy(&output.stderr)))
+            services.push(ServiceInfo {
+                name,
+                status,
+                enabled: true,
+                active,
+                description: parts[4..].join(" "),
+                container_id: Some(container_id),
+                vm_id: None,
+            });
+        }
+    }
+    
+    Ok(services)
+}
+
+#[tauri::command]
+async fn get_container_configs(container_id: u32) -> Result<Vec<ConfigInfo>, String> {
+    let common_configs = [
+        "/etc/nginx/nginx.conf",
+        "/etc/apache2/apache2.conf",
+        "/etc/mysql/my.cnf",
+        "/etc/redis/redis.conf",
+        "/etc/ssh/sshd_config",
+        "/etc/hosts",
+        "/etc/resolv.conf",
+        "/etc/fstab",
+    ];
+    
+    let mut configs = Vec::new();
+    
+    for config_path in &common_configs {
+        let output = run_remote(&guest_host(container_id).await, &[&format!("pct exec {} -- ls -la {} 2>/dev/null", container_id, config_path)], *COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to check config {}: {}", config_path, e))?
+            .stdout;
+        
+        let ls_output = String::from_utf8_lossy(&output).trim().to_string();
+        if !ls_output.is_empty() && !ls_output.contains("No such file") {
+            let parts: Vec<&str> = ls_output.split_whitespace().collect();
+            if parts.len() >= 9 {
+                configs.push(ConfigInfo {
+                    name: config_path.split('/').last().unwrap_or(config_path).to_string(),
+                    path: config_path.to_string(),
+                    exists: true,
+                    readable: true,
+                    writable: parts[0].chars().nth(2) == Some('w'),
+                    size: parts[4].parse().unwrap_or(0),
+                    modified: format!("{} {} {}", parts[5], parts[6], parts[7]),
+                    container_id: Some(container_id),
+                    vm_id: None,
+                });
             }
         }
-        Err(e) => Err(format!("Failed to spawn command: {}", e))
     }
+    
+    Ok(configs)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct VersionMismatch {
+    name: String,
+    version_a: String,
+    version_b: String,
+}
+
+// Items found in only one of two containers, plus the shared binaries whose versions differ
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct ContainerDiff {
+    container_a: u32,
+    container_b: u32,
+    binaries_only_in_a: Vec<String>,
+    binaries_only_in_b: Vec<String>,
+    version_mismatches: Vec<VersionMismatch>,
+    services_only_in_a: Vec<String>,
+    services_only_in_b: Vec<String>,
+    configs_only_in_a: Vec<String>,
+    configs_only_in_b: Vec<String>,
+}
+
+// The listings compare_containers diffs for one container
+struct ContainerInventory {
+    container_id: u32,
+    binaries: Vec<BinaryInfo>,
+    services: Vec<ServiceInfo>,
+    configs: Vec<ConfigInfo>,
 }
 
-// Helper functions for maintenance
-async fn get_all_services() -> Result<Vec<ServiceInfo>, String> {
-    let mut services = Vec::new();
-    
-    // Define services for each container/VM
-    let service_definitions = [
-        // Proxmox host services
-        ("nginx", None, None),
-        ("docker", None, None),
-        ("ssh", None, None),
-        
-        // Container-specific services (these would be checked inside containers)
-        ("sonarr", Some(214), None),
-        ("radarr", Some(215), None),
-        ("prowlarr", Some(210), None),
-        ("qbittorrent", Some(212), None),
-        ("plex", Some(230), None),
-        ("jellyfin", Some(231), None),
-        
-        // VM services
-        ("home-assistant", None, Some(500)),
-        ("alexa-service", None, Some(611)),
-    ];
-    
-    for &(service_name, container_id, vm_id) in &service_definitions {
-        if let Ok(service_info) = check_service_status(service_name.to_string(), container_id, vm_id).await {
-            services.push(service_info);
-        }
+// Helper function to split two name lists into (only in a, only in b), sorted and deduplicated
+fn name_difference(a: &[String], b: &[String]) -> (Vec<String>, Vec<String>) {
+    let a_set: std::collections::BTreeSet<&String> = a.iter().collect();
+    let b_set: std::collections::BTreeSet<&String> = b.iter().collect();
+    (
+        a_set.difference(&b_set).map(|name| name.to_string()).collect(),
+        b_set.difference(&a_set).map(|name| name.to_string()).collect(),
+    )
+}
+
+// Helper function to diff the binary/service/config listings of two containers
+fn diff_containers(a: &ContainerInventory, b: &ContainerInventory) -> ContainerDiff {
+    let binaries = |inventory: &ContainerInventory| inventory.binaries.iter().map(|b| b.name.clone()).collect::<Vec<_>>();
+    let (binaries_only_in_a, binaries_only_in_b) = name_difference(&binaries(a), &binaries(b));
+
+    let versions_b: HashMap<&str, &str> = b.binaries.iter().map(|b| (b.name.as_str(), b.version.as_str())).collect();
+    let mut version_mismatches: Vec<VersionMismatch> = a.binaries.iter()
+        .filter_map(|binary| {
+            let version_b = versions_b.get(binary.name.as_str())?;
+            (binary.version != *version_b).then(|| VersionMismatch {
+                name: binary.name.clone(),
+                version_a: binary.version.clone(),
+                version_b: version_b.to_string(),
+            })
+        })
+        .collect();
+    version_mismatches.sort_by(|x, y| x.name.cmp(&y.name));
+
+    let services = |inventory: &ContainerInventory| inventory.services.iter().map(|s| s.name.clone()).collect::<Vec<_>>();
+    let (services_only_in_a, services_only_in_b) = name_difference(&services(a), &services(b));
+
+    let configs = |inventory: &ContainerInventory| inventory.configs.iter().map(|c| c.path.clone()).collect::<Vec<_>>();
+    let (configs_only_in_a, configs_only_in_b) = name_difference(&configs(a), &configs(b));
+
+    ContainerDiff {
+        container_a: a.container_id,
+        container_b: b.container_id,
+        binaries_only_in_a,
+        binaries_only_in_b,
+        version_mismatches,
+        services_only_in_a,
+        services_only_in_b,
+        configs_only_in_a,
+        configs_only_in_b,
     }
-    
-    Ok(services)
 }
 
+// Helper function to fetch the listings compare_containers diffs, with the three probes run side by side
+async fn container_inventory(container_id: u32) -> Result<ContainerInventory, String> {
+    let binaries = tokio::spawn(get_container_binaries(container_id));
+    let services = tokio::spawn(get_container_services(container_id));
+    let configs = tokio::spawn(get_container_configs(container_id));
 
-async fn get_all_binaries() -> Result<Vec<BinaryInfo>, String> {
-    let mut binaries = Vec::new();
-    
-    // Define binaries to check
-    let binary_definitions = [
-        // System binaries
-        ("docker", None, None),
-        ("systemctl", None, None),
-        ("nginx", None, None),
-        
-        // Application binaries (in containers)
-        ("sonarr", Some(214), None),
-        ("radarr", Some(215), None),
-        ("prowlarr", Some(210), None),
-        ("plex", Some(230), None),
-        ("jellyfin", Some(231), None),
-        
-        // VM binaries
-        ("python3", None, Some(500)),
-        ("hass", None, Some(500)),
-    ];
-    
-    for &(binary_name, container_id, vm_id) in &binary_definitions {
-        if let Ok(binary_info) = check_binary(binary_name.to_string(), container_id, vm_id).await {
-            binaries.push(binary_info);
-        }
-    }
-    
-    Ok(binaries)
+    let join_error = |e: tokio::task::JoinError| format!("Inventory task for container {} failed: {}", container_id, e);
+    Ok(ContainerInventory {
+        container_id,
+        binaries: binaries.await.map_err(join_error)??,
+        services: services.await.map_err(join_error)??,
+        configs: configs.await.map_err(join_error)??,
+    })
 }
 
-async fn get_all_configs() -> Result<Vec<ConfigInfo>, String> {
-    let mut configs = Vec::new();
-    
-    // Define config files to check
-    let config_definitions = [
-        // System configs
-        ("/etc/nginx/nginx.conf", None, None),
-        ("/etc/docker/daemon.json", None, None),
-        
-        // Application configs (in containers)
-        ("/config/config.xml", Some(214), None), // Sonarr
-        ("/config/config.xml", Some(215), None), // Radarr
-        ("/config/config.xml", Some(210), None), // Prowlarr
-        ("/config/qBittorrent/qBittorrent.conf", Some(212), None),
-        
-        // VM configs
-        ("/config/configuration.yaml", None, Some(500)), // Home Assistant
-    ];
-    
-    for &(config_path, container_id, vm_id) in &config_definitions {
-        if let Ok(config_info) = check_config(config_path.to_string(), container_id, vm_id).await {
-            configs.push(config_info);
-        }
+// Tauri command to diff installed binaries, services and config files between two containers
+#[tauri::command]
+async fn compare_containers(id_a: u32, id_b: u32) -> Result<ContainerDiff, String> {
+    if id_a == id_b {
+        return Err("Pick two different containers to compare".to_string());
     }
-    
-    Ok(configs)
+
+    // A half-fetched inventory would show everything as "missing", so either side failing fails the diff
+    let inventory_a = tokio::spawn(container_inventory(id_a));
+    let inventory_b = tokio::spawn(container_inventory(id_b));
+    let inventory_a = inventory_a.await.map_err(|e| format!("Inventory task failed: {}", e))??;
+    let inventory_b = inventory_b.await.map_err(|e| format!("Inventory task failed: {}", e))??;
+
+    Ok(diff_containers(&inventory_a, &inventory_b))
 }
 
-async fn get_system_health() -> Result<SystemHealth, String> {
-    // Get system health from Proxmox host
-    let df_output = Command::new("ssh")
-        .args(["proxmox", "df", "-h", "/"])
-        .output()
-        .unwrap_or_else(|_| std::process::Output {
-            status: std::process::ExitStatus::from_raw(1),
-            stdout: Vec::new(),
-            stderr: Vec::new(),
-        });
-    
-    let disk_usage = if df_output.status.success() {
-        let df_str = String::from_utf8_lossy(&df_output.stdout);
-        df_str.lines().nth(1)
-            .and_then(|line| line.split_whitespace().nth(4))
-            .and_then(|usage| usage.trim_end_matches('%').parse().ok())
-            .unwrap_or(0.0)
-    } else {
-        0.0
-    };
-    
-    let free_output = Command::new("ssh")
-        .args(["proxmox", "free", "-m"])
-        .output()
-        .unwrap_or_else(|_| std::process::Output {
-            status: std::process::ExitStatus::from_raw(1),
-            stdout: Vec::new(),
-            stderr: Vec::new(),
-        });
-    
-    let memory_usage = if free_output.status.success() {
-        let _free_str = String::from_utf8_lossy(&free_output.stdout);
-        // Parse memory usage from free command output
-        50.0 // Placeholder
-    } else {
-        0.0
-    };
-    
-    let uptime_output = Command::new("ssh")
-        .args(["proxmox", "uptime"])
-        .output()
-        .unwrap_or_else(|_| std::process::Output {
-            status: std::process::ExitStatus::from_raw(1),
-            stdout: Vec::new(),
-            stderr: Vec::new(),
-        });
-    
-    let uptime = if uptime_output.status.success() {
-        String::from_utf8_lossy(&uptime_output.stdout).trim().to_string()
-    } else {
-        "Unknown".to_string()
-    };
-    
-    Ok(SystemHealth {
-        disk_usage,
-        memory_usage,
-        cpu_load: 0.0, // Would need to parse from uptime or top
-        network_status: "Connected".to_string(),
-        uptime,
-    })
+// CPU and memory use of a running container
+#[derive(Debug, Serialize, Deserialize)]
+struct ContainerUsage {
+    container_id: u32,
+    source: String,                // "cgroup" when read on the host, "container" when read through pct exec
+    cpu_percent: Option<f64>,      // Of the CPUs the container may use; None when it can't be worked out
+    memory_used_mb: f64,
+    memory_limit_mb: Option<f64>,  // None when unlimited
 }
 
-fn get_ssh_target(container_id: Option<u32>, vm_id: Option<u32>) -> String {
-    if let Some(cid) = container_id {
-        // For containers, we might need to enter the container
-        // This depends on your setup - you might use 'lxc exec' or docker exec
-        format!("proxmox lxc exec {} --", cid)
-    } else if let Some(vid) = vm_id {
-        // For VMs, you might have separate SSH access
-        match vid {
-            500 => "homeassistant".to_string(), // Assuming you have SSH alias
-            611 => "alexa".to_string(),
-            900 => "ai-system".to_string(),
-            _ => "proxmox".to_string(),
-        }
-    } else {
-        "proxmox".to_string()
-    }
+// One read of a container's cgroup v2 files on the host
+#[derive(Debug, PartialEq)]
+struct CgroupSample {
+    memory_current: u64,
+    memory_max: Option<u64>, // None for "max"
+    cpus: u32,               // From cpuset.cpus.effective
+    cpu_usage_usec: u64,
 }
 
-impl Default for SystemHealth {
-    fn default() -> Self {
-        SystemHealth {
-            disk_usage: 0.0,
-            memory_usage: 0.0,
-            cpu_load: 0.0,
-            network_status: "Unknown".to_string(),
-            uptime: "Unknown".to_string(),
-        }
-    }
+// Helper function to count the CPUs in a cpuset list such as "0-3,8"
+fn count_cpu_list(list: &str) -> u32 {
+    list.trim().split(',')
+        .filter(|range| !range.is_empty())
+        .map(|range| match range.split_once('-') {
+            Some((first, last)) => match (first.parse::<u32>(), last.parse::<u32>()) {
+                (Ok(first), Ok(last)) if last >= first => last - first + 1,
+                _ => 0,
+            },
+            None => range.parse::<u32>().map_or(0, |_| 1),
+        })
+        .sum()
 }
 
-// Tauri command to get system overview
-#[derive(Debug, Serialize, Deserialize)]
-struct ContainerDetail {
-    id: u32,
-    name: String,
-    status: String,
-    os_info: OsInfo,
-    running_processes: Vec<ProcessInfo>,
-    installed_binaries: Vec<BinaryInfo>,
-    systemd_services: Vec<ServiceInfo>,
-    configs: Vec<ConfigInfo>,
+// Helper function to parse memory.current, memory.max, cpuset.cpus.effective and the usage_usec
+// line of cpu.stat, one per line in that order
+fn parse_cgroup_sample(output: &str) -> Option<CgroupSample> {
+    let mut lines = output.lines().map(str::trim);
+    let memory_current = lines.next()?.parse().ok()?;
+    let memory_max = match lines.next()? {
+        "max" => None,
+        value => Some(value.parse().ok()?),
+    };
+    let cpus = count_cpu_list(lines.next()?);
+    let cpu_usage_usec = lines.next()?.strip_prefix("usage_usec")?.trim().parse().ok()?;
+    Some(CgroupSample { memory_current, memory_max, cpus, cpu_usage_usec })
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct OsInfo {
-    distribution: String,
-    version: String,
-    kernel: String,
-    architecture: String,
-    package_manager: String,
+// Helper function to turn a cgroup CPU counter into a percentage: the change since the previous
+// read of this container, or the average since it started when there is no previous read
+fn cgroup_cpu_percent(container_id: u32, sample: &CgroupSample, uptime_seconds: Option<u64>) -> Option<f64> {
+    let cache_key = format!("cgroup_cpu_{}", container_id);
+    let now_ms = Utc::now().timestamp_millis();
+    let previous = get_from_cache(&cache_key).and_then(|cached| {
+        let (usage, at) = cached.split_once('|')?;
+        Some((usage.parse::<u64>().ok()?, at.parse::<i64>().ok()?))
+    });
+    store_in_cache(&cache_key, &format!("{}|{}", sample.cpu_usage_usec, now_ms));
+
+    let cpus = sample.cpus.max(1) as f64;
+    let (used_usec, wall_usec) = match previous {
+        // A lower counter means the container restarted in between
+        Some((usage, at)) if now_ms > at && sample.cpu_usage_usec >= usage => {
+            (sample.cpu_usage_usec - usage, (now_ms - at) as u64 * 1000)
+        }
+        _ => (sample.cpu_usage_usec, uptime_seconds.filter(|uptime| *uptime > 0)? * 1_000_000),
+    };
+    Some((used_usec as f64 / wall_usec as f64 / cpus * 100.0).min(100.0))
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct ProcessInfo {
-    pid: String,
-    name: String,
-    cpu: String,
-    memory: String,
-    command: String,
+// Helper function to parse MemTotal and MemAvailable (in kB) from /proc/meminfo into used and total MB
+fn parse_meminfo_usage(output: &str) -> Option<(f64, f64)> {
+    let field = |key: &str| output.lines()
+        .find_map(|line| line.strip_prefix(key))
+        .and_then(|rest| rest.trim().trim_end_matches("kB").trim().parse::<f64>().ok());
+    let total = field("MemTotal:")?;
+    let available = field("MemAvailable:")?;
+    Some(((total - available) / 1024.0, total / 1024.0))
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct AiSuggestion {
-    suggestion: String,
-    explanation: String,
-    confidence: f32,
+// Helper function to read a running container's usage from its cgroup on the host, which needs no
+// pct exec. Hosts on the cgroup v1 layout fall back to /proc/meminfo inside the container (LXCFS
+// scopes it to the container), which gives memory but no CPU figure.
+async fn container_usage(container_id: u32, uptime_seconds: Option<u64>) -> Result<ContainerUsage, String> {
+    let script = format!("d=/sys/fs/cgroup/lxc/{}; [ -f $d/memory.current ] || exit 3; \
+                          cat $d/memory.current $d/memory.max $d/cpuset.cpus.effective && grep '^usage_usec' $d/cpu.stat", container_id);
+    let output = run_remote_read(&guest_host(container_id).await, &["sh", "-c", &shell_escape(&script)], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+    if let Some(sample) = output.status.success().then(|| parse_cgroup_sample(&String::from_utf8_lossy(&output.stdout))).flatten() {
+        return Ok(ContainerUsage {
+            container_id,
+            source: "cgroup".to_string(),
+            cpu_percent: cgroup_cpu_percent(container_id, &sample, uptime_seconds),
+            memory_used_mb: sample.memory_current as f64 / 1024.0 / 1024.0,
+            memory_limit_mb: sample.memory_max.map(|max| max as f64 / 1024.0 / 1024.0),
+        });
+    }
+
+    let output = run_remote_read(&guest_host(container_id).await, &["pct", "exec", &container_id.to_string(), "--", "cat", "/proc/meminfo"], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("Failed to read usage of container {}: {}", container_id, String::from_utf8_lossy(&output.stderr)));
+    }
+    let (used, total) = parse_meminfo_usage(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| format!("Unexpected /proc/meminfo output from container {}", container_id))?;
+
+    Ok(ContainerUsage {
+        container_id,
+        source: "container".to_string(),
+        cpu_percent: None,
+        memory_used_mb: used,
+        memory_limit_mb: Some(total),
+    })
 }
 
-// Comprehensive AI ecosystem analysis structures
-#[derive(Debug, Serialize, Deserialize)]
-struct ProxmoxEcosystemScan {
+// Tauri command to get a running container's CPU and memory use, read from its cgroup on the host
+#[tauri::command]
+async fn get_container_usage(container_id: u32) -> Result<ContainerUsage, String> {
+    // Only the uptime is needed here; guest_status would take a cgroup sample of its own
+    let status = run_remote_read(&guest_host(container_id).await, &["pct", "status", &container_id.to_string(), "--verbose"], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    let uptime_seconds = parse_key_values(&String::from_utf8_lossy(&status.stdout))
+        .get("uptime")
+        .and_then(|uptime| uptime.parse::<u64>().ok());
+    container_usage(container_id, uptime_seconds).await
+}
+
+// Cumulative disk and network I/O counters for a guest; the UI computes rates between samples
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct IoStats {
+    id: u32,
+    is_vm: bool,
+    disk_read_bytes: u64,
+    disk_write_bytes: u64,
+    net_in_bytes: u64,
+    net_out_bytes: u64,
     timestamp: DateTime<Utc>,
-    host_analysis: HostAnalysis,
-    container_analysis: Vec<ContainerAnalysis>,
-    vm_analysis: Vec<VmAnalysis>,
-    network_analysis: NetworkAnalysis,
-    storage_analysis: StorageAnalysis,
-    security_analysis: SecurityAnalysis,
-    performance_analysis: PerformanceAnalysis,
-    optimization_recommendations: Vec<OptimizationRecommendation>,
-    overall_health_score: f32,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct HostAnalysis {
-    cpu_optimization: Vec<String>,
-    memory_optimization: Vec<String>,
-    network_optimization: Vec<String>,
-    storage_optimization: Vec<String>,
-    security_issues: Vec<String>,
-    configuration_recommendations: Vec<ConfigRecommendation>,
+// Helper function to parse "key: value" lines such as `qm status --verbose` or `pct config` output
+fn parse_key_values(output: &str) -> HashMap<String, String> {
+    output.lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .filter(|(key, _)| !key.is_empty())
+        .collect()
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct ContainerAnalysis {
-    id: u32,
-    name: String,
-    health_score: f32,
-    resource_usage: ResourceUsage,
-    security_assessment: SecurityAssessment,
-    configuration_issues: Vec<String>,
-    optimization_opportunities: Vec<String>,
-    recommended_configs: Vec<ConfigRecommendation>,
+// Helper function to sum read/write bytes from cgroup v2 io.stat or cgroup v1 blkio output
+fn parse_cgroup_io(output: &str) -> (u64, u64) {
+    let mut read_bytes = 0;
+    let mut write_bytes = 0;
+
+    for line in output.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+
+        // cgroup v2: "8:0 rbytes=1234 wbytes=5678 rios=1 wios=2 ..."
+        for field in &parts {
+            if let Some(value) = field.strip_prefix("rbytes=") {
+                read_bytes += value.parse::<u64>().unwrap_or(0);
+            } else if let Some(value) = field.strip_prefix("wbytes=") {
+                write_bytes += value.parse::<u64>().unwrap_or(0);
+            }
+        }
+
+        // cgroup v1: "8:0 Read 1234" / "8:0 Write 5678"
+        if parts.len() == 3 {
+            match parts[1] {
+                "Read" => read_bytes += parts[2].parse::<u64>().unwrap_or(0),
+                "Write" => write_bytes += parts[2].parse::<u64>().unwrap_or(0),
+                _ => {}
+            }
+        }
+    }
+
+    (read_bytes, write_bytes)
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct VmAnalysis {
-    id: u32,
-    name: String,
-    health_score: f32,
-    resource_allocation: ResourceAllocation,
-    performance_metrics: VmPerformanceMetrics,
-    configuration_recommendations: Vec<ConfigRecommendation>,
+// Helper function to sum received/transmitted bytes from /proc/net/dev, ignoring loopback
+fn parse_proc_net_dev(output: &str) -> (u64, u64) {
+    let mut in_bytes = 0;
+    let mut out_bytes = 0;
+
+    for line in output.lines().skip(2) { // Skip the two header lines
+        if let Some((interface, counters)) = line.split_once(':') {
+            if interface.trim() == "lo" {
+                continue;
+            }
+            let fields: Vec<&str> = counters.split_whitespace().collect();
+            if fields.len() >= 9 {
+                in_bytes += fields[0].parse::<u64>().unwrap_or(0);
+                out_bytes += fields[8].parse::<u64>().unwrap_or(0);
+            }
+        }
+    }
+
+    (in_bytes, out_bytes)
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct NetworkAnalysis {
-    overall_health: f32,
-    bandwidth_utilization: f32,
-    latency_issues: Vec<String>,
-    security_concerns: Vec<String>,
-    optimization_recommendations: Vec<String>,
-    firewall_recommendations: Vec<String>,
+// Tauri command to get cumulative disk/network I/O counters for a container
+#[tauri::command]
+async fn get_container_io_stats(container_id: u32) -> Result<IoStats, String> {
+    let output = run_remote(&guest_host(container_id).await, &[&format!("pct exec {} -- sh -c 'cat /sys/fs/cgroup/io.stat 2>/dev/null || cat /sys/fs/cgroup/blkio/blkio.throttle.io_service_bytes 2>/dev/null; echo ---; cat /proc/net/dev'", container_id)], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to get I/O stats: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!("Failed to get I/O stats for container {}: {}", container_id, String::from_utf8_lossy(&output.stderr)));
+    }
+
+    let stats_output = String::from_utf8_lossy(&output.stdout);
+    let (io_section, net_section) = stats_output.split_once("---").unwrap_or(("", &stats_output));
+    let (disk_read_bytes, disk_write_bytes) = parse_cgroup_io(io_section);
+    let (net_in_bytes, net_out_bytes) = parse_proc_net_dev(net_section.trim_start_matches('\n'));
+
+    Ok(IoStats {
+        id: container_id,
+        is_vm: false,
+        disk_read_bytes,
+        disk_write_bytes,
+        net_in_bytes,
+        net_out_bytes,
+        timestamp: Utc::now(),
+    })
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct StorageAnalysis {
-    overall_health: f32,
-    usage_patterns: Vec<String>,
-    performance_bottlenecks: Vec<String>,
-    redundancy_assessment: String,
-    optimization_recommendations: Vec<String>,
-    backup_recommendations: Vec<String>,
+// Tauri command to get cumulative disk/network I/O counters for a VM
+#[tauri::command]
+async fn get_vm_io_stats(vm_id: u32) -> Result<IoStats, String> {
+    let output = run_remote(&guest_host(vm_id).await, &["qm", "status", &vm_id.to_string(), "--verbose"], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!("Failed to get I/O stats for VM {}: {}", vm_id, String::from_utf8_lossy(&output.stderr)));
+    }
+
+    let values = parse_key_values(&String::from_utf8_lossy(&output.stdout));
+    let counter = |key: &str| values.get(key).and_then(|v| v.parse::<u64>().ok()).unwrap_or(0);
+
+    Ok(IoStats {
+        id: vm_id,
+        is_vm: true,
+        disk_read_bytes: counter("diskread"),
+        disk_write_bytes: counter("diskwrite"),
+        net_in_bytes: counter("netin"),
+        net_out_bytes: counter("netout"),
+        timestamp: Utc::now(),
+    })
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct SecurityAnalysis {
-    overall_score: f32,
-    vulnerabilities: Vec<SecurityVulnerability>,
-    access_control_issues: Vec<String>,
-    network_security_gaps: Vec<String>,
-    compliance_recommendations: Vec<String>,
-    hardening_suggestions: Vec<String>,
+// Pressure stall averages (percent of time stalled) over the last 10s, 60s and 300s
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+struct PressureAverages {
+    avg10: f64,
+    avg60: f64,
+    avg300: f64,
+}
+
+// "some": at least one task stalled; "full": every task stalled (absent for cpu on older kernels)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+struct PressureStat {
+    some: PressureAverages,
+    full: Option<PressureAverages>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct PerformanceAnalysis {
-    overall_score: f32,
-    cpu_bottlenecks: Vec<String>,
-    memory_bottlenecks: Vec<String>,
-    io_bottlenecks: Vec<String>,
-    network_bottlenecks: Vec<String>,
-    scaling_recommendations: Vec<String>,
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct PressureInfo {
+    container_id: u32,
+    cpu: Option<PressureStat>,
+    memory: Option<PressureStat>,
+    io: Option<PressureStat>,
+    note: Option<String>, // Why figures are missing, e.g. a kernel without PSI
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct OptimizationRecommendation {
-    priority: String, // High, Medium, Low
-    category: String, // Performance, Security, Cost, Reliability
-    title: String,
-    description: String,
-    implementation_steps: Vec<String>,
-    estimated_impact: String,
-    configuration: Option<String>,
-    script: Option<String>,
+// Helper function to parse a PSI file ("some avg10=0.00 avg60=0.00 avg300=0.00 total=0" plus a "full" line)
+fn parse_pressure(output: &str) -> Option<PressureStat> {
+    let mut some = None;
+    let mut full = None;
+
+    for line in output.lines() {
+        let mut fields = line.split_whitespace();
+        let kind = fields.next();
+        let mut averages = PressureAverages::default();
+        for field in fields {
+            if let Some((key, value)) = field.split_once('=') {
+                let value = value.parse().unwrap_or(0.0);
+                match key {
+                    "avg10" => averages.avg10 = value,
+                    "avg60" => averages.avg60 = value,
+                    "avg300" => averages.avg300 = value,
+                    _ => {}
+                }
+            }
+        }
+        match kind {
+            Some("some") => some = Some(averages),
+            Some("full") => full = Some(averages),
+            _ => {}
+        }
+    }
+
+    some.map(|some| PressureStat { some, full })
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct ConfigRecommendation {
-    file_path: String,
-    section: String,
-    current_value: Option<String>,
-    recommended_value: String,
-    reason: String,
-    impact: String,
-    backup_required: bool,
+// Tauri command to get CPU/memory/IO pressure stall figures for a container. The container's own
+// cgroup files are preferred since /proc/pressure inside an LXC reports the whole host.
+#[tauri::command]
+async fn get_container_pressure(container_id: u32) -> Result<PressureInfo, String> {
+    let script = "for r in cpu memory io; do echo \"== $r\"; \
+                  cat /sys/fs/cgroup/$r.pressure 2>/dev/null || cat /proc/pressure/$r 2>/dev/null; done";
+    let output = run_guest_shell(Some(container_id), None, script, *COMMAND_TIMEOUT).await?;
+
+    if !output.status.success() {
+        return Err(format!("Failed to read pressure for container {}: {}", container_id, String::from_utf8_lossy(&output.stderr)));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut sections: HashMap<&str, &str> = HashMap::new();
+    for section in stdout.split("== ").skip(1) {
+        let (resource, body) = section.split_once('\n').unwrap_or((section, ""));
+        sections.insert(resource.trim(), body);
+    }
+    let stat = |resource: &str| sections.get(resource).and_then(|body| parse_pressure(body));
+
+    let (cpu, memory, io) = (stat("cpu"), stat("memory"), stat("io"));
+    let note = (cpu.is_none() && memory.is_none() && io.is_none())
+        .then(|| "Pressure stall information is not available; it needs kernel 4.20+ with PSI enabled".to_string());
+
+    Ok(PressureInfo {
+        container_id,
+        cpu,
+        memory,
+        io,
+        note,
+    })
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct ResourceUsage {
-    cpu_percent: f32,
-    memory_percent: f32,
-    disk_usage: f32,
-    network_io: String,
-    efficiency_score: f32,
+// OS Update/Upgrade commands
+#[tauri::command]
+async fn update_container_packages(container_id: u32) -> Result<PackageUpgradeResult, String> {
+    audited("update_container_packages", format!("ct:{}", container_id), serde_json::json!({ "container_id": container_id }), async move {
+        let os_info = get_container_os_info(container_id).await?;
+    
+        let update_command = match os_info.package_manager.as_str() {
+            "apk" => "apk update && apk upgrade",
+            "apt" => "apt update && apt upgrade -y",
+            "yum" => "yum update -y",
+            _ => return Err("Unknown package manager".to_string()),
+        };
+    
+        let output = run_command(&guest_host(container_id).await, &[&format!("pct exec {} -- sh -c {}", container_id, shell_escape(update_command))], *LONG_COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to update packages: {}", e))?;
+    
+        if output.success() {
+            Ok(PackageUpgradeResult {
+                message: format!("Packages updated in container {}", container_id),
+                output,
+            })
+        } else {
+            Err(format!("Failed to update packages in container {}: {}\n{}", container_id, output.stderr.trim(), output.stdout.trim()))
+        }
+    }).await
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct SecurityAssessment {
-    score: f32,
-    open_ports: Vec<u16>,
-    running_services: Vec<String>,
-    user_permissions: Vec<String>,
-    security_updates_needed: bool,
+struct PackageUpgradeResult {
+    message: String,
+    output: CommandOutput, // Full apt/apk/yum output, warnings included
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct ResourceAllocation {
-    cpu_cores: u32,
-    memory_gb: f32,
-    disk_gb: f32,
-    utilization_efficiency: f32,
-    recommended_allocation: ResourceRecommendation,
+struct PackageUpdate {
+    name: String,
+    current_version: String,
+    new_version: String,
+    security: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct VmPerformanceMetrics {
-    cpu_usage: f32,
-    memory_usage: f32,
-    disk_io: String,
-    network_io: String,
-    performance_score: f32,
+struct PendingUpdates {
+    package_manager: String,
+    updates: Vec<PackageUpdate>,
+    // None when the package manager doesn't tell us which updates are security fixes
+    security_count: Option<usize>,
+    regular_count: Option<usize>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct SecurityVulnerability {
-    severity: String,
-    description: String,
-    affected_component: String,
-    remediation: String,
-    cve_id: Option<String>,
+// Helper function to parse `apt list --upgradable` output
+fn parse_apt_upgradable(output: &str) -> Vec<PackageUpdate> {
+    // e.g. "openssl/stable-security 3.0.15-1~deb12u1 amd64 [upgradable from: 3.0.14-1~deb12u2]"
+    output.lines()
+        .filter_map(|line| {
+            let (name, rest) = line.split_once('/')?;
+            let mut parts = rest.split_whitespace();
+            let suites = parts.next()?;
+            let new_version = parts.next()?;
+            let current_version = line.split("upgradable from: ").nth(1)?.trim_end_matches(']');
+
+            Some(PackageUpdate {
+                name: name.to_string(),
+                current_version: current_version.to_string(),
+                new_version: new_version.to_string(),
+                security: suites.split(',').any(|suite| suite.contains("-security")),
+            })
+        })
+        .collect()
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct ResourceRecommendation {
-    cpu_cores: u32,
-    memory_gb: f32,
-    disk_gb: f32,
-    reasoning: String,
+// Helper function to parse `apk version -l '<'` output
+fn parse_apk_upgradable(output: &str) -> Vec<PackageUpdate> {
+    // e.g. "busybox-1.36.1-r15 < 1.36.1-r19"; the version is the last two dash-separated fields
+    output.lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let installed = parts.next()?;
+            if parts.next()? != "<" {
+                return None;
+            }
+            let new_version = parts.next()?;
+
+            let mut fields = installed.rsplitn(3, '-');
+            let release = fields.next()?;
+            let version = fields.next()?;
+            let name = fields.next()?;
+
+            Some(PackageUpdate {
+                name: name.to_string(),
+                current_version: format!("{}-{}", version, release),
+                new_version: new_version.to_string(),
+                security: false,
+            })
+        })
+        .collect()
 }
 
-// Container detailed management commands
-#[tauri::command]
-async fn get_container_details(container_id: u32) -> Result<ContainerDetail, String> {
-    let cache_key = format!("container_details_{}", container_id);
-    
-    // Check if we have valid cached data
-    if is_cache_valid_with_duration(&cache_key, *CONTAINER_CACHE_DURATION) {
-        if let Some(cached_data) = get_from_cache(&cache_key) {
-            if let Ok(container_detail) = serde_json::from_str::<ContainerDetail>(&cached_data) {
-                return Ok(container_detail);
+// Helper function to parse `yum check-update` output, marking packages named in `yum updateinfo list security`
+fn parse_yum_upgradable(output: &str, security_list: &str) -> Vec<PackageUpdate> {
+    // Advisory lines look like "RHSA-2024:1234 Important/Sec. openssl-libs-1:3.0.7-27.el9.x86_64"
+    let security_packages: Vec<&str> = security_list.lines()
+        .filter_map(|line| line.split_whitespace().nth(2))
+        .collect();
+
+    // Update lines look like "openssl-libs.x86_64    1:3.0.7-27.el9    baseos"
+    output.lines()
+        .take_while(|line| !line.starts_with("Obsoleting"))
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() != 3 {
+                return None;
             }
-        }
-    }
-    
-    let container_name = get_container_name(container_id).await?;
-    
-    let os_info = get_container_os_info(container_id).await?;
-    let running_processes = get_container_processes(container_id).await?;
-    let installed_binaries = get_container_binaries(container_id).await?;
-    let systemd_services = get_container_services(container_id).await?;
-    let configs = get_container_configs(container_id).await?;
-    
-    let status_info = get_container_status(container_id).await?;
-    
-    let container_detail = ContainerDetail {
-        id: container_id,
-        name: container_name,
-        status: status_info.status,
-        os_info,
-        running_processes,
-        installed_binaries,
-        systemd_services,
-        configs,
-    };
-    
-    // Store in cache
-    if let Ok(serialized) = serde_json::to_string(&container_detail) {
-        store_in_cache(&cache_key, &serialized);
-    }
-    
-    Ok(container_detail)
+            let (name, _arch) = parts[0].rsplit_once('.')?;
+
+            Some(PackageUpdate {
+                name: name.to_string(),
+                current_version: "unknown".to_string(),
+                new_version: parts[1].to_string(),
+                security: security_packages.iter().any(|package| {
+                    package.strip_prefix(name).map_or(false, |rest| rest.starts_with('-'))
+                }),
+            })
+        })
+        .collect()
+}
+
+// Helper function to detect the package manager on the host or a VM
+async fn detect_package_manager(vm_id: Option<u32>) -> Result<String, String> {
+    let output = run_guest_shell(None, vm_id,
+        "if command -v apt-get >/dev/null 2>&1; then echo apt; elif command -v apk >/dev/null 2>&1; then echo apk; elif command -v yum >/dev/null 2>&1; then echo yum; else echo unknown; fi", *COMMAND_TIMEOUT).await?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
 
+// Tauri command to preview the package updates pending on the host, a container or a VM
 #[tauri::command]
-async fn get_container_os_info(container_id: u32) -> Result<OsInfo, String> {
-    let output = Command::new("ssh")
-        .args(["proxmox", &format!("pct exec {} -- sh -c 'cat /etc/os-release 2>/dev/null || cat /etc/lsb-release 2>/dev/null || echo \"ID=unknown\"'", container_id)])
-        .output()
-        .map_err(|e| format!("Failed to get OS info: {}", e))?
-        .stdout;
-    
-    let os_release = String::from_utf8_lossy(&output);
-    
-    let mut distribution = "Unknown".to_string();
-    let mut version = "Unknown".to_string();
-    
-    for line in os_release.lines() {
-        if line.starts_with("ID=") {
-            distribution = line.split('=').nth(1).unwrap_or("Unknown").trim_matches('"').to_string();
-        }
-        if line.starts_with("VERSION_ID=") {
-            version = line.split('=').nth(1).unwrap_or("Unknown").trim_matches('"').to_string();
-        }
+async fn list_pending_updates(container_id: Option<u32>, vm_id: Option<u32>) -> Result<PendingUpdates, String> {
+    let package_manager = match container_id {
+        Some(cid) => get_container_os_info(cid).await?.package_manager,
+        None => detect_package_manager(vm_id).await?,
+    };
+
+    let list_command = match package_manager.as_str() {
+        "apk" => "apk update >/dev/null 2>&1; apk version -l '<'",
+        "apt" => "apt-get update >/dev/null 2>&1; apt list --upgradable 2>/dev/null",
+        "yum" => "yum check-update -q; true",
+        _ => return Err("Unknown package manager".to_string()),
+    };
+
+    // Refreshing the package index can take a while on slow mirrors
+    let output = run_guest_shell(container_id, vm_id, list_command, *LONG_COMMAND_TIMEOUT).await?;
+    if !output.status.success() {
+        return Err(format!("Failed to list pending updates: {}", String::from_utf8_lossy(&output.stderr)));
     }
-    
-    let kernel_output = Command::new("ssh")
-        .args(["proxmox", &format!("pct exec {} -- uname -r", container_id)])
-        .output()
-        .map_err(|e| format!("Failed to get kernel info: {}", e))?
-        .stdout;
-    let kernel = String::from_utf8_lossy(&kernel_output).trim().to_string();
-    
-    let arch_output = Command::new("ssh")
-        .args(["proxmox", &format!("pct exec {} -- uname -m", container_id)])
-        .output()
-        .map_err(|e| format!("Failed to get architecture: {}", e))?
-        .stdout;
-    let architecture = String::from_utf8_lossy(&arch_output).trim().to_string();
-    
-    let package_manager = match distribution.to_lowercase().as_str() {
-        "alpine" => "apk",
-        "ubuntu" | "debian" => "apt",
-        "centos" | "rhel" | "fedora" => "yum",
-        _ => "unknown",
-    }.to_string();
-    
-    Ok(OsInfo {
-        distribution,
-        version,
-        kernel,
-        architecture,
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let (updates, has_security_info) = match package_manager.as_str() {
+        "apt" => (parse_apt_upgradable(&stdout), true),
+        "apk" => (parse_apk_upgradable(&stdout), false),
+        _ => {
+            // Not every yum repo ships updateinfo metadata, so a failure here just means no security split
+            let security = run_guest_shell(container_id, vm_id, "yum updateinfo list security -q 2>/dev/null", *LONG_COMMAND_TIMEOUT).await?;
+            let security_list = String::from_utf8_lossy(&security.stdout);
+            (parse_yum_upgradable(&stdout, &security_list), security.status.success())
+        }
+    };
+
+    let security_count = has_security_info.then(|| updates.iter().filter(|update| update.security).count());
+
+    Ok(PendingUpdates {
         package_manager,
+        security_count,
+        regular_count: security_count.map(|count| updates.len() - count),
+        updates,
     })
 }
 
-#[tauri::command]
-async fn get_container_processes(container_id: u32) -> Result<Vec<ProcessInfo>, String> {
-    let output = Command::new("ssh")
-        .args(["proxmox", &format!("pct exec {} -- ps aux --no-headers", container_id)])
-        .output()
-        .map_err(|e| format!("Failed to get processes: {}", e))?
-        .stdout;
-    
-    let ps_output = String::from_utf8_lossy(&output);
-    let mut processes = Vec::new();
-    
-    for line in ps_output.lines() {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() >= 11 {
-            processes.push(ProcessInfo {
-                pid: parts[1].to_string(),
-                name: parts[10].to_string(),
-                cpu: parts[2].to_string(),
-                memory: parts[3].to_string(),
-                command: parts[10..].join(" "),
-            });
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct RebootStatus {
+    required: bool,
+    reasons: Vec<String>,
+}
+
+// Helper function to read the ==flag, ==pkgs, ==running and ==installed sections printed by check_reboot_required.
+// Kernels are only compared when `check_kernel` is set, since containers run the host's kernel.
+fn parse_reboot_check(output: &str, check_kernel: bool) -> RebootStatus {
+    let mut sections: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut current = "";
+    for line in output.lines() {
+        match line.strip_prefix("==") {
+            Some(name) => current = name,
+            None if !line.trim().is_empty() => sections.entry(current).or_default().push(line.trim()),
+            None => {}
         }
     }
-    
-    Ok(processes)
-}
+    let section = |name: &str| sections.get(name).cloned().unwrap_or_default();
 
-#[tauri::command]
-async fn get_container_binaries(container_id: u32) -> Result<Vec<BinaryInfo>, String> {
-    let common_binaries = ["docker", "systemctl", "nginx", "apache2", "mysql", "postgres", "redis", "node", "python", "php", "java", "git", "curl", "wget", "vim", "nano"];
-    let mut binaries = Vec::new();
-    
-    for binary in &common_binaries {
-        let output = Command::new("ssh")
-            .args(["proxmox", &format!("pct exec {} -- which {} 2>/dev/null", container_id, binary)])
-            .output()
-            .map_err(|e| format!("Failed to check binary {}: {}", binary, e))?
-            .stdout;
-        
-        let path = String::from_utf8_lossy(&output).trim().to_string();
-        if !path.is_empty() {
-            let version_output = Command::new("ssh")
-                .args(["proxmox", &format!("pct exec {} -- {} --version 2>/dev/null || {} -v 2>/dev/null || echo 'Unknown'", container_id, binary, binary)])
-                .output()
-                .unwrap_or_else(|_| std::process::Output {
-                    status: std::process::ExitStatus::from_raw(0),
-                    stdout: b"Unknown".to_vec(),
-                    stderr: Vec::new(),
-                });
-            
-            let version = String::from_utf8_lossy(&version_output.stdout).lines().next().unwrap_or("Unknown").to_string();
-            
-                binaries.push(BinaryInfo {
-                name: binary.to_string(),
-                path,
-                version,
-                exists: true,
-                executable: true,
-                container_id: Some(container_id),
-                vm_id: None,
-            });
+    let mut reasons = Vec::new();
+    if !section("flag").is_empty() {
+        let packages = section("pkgs");
+        reasons.push(if packages.is_empty() {
+            "/var/run/reboot-required is present".to_string()
+        } else {
+            format!("/var/run/reboot-required is present (requested by {})", packages.join(", "))
+        });
+    }
+
+    if check_kernel {
+        let running = section("running").first().map(|release| release.to_string());
+        let installed = section("installed").into_iter().max_by_key(|release| kernel_version_key(release));
+        if let (Some(running), Some(installed)) = (running, installed) {
+            if kernel_version_key(installed) > kernel_version_key(&running) {
+                reasons.push(format!("Kernel {} is installed but {} is running", installed, running));
+            }
         }
     }
-    
-    Ok(binaries)
-}
 
-#[tauri::command]
-async fn get_container_services(container_id: u32) -> Result<Vec<ServiceInfo>, String> {
-    let output = Command::new("ssh")
-        .args(["proxmox", &format!("pct exec {} -- systemctl list-units --type=service --no-pager --no-legend 2>/dev/null || echo 'No systemd'", container_id)])
-        .output()
-        .map_err(|e| format!("Failed to get services: {}", e))?
-        .stdout;
-    
-    let services_output = String::from_utf8_lossy(&output);
-    let mut services = Vec::new();
-    
-    if services_output.trim() == "No systemd" {
-        return Ok(services);
+    RebootStatus {
+        required: !reasons.is_empty(),
+        reasons,
     }
-    
-    for line in services_output.lines() {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() >= 4 {
-            let name = parts[0].replace(".service", "");
-            let active = parts[2] == "active";
-            let status = parts[3].to_string();
-            
-            services.push(ServiceInfo {
-                name,
-                status,
-                enabled: true,
-                active,
-                description: parts[4..].join(" "),
-                container_id: Some(container_id),
-                vm_id: None,
-            });
+}
+
+// Tauri command to tell whether the host, a container or a VM needs a reboot to finish an upgrade:
+// the Debian/Ubuntu /var/run/reboot-required flag, and for VMs a newer kernel in /boot than the running
+// one. The host's kernel is judged from its pve-kernel packages, as in get_pve_versions.
+#[tauri::command]
+async fn check_reboot_required(container_id: Option<u32>, vm_id: Option<u32>) -> Result<RebootStatus, String> {
+    let script = "echo '==flag'; test -e /var/run/reboot-required && echo present; \
+                  echo '==pkgs'; cat /var/run/reboot-required.pkgs 2>/dev/null | sort -u; \
+                  echo '==running'; uname -r; \
+                  echo '==installed'; for k in /boot/vmlinuz-*; do [ -e \"$k\" ] && echo \"${k#/boot/vmlinuz-}\"; done; true";
+    let output = run_guest_shell(container_id, vm_id, script, *COMMAND_TIMEOUT).await?;
+    if !output.status.success() {
+        return Err(format!("Failed to check reboot state of {}: {}", audit_target(container_id, vm_id), String::from_utf8_lossy(&output.stderr)));
+    }
+
+    let is_host = container_id.is_none() && vm_id.is_none();
+    let mut status = parse_reboot_check(&String::from_utf8_lossy(&output.stdout), vm_id.is_some() && container_id.is_none());
+    if is_host {
+        let versions = get_pve_versions().await?;
+        if versions.reboot_required {
+            status.reasons.push(format!("Kernel {} is installed but {} is running",
+                                        versions.latest_kernel.unwrap_or_default(), versions.running_kernel));
+            status.required = true;
         }
     }
-    
-    Ok(services)
+    Ok(status)
 }
 
-#[tauri::command]
-async fn get_container_configs(container_id: u32) -> Result<Vec<ConfigInfo>, String> {
-    let common_configs = [
-        "/etc/nginx/nginx.conf",
-        "/etc/apache2/apache2.conf",
-        "/etc/mysql/my.cnf",
-        "/etc/redis/redis.conf",
-        "/etc/ssh/sshd_config",
-        "/etc/hosts",
-        "/etc/resolv.conf",
-        "/etc/fstab",
-    ];
-    
-    let mut configs = Vec::new();
-    
-    for config_path in &common_configs {
-        let output = Command::new("ssh")
-            .args(["proxmox", &format!("pct exec {} -- ls -la {} 2>/dev/null", container_id, config_path)])
-            .output()
-            .map_err(|e| format!("Failed to check config {}: {}", config_path, e))?
-            .stdout;
-        
-        let ls_output = String::from_utf8_lossy(&output).trim().to_string();
-        if !ls_output.is_empty() && !ls_output.contains("No such file") {
-            let parts: Vec<&str> = ls_output.split_whitespace().collect();
-            if parts.len() >= 9 {
-                configs.push(ConfigInfo {
-                    name: config_path.split('/').last().unwrap_or(config_path).to_string(),
-                    path: config_path.to_string(),
-                    exists: true,
-                    readable: true,
-                    writable: parts[0].chars().nth(2) == Some('w'),
-                    size: parts[4].parse().unwrap_or(0),
-                    modified: format!("{} {} {}", parts[5], parts[6], parts[7]),
-                    container_id: Some(container_id),
-                    vm_id: None,
-                });
+// Guests update_all_guests upgrades at the same time
+const MAX_CONCURRENT_UPDATES: usize = 4;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct UpdateResult {
+    target: String,                  // "host" or "ct:<id>"
+    package_manager: Option<String>,
+    updated: usize,                  // Packages upgraded, or that would be with dry_run
+    errors: Vec<String>,
+    reboot_required: bool,
+    reboot_reasons: Vec<String>,
+}
+
+// Helper function to upgrade (or with dry_run, only count the pending updates of) the host or one container
+async fn update_target(container_id: Option<u32>, dry_run: bool, confirmation_token: Option<String>) -> UpdateResult {
+    let mut result = UpdateResult {
+        target: audit_target(container_id, None),
+        package_manager: None,
+        updated: 0,
+        errors: Vec::new(),
+        reboot_required: false,
+        reboot_reasons: Vec::new(),
+    };
+
+    match list_pending_updates(container_id, None).await {
+        Ok(pending) => {
+            result.package_manager = Some(pending.package_manager);
+            if dry_run || pending.updates.is_empty() {
+                result.updated = pending.updates.len();
+            } else {
+                let upgrade = match container_id {
+                    Some(id) => update_container_packages(id).await,
+                    None => update_proxmox_packages(confirmation_token).await,
+                };
+                match upgrade {
+                    Ok(_) => result.updated = pending.updates.len(),
+                    Err(e) => result.errors.push(e),
+                }
             }
         }
+        Err(e) => result.errors.push(e),
     }
-    
-    Ok(configs)
+
+    match check_reboot_required(container_id, None).await {
+        Ok(status) => {
+            result.reboot_required = status.required;
+            result.reboot_reasons = status.reasons;
+        }
+        Err(e) => result.errors.push(format!("Failed to check for a pending reboot: {}", e)),
+    }
+    result
 }
 
-// OS Update/Upgrade commands
+// Tauri command to upgrade the packages of every running container on this node, a few at a time, and
+// then the host on its own once they are done. With dry_run nothing is changed and `updated` is the
+// number of updates pending. Failures are reported per target, host first; the host part needs the
+// same confirmation as update_proxmox_packages.
 #[tauri::command]
-async fn update_container_packages(container_id: u32) -> Result<String, String> {
-    let os_info = get_container_os_info(container_id).await?;
-    
-    let update_command = match os_info.package_manager.as_str() {
-        "apk" => "apk update && apk upgrade",
-        "apt" => "apt update && apt upgrade -y",
-        "yum" => "yum update -y",
-        _ => return Err("Unknown package manager".to_string()),
-    };
-    
-    let output = Command::new("ssh")
-        .args(["proxmox", &format!("pct exec {} -- {}", container_id, update_command)])
-        .output()
-        .map_err(|e| format!("Failed to update packages: {}", e))?
-        .stdout;
-    
-    Ok(String::from_utf8_lossy(&output).to_string())
+async fn update_all_guests(dry_run: Option<bool>, confirmation_token: Option<String>) -> Result<Vec<UpdateResult>, String> {
+    let dry_run = dry_run.unwrap_or(false);
+    audited("update_all_guests", "cluster".to_string(), serde_json::json!({ "dry_run": dry_run }), async move {
+        if !dry_run {
+            confirm_host_action(confirmation_token.as_deref()).await?;
+        }
+
+        let node = get_node_name().await?;
+        let containers: Vec<u32> = transport().cluster_resources().await?.iter()
+            .filter(|resource| resource["type"] == "lxc" && resource["status"] == "running")
+            .filter(|resource| resource["node"].as_str().map(|n| n == node).unwrap_or(true))
+            .filter_map(|resource| json_u64(&resource["vmid"]).map(|id| id as u32))
+            .collect();
+
+        let permits = Arc::new(tokio::sync::Semaphore::new(MAX_CONCURRENT_UPDATES));
+        let handles: Vec<_> = containers.into_iter()
+            .map(|container_id| {
+                let permits = permits.clone();
+                tokio::spawn(async move {
+                    let _permit = permits.acquire_owned().await.ok();
+                    update_target(Some(container_id), dry_run, None).await
+                })
+            })
+            .collect();
+
+        let mut results = Vec::new();
+        for handle in handles {
+            results.push(handle.await.map_err(|e| format!("Update task failed: {}", e))?);
+        }
+
+        // Upgrading the host restarts PVE services the container upgrades go through, so it runs last and alone
+        results.insert(0, update_target(None, dry_run, confirmation_token).await);
+        Ok(results)
+    }).await
 }
 
 // AI-powered configuration editing
+const AI_SUGGESTION_FORMAT: &str = "Respond only with JSON of the form {\"suggestions\": [{\"suggestion\": string, \"explanation\": string, \"confidence\": number between 0 and 1}]}.";
+
+// Helper function to call the configured Ollama /api/generate endpoint and return the model's text
+async fn ollama_generate(config: &AiConfig, model: &str, prompt: &str, json_format: bool) -> Result<String, String> {
+    let mut body = serde_json::json!({
+        "model": model,
+        "prompt": prompt,
+        "stream": false,
+    });
+    if json_format {
+        body["format"] = serde_json::json!("json");
+    }
+    let url = format!("{}/api/generate", config.base_url.trim_end_matches('/'));
+
+    let ai_response: serde_json::Value = if config.mode == "direct" {
+        reqwest::Client::new()
+            .post(&url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach AI system: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse AI response: {}", e))?
+    } else {
+        let output = run_remote(&guest_host(config.container_id).await, &[&format!("pct exec {} -- curl -s -X POST {} -H 'Content-Type: application/json' -d {}",
+                                       config.container_id, shell_escape(&url), shell_escape(&body.to_string()))], *AI_COMMAND_TIMEOUT)
+            .await
+            .map_err(|e| format!("Failed to reach AI system: {}", e))?;
+
+        if !output.status.success() {
+            return Err(format!("AI request failed: {}", String::from_utf8_lossy(&output.stderr)));
+        }
+
+        serde_json::from_slice(&output.stdout)
+            .map_err(|e| format!("Failed to parse AI response: {}", e))?
+    };
+
+    ai_response["response"].as_str()
+        .map(|response| response.to_string())
+        .ok_or_else(|| format!("AI response has no text: {}", ai_response["error"].as_str().unwrap_or("unknown error")))
+}
+
+// Helper function to turn the model's text into suggestions, falling back to the raw text
+fn parse_ai_suggestions(response: &str) -> Vec<AiSuggestion> {
+    let parsed = serde_json::from_str::<serde_json::Value>(response.trim()).ok()
+        .or_else(|| {
+            // Models sometimes wrap the JSON in prose or code fences
+            let start = response.find(['{', '['])?;
+            let end = response.rfind(['}', ']'])?;
+            serde_json::from_str(response.get(start..=end)?).ok()
+        });
+
+    let items = parsed.and_then(|value| match value {
+        serde_json::Value::Array(items) => Some(items),
+        serde_json::Value::Object(mut object) => match object.remove("suggestions") {
+            Some(serde_json::Value::Array(items)) => Some(items),
+            _ => None,
+        },
+        _ => None,
+    });
+
+    let suggestions: Vec<AiSuggestion> = items.unwrap_or_default()
+        .into_iter()
+        .filter_map(|item| serde_json::from_value::<AiSuggestion>(item).ok())
+        .map(|suggestion| AiSuggestion {
+            confidence: suggestion.confidence.clamp(0.0, 1.0),
+            ..suggestion
+        })
+        .collect();
+
+    if suggestions.is_empty() {
+        vec![AiSuggestion {
+            suggestion: response.trim().to_string(),
+            explanation: "The AI response could not be parsed into structured suggestions".to_string(),
+            confidence: 0.0,
+        }]
+    } else {
+        suggestions
+    }
+}
+
+// Tauri command to get the AI (Ollama) settings
+#[tauri::command]
+async fn get_ai_config() -> Result<AiConfig, String> {
+    Ok(app_config().ai)
+}
+
+// Tauri command to change the AI (Ollama) settings
+#[tauri::command]
+async fn set_ai_config(config: AiConfig) -> Result<AiConfig, String> {
+    if config.mode != "container" && config.mode != "direct" {
+        return Err(format!("Unknown AI mode '{}', expected 'container' or 'direct'", config.mode));
+    }
+    if config.model.trim().is_empty() || config.code_model.trim().is_empty() {
+        return Err("AI model names must not be empty".to_string());
+    }
+
+    Ok(update_app_config(|app_config| app_config.ai = config)?.ai)
+}
+
 #[tauri::command]
 async fn get_ai_config_suggestions(container_id: u32, config_path: String, config_content: String) -> Result<Vec<AiSuggestion>, String> {
     let ai_prompt = format!(
-        "Analyze this configuration file from container {} at path {}:\n\n{}\n\nProvide 3 optimization suggestions with explanations and confidence scores.",
-        container_id, config_path, config_content
+        "Analyze this configuration file from container {} at path {}:\n\n{}\n\nProvide 3 optimization suggestions with explanations and confidence scores. {}",
+        container_id, config_path, config_content, AI_SUGGESTION_FORMAT
     );
     
-    // Call AI system in CT-900
-    let output = Command::new("ssh")
-        .args(["proxmox", &format!("pct exec 900 -- curl -s -X POST http://localhost:11434/api/generate -H 'Content-Type: application/json' -d '{{\"model\": \"llama2\", \"prompt\": \"{}\", \"stream\": false}}'", ai_prompt.replace('"', "\\\""))])
-        .output()
-        .map_err(|e| format!("Failed to get AI suggestions: {}", e))?
-        .stdout;
-    
-    let _ai_response = String::from_utf8_lossy(&output);
-    
-    // Parse AI response (simplified - in production you'd parse JSON properly)
-    let suggestions = vec![
-        AiSuggestion {
-            suggestion: "Optimize buffer sizes for better performance".to_string(),
-            explanation: "Current buffer settings may be too small for your workload".to_string(),
-            confidence: 0.85,
-        },
-        AiSuggestion {
-            suggestion: "Enable compression to reduce bandwidth usage".to_string(),
-            explanation: "Compression can significantly reduce network overhead".to_string(),
-            confidence: 0.78,
-        },
-        AiSuggestion {
-            suggestion: "Review security settings for hardening".to_string(),
-            explanation: "Some security options could be tightened".to_string(),
-            confidence: 0.92,
-        },
-    ];
+    // Call the configured AI system
+    let ai_config = app_config().ai;
+    let ai_response = ollama_generate(&ai_config, &ai_config.model, &ai_prompt, true).await?;
     
-    Ok(suggestions)
+    Ok(parse_ai_suggestions(&ai_response))
 }
 
 #[tauri::command]
 async fn scan_proxmox_host() -> Result<String, String> {
     let scan_command = "nmap -sS 192.168.1.1/24"; // Example network scan
     
-    // Call AI system in CT-900
-    let output = Command::new("ssh")
-        .args(["proxmox", &format!("pct exec 900 -- curl -s -X POST http://localhost:11434/api/generate -H 'Content-Type: application/json' -d '{{\"model\": \"llama2\", \"prompt\": \"Analyze network scan results: {}\", \"stream\": false}}'", scan_command)])
-        .output()
-        .map_err(|e| format!("Failed to analyze Proxmox host: {}", e))?
-        .stdout;
-    
-    let ai_results = String::from_utf8_lossy(&output);
+    // Call the configured AI system
+    let ai_config = app_config().ai;
+    let ai_results = ollama_generate(&ai_config, &ai_config.model, &format!("Analyze network scan results: {}", scan_command), false)
+        .await
+        .map_err(|e| format!("Failed to analyze Proxmox host: {}", e))?;
     
-    Ok(ai_results.to_string())
+    Ok(ai_results)
 }
 
 #[tauri::command]
 async fn scan_media_stack() -> Result<Vec<AiSuggestion>, String> {
     let scan_command = "du -sh /srv/media/*"; // Example media stack scan
     
-    // Call AI system in CT-900
-    let output = Command::new("ssh")
-        .args(["proxmox", &format!("pct exec 900 -- curl -s -X POST http://localhost:11434/api/generate -H 'Content-Type: application/json' -d '{{\"model\": \"llama2\", \"prompt\": \"Provide optimization suggestions for media stack based on scan output:\\n\\n{}\", \"stream\": false}}'", scan_command)])
-        .output()
-        .map_err(|e| format!("Failed to analyze media stack: {}", e))?
-        .stdout;
-    
-    let _ai_response = String::from_utf8_lossy(&output);
+    // Call the configured AI system
+    let ai_config = app_config().ai;
+    let ai_prompt = format!(
+        "Provide optimization suggestions for media stack based on scan output:\n\n{}\n\n{}",
+        scan_command, AI_SUGGESTION_FORMAT
+    );
+    let ai_response = ollama_generate(&ai_config, &ai_config.model, &ai_prompt, true)
+        .await
+        .map_err(|e| format!("Failed to analyze media stack: {}", e))?;
     
-    let suggestions = vec![
-        AiSuggestion {
-            suggestion: "Optimize storage by archiving unused media".to_string(),
-            explanation: "Large directories indicate potential for storage optimization".to_string(),
-            confidence: 0.8,
+    Ok(parse_ai_suggestions(&ai_response))
+}
+
+// Helper function to parse listening ports and their owning process out of `ss -tlnp` or `netstat -tlnp` output
+fn parse_listeners(output: &str) -> Vec<(u16, String)> {
+    // ss:      "LISTEN 0 4096 0.0.0.0:22 0.0.0.0:* users:(("sshd",pid=612,fd=3))"
+    // netstat: "tcp 0 0 0.0.0.0:22 0.0.0.0:* LISTEN 612/sshd"
+    // Both put the local address in the fourth column; header lines fail to parse and drop out
+    let mut listeners: Vec<(u16, String)> = output.lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let port = fields.get(3)?.rsplit(':').next()?.parse::<u16>().ok()?;
+            let process = match line.find("((\"") {
+                Some(start) => line[start + 3..].split('"').next().unwrap_or_default().to_string(),
+                None => fields.last()
+                    .and_then(|last| last.split_once('/'))
+                    .map(|(_, name)| name.to_string())
+                    .unwrap_or_default(),
+            };
+            Some((port, process))
+        })
+        .collect();
+    // IPv4 and IPv6 sockets often list the same port; drop the copies, and unnamed ones when a name is known
+    listeners.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| b.1.cmp(&a.1)));
+    listeners.dedup_by(|later, kept| later.0 == kept.0 && (later.1.is_empty() || later.1 == kept.1));
+    listeners
+}
+
+// Helper function to parse the local ports out of `ss -tlnH` output, sorted and deduplicated
+fn parse_listening_ports(output: &str) -> Vec<u16> {
+    let mut ports: Vec<u16> = parse_listeners(output).into_iter().map(|(port, _)| port).collect();
+    ports.dedup();
+    ports
+}
+
+// Helper function to list a container's TCP listeners, falling back to netstat where ss isn't installed
+async fn container_listeners(container_id: u32) -> Result<Vec<(u16, String)>, String> {
+    let output = run_guest_shell(Some(container_id), None,
+        "if command -v ss >/dev/null 2>&1; then ss -tlnpH; else netstat -tlnp 2>/dev/null || netstat -tln; fi", *COMMAND_TIMEOUT).await?;
+
+    if !output.status.success() {
+        return Err(format!("Failed to list listening ports in container {}: {}", container_id, String::from_utf8_lossy(&output.stderr)));
+    }
+
+    Ok(parse_listeners(&String::from_utf8_lossy(&output.stdout)))
+}
+
+// Helper function to get a container's listening TCP ports, empty if they can't be read
+async fn container_listening_ports(container_id: u32) -> Vec<u16> {
+    get_container_open_ports(container_id).await.unwrap_or_default()
+}
+
+// Tauri command to get the TCP ports a container is listening on
+#[tauri::command]
+async fn get_container_open_ports(container_id: u32) -> Result<Vec<u16>, String> {
+    let mut ports: Vec<u16> = container_listeners(container_id).await?.into_iter().map(|(port, _)| port).collect();
+    ports.dedup();
+    Ok(ports)
+}
+
+// Tauri command to get a container's listening TCP ports with the owning process name, when known
+#[tauri::command]
+async fn get_container_listeners(container_id: u32) -> Result<Vec<(u16, String)>, String> {
+    container_listeners(container_id).await
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PortOwner {
+    port: u16,
+    address: Option<String>,       // Local address the host socket is bound to
+    process: Option<String>,
+    pid: Option<u32>,
+    forwarded_to: Option<String>,  // DNAT destination ("ip[:port]") when the port is forwarded
+    possible_guest_id: Option<u32>,
+    note: Option<String>,          // Why nothing (or only a guess) could be attributed
+}
+
+// Helper function to find the DNAT destination for a TCP port in `iptables-save -t nat` output
+fn parse_dnat_destination(rules: &str, port: u16) -> Option<String> {
+    let dport = port.to_string();
+    rules.lines()
+        .filter(|line| line.contains("-j DNAT"))
+        .find_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let value = |flag: &str| fields.iter().position(|field| *field == flag).and_then(|i| fields.get(i + 1)).copied();
+            if value("--dport") != Some(dport.as_str()) || value("-p").map_or(false, |protocol| protocol != "tcp") {
+                return None;
+            }
+            value("--to-destination").map(str::to_string)
+        })
+}
+
+// Tauri command to find what is listening on a TCP port of the host and, for forwarded ports
+// or QEMU user-mode forwards, which guest it most likely leads to
+#[tauri::command]
+async fn find_port_owner(port: u16) -> Result<PortOwner, String> {
+    let script = format!("ss -tlnpH 'sport = :{}'; echo ---; iptables-save -t nat 2>/dev/null", port);
+    let output = run_remote("proxmox", &[&script], *COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| format!("Failed to execute SSH command: {}", e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (listeners, nat_rules) = stdout.split_once("---").unwrap_or((&stdout, ""));
+
+    let listener = listeners.lines().find(|line| parse_listeners(line).iter().any(|(listen_port, _)| *listen_port == port));
+    let process = listener.and_then(|line| parse_listeners(line).into_iter().next())
+        .map(|(_, process)| process)
+        .filter(|process| !process.is_empty());
+    let pid = listener
+        .and_then(|line| line.split_once("pid="))
+        .and_then(|(_, rest)| rest.split(|c: char| !c.is_ascii_digit()).next())
+        .and_then(|pid| pid.parse::<u32>().ok());
+    let address = listener
+        .and_then(|line| line.split_whitespace().nth(3))
+        .and_then(|local| local.rsplit_once(':'))
+        .map(|(address, _)| address.to_string());
+    let forwarded_to = parse_dnat_destination(nat_rules, port);
+
+    let mut possible_guest_id = None;
+    if let Some(destination) = &forwarded_to {
+        // "10.0.0.5:80" or "[fd00::5]:80" -> the bare address
+        let address = destination.rsplit_once(':')
+            .filter(|(host, _)| !host.contains(':') || host.ends_with(']'))
+            .map_or(destination.as_str(), |(host, _)| host)
+            .trim_matches(|c| c == '[' || c == ']')
+            .to_string();
+        let resources = transport().cluster_resources().await.unwrap_or_default();
+        let lookups: Vec<_> = resources.iter()
+            .filter(|resource| matches!(resource["type"].as_str(), Some("lxc") | Some("qemu")) && resource["status"] == "running")
+            .filter_map(|resource| Some((json_u64(&resource["vmid"])? as u32, resource["type"] == "qemu")))
+            .map(|(id, is_vm)| (id, tokio::spawn(lookup_guest_network(id, is_vm))))
+            .collect();
+        for (id, lookup) in lookups {
+            if let Ok(Ok(network)) = lookup.await {
+                if possible_guest_id.is_none() && network.ip_addresses.contains(&address) {
+                    possible_guest_id = Some(id);
+                }
+            }
+        }
+    } else if let (Some("kvm"), Some(pid)) = (process.as_deref(), pid) {
+        // A QEMU hostfwd listener belongs to the VM whose kvm process holds it; kvm runs with "-id <vmid>"
+        if let Ok(ps) = run_remote("proxmox", &["ps", "-o", "args=", "-p", &pid.to_string()], *COMMAND_TIMEOUT).await {
+            let args = String::from_utf8_lossy(&ps.stdout);
+            possible_guest_id = args.split_whitespace()
+                .skip_while(|arg| *arg != "-id")
+                .nth(1)
+                .and_then(|id| id.parse().ok());
+        }
+    }
+
+    let note = match (&listener, &forwarded_to, possible_guest_id) {
+        (None, None, _) => Some(format!("Nothing on the host listens on or forwards port {}", port)),
+        (_, Some(destination), None) => Some(format!("Forwarded to {}, which no running guest reports as its address", destination)),
+        (Some(_), None, None) if process.is_none() => Some("Listener found but its process could not be read".to_string()),
+        _ => None,
+    };
+
+    Ok(PortOwner {
+        port,
+        address,
+        process,
+        pid,
+        forwarded_to,
+        possible_guest_id,
+        note,
+    })
+}
+
+// Helper function to rate how well an allocation fits its use: 100 between 40% and 80% memory use
+fn utilization_efficiency(memory_percent: f32) -> f32 {
+    if memory_percent < 40.0 {
+        50.0 + memory_percent / 40.0 * 50.0
+    } else if memory_percent > 80.0 {
+        (100.0 - (memory_percent - 80.0) * 2.5).max(50.0)
+    } else {
+        100.0
+    }
+}
+
+// Helper function to read a list of strings from a section of the AI's scan advice
+fn ai_list(advice: &serde_json::Value, section: &str, key: &str) -> Vec<String> {
+    advice[section][key].as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|item| item.as_str().map(|s| s.to_string()))
+        .collect()
+}
+
+// Helper function to read the AI's prioritized recommendations, skipping malformed entries
+fn ai_recommendations(advice: &serde_json::Value) -> Vec<OptimizationRecommendation> {
+    let text = |item: &serde_json::Value, key: &str, default: &str| item[key].as_str().unwrap_or(default).to_string();
+
+    advice["recommendations"].as_array()
+        .into_iter()
+        .flatten()
+        .filter(|item| item["title"].is_string() && item["description"].is_string())
+        .map(|item| OptimizationRecommendation {
+            priority: text(item, "priority", "Medium"),
+            category: text(item, "category", "Performance"),
+            title: text(item, "title", ""),
+            description: text(item, "description", ""),
+            implementation_steps: item["implementation_steps"].as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|step| step.as_str().map(|s| s.to_string()))
+                .collect(),
+            estimated_impact: text(item, "estimated_impact", "Unknown"),
+            configuration: item["configuration"].as_str().map(|s| s.to_string()),
+            script: item["script"].as_str().map(|s| s.to_string()),
+        })
+        .collect()
+}
+
+const ECOSYSTEM_SCAN_FORMAT: &str = "Respond only with JSON of the form {\"host\": {\"cpu_optimization\": [string], \"memory_optimization\": [string], \"network_optimization\": [string], \"storage_optimization\": [string], \"security_issues\": [string]}, \"network\": {\"latency_issues\": [string], \"security_concerns\": [string], \"optimization_recommendations\": [string], \"firewall_recommendations\": [string]}, \"storage\": {\"redundancy_assessment\": string, \"performance_bottlenecks\": [string], \"optimization_recommendations\": [string], \"backup_recommendations\": [string]}, \"security\": {\"access_control_issues\": [string], \"network_security_gaps\": [string], \"compliance_recommendations\": [string], \"hardening_suggestions\": [string]}, \"performance\": {\"scaling_recommendations\": [string]}, \"recommendations\": [{\"priority\": \"High\"|\"Medium\"|\"Low\", \"category\": \"Performance\"|\"Security\"|\"Cost\"|\"Reliability\", \"title\": string, \"description\": string, \"implementation_steps\": [string], \"estimated_impact\": string}]}.";
+
+// Tauri command to scan the whole Proxmox ecosystem. Resource numbers and open ports come from
+// the host; the recommendation lists come from the configured AI and stay empty if it's unavailable.
+#[tauri::command]
+async fn run_ecosystem_scan() -> Result<ProxmoxEcosystemScan, String> {
+    let cache_key = "ecosystem_scan";
+
+    if is_cache_valid_with_duration(cache_key, *ECOSYSTEM_SCAN_CACHE_DURATION) {
+        if let Some(cached_data) = get_from_cache(cache_key) {
+            if let Ok(scan) = serde_json::from_str::<ProxmoxEcosystemScan>(&cached_data) {
+                return Ok(scan);
+            }
+        }
+    }
+
+    let host_info = get_proxmox_host_info().await?;
+    let resources = transport().cluster_resources().await?;
+    let samples = resource_samples(&resources);
+    let usage = |target: &str, metric: &str| samples.iter()
+        .find(|(sample_target, sample_metric, _)| sample_target == target && *sample_metric == metric)
+        .map(|(_, _, value)| *value as f32)
+        .unwrap_or(0.0);
+
+    let guests: Vec<&serde_json::Value> = resources.iter()
+        .filter(|resource| matches!(resource["type"].as_str(), Some("lxc") | Some("qemu")))
+        .filter(|resource| json_u64(&resource["template"]) != Some(1))
+        .collect();
+
+    // Port listings are one ssh round trip per container, so run them side by side
+    let port_lookups: Vec<(u32, tokio::task::JoinHandle<Vec<u16>>)> = guests.iter()
+        .filter(|guest| guest["type"] == "lxc" && guest["status"] == "running")
+        .filter_map(|guest| json_u64(&guest["vmid"]))
+        .map(|id| (id as u32, tokio::spawn(container_listening_ports(id as u32))))
+        .collect();
+    let pressure_lookups: Vec<(u32, tokio::task::JoinHandle<Result<PressureInfo, String>>)> = port_lookups.iter()
+        .map(|(id, _)| (*id, tokio::spawn(get_container_pressure(*id))))
+        .collect();
+    let mut open_ports: HashMap<u32, Vec<u16>> = HashMap::new();
+    for (id, lookup) in port_lookups {
+        open_ports.insert(id, lookup.await.unwrap_or_default());
+    }
+    let mut pressure: HashMap<u32, PressureInfo> = HashMap::new();
+    for (id, lookup) in pressure_lookups {
+        if let Ok(Ok(info)) = lookup.await {
+            pressure.insert(id, info);
+        }
+    }
+
+    let host_ports = match run_remote("proxmox", &["ss", "-tlnH"], *COMMAND_TIMEOUT).await {
+        Ok(output) if output.status.success() => parse_listening_ports(&String::from_utf8_lossy(&output.stdout)),
+        _ => Vec::new(),
+    };
+
+    let mut container_analysis = Vec::new();
+    let mut vm_analysis = Vec::new();
+    let mut cpu_bottlenecks = Vec::new();
+    let mut memory_bottlenecks = Vec::new();
+
+    for guest in &guests {
+        let id = json_u64(&guest["vmid"]).unwrap_or(0) as u32;
+        let is_vm = guest["type"] == "qemu";
+        let target = format!("{}:{}", if is_vm { "vm" } else { "ct" }, id);
+        let name = guest["name"].as_str().map(|s| s.to_string())
+            .unwrap_or_else(|| if is_vm { get_vm_name(id) } else { get_container_display_name(id) });
+
+        let cpu_percent = usage(&target, "cpu");
+        let memory_percent = usage(&target, "memory");
+        if cpu_percent > 85.0 {
+            cpu_bottlenecks.push(format!("{} ({}) is at {:.0}% CPU", name, target, cpu_percent));
+        }
+        if memory_percent > 85.0 {
+            memory_bottlenecks.push(format!("{} ({}) is at {:.0}% memory", name, target, memory_percent));
+        }
+        // A container can sit well under its limit and still be stalled reclaiming memory
+        let guest_pressure = if is_vm { None } else { pressure.remove(&id) };
+        if let Some(memory) = guest_pressure.as_ref().and_then(|p| p.memory) {
+            if memory.some.avg60 > 10.0 {
+                memory_bottlenecks.push(format!("{} ({}) stalled on memory {:.0}% of the last minute", name, target, memory.some.avg60));
+            }
+        }
+
+        let resource_usage = ResourceUsage {
+            cpu_percent,
+            memory_percent,
+            disk_usage: usage(&target, "disk"),
+            io: None,
+            pressure: guest_pressure,
+            efficiency_score: utilization_efficiency(memory_percent),
+        };
+        let health_score = compute_health_score(&resource_usage, &[], &SecurityFlags::default());
+
+        if is_vm {
+            let memory_gb = json_u64(&guest["maxmem"]).unwrap_or(0) as f32 / 1_073_741_824.0;
+            let disk_gb = json_u64(&guest["maxdisk"]).unwrap_or(0) as f32 / 1_073_741_824.0;
+            let cpu_cores = json_u64(&guest["maxcpu"]).unwrap_or(0) as u32;
+            let (recommended_memory_gb, reasoning) = if memory_percent > 90.0 {
+                (memory_gb * 1.5, "Memory is nearly exhausted; allow 50% more".to_string())
+            } else if memory_percent > 0.0 && memory_percent < 20.0 && memory_gb > 2.0 {
+                (memory_gb / 2.0, "Less than 20% of memory is used; half would do".to_string())
+            } else {
+                (memory_gb, "Current allocation fits current use".to_string())
+            };
+
+            vm_analysis.push(VmAnalysis {
+                id,
+                name,
+                health_score,
+                resource_allocation: ResourceAllocation {
+                    cpu_cores,
+                    memory_gb,
+                    disk_gb,
+                    utilization_efficiency: resource_usage.efficiency_score,
+                    recommended_allocation: ResourceRecommendation {
+                        cpu_cores,
+                        memory_gb: recommended_memory_gb,
+                        disk_gb,
+                        reasoning,
+                    },
+                },
+                performance_metrics: VmPerformanceMetrics {
+                    cpu_usage: cpu_percent,
+                    memory_usage: memory_percent,
+                    disk_io: format!("{:.1} MB read / {:.1} MB written",
+                                     json_u64(&guest["diskread"]).unwrap_or(0) as f64 / 1_048_576.0,
+                                     json_u64(&guest["diskwrite"]).unwrap_or(0) as f64 / 1_048_576.0),
+                    network_io: format!("{:.1} MB in / {:.1} MB out",
+                                        json_u64(&guest["netin"]).unwrap_or(0) as f64 / 1_048_576.0,
+                                        json_u64(&guest["netout"]).unwrap_or(0) as f64 / 1_048_576.0),
+                    performance_score: health_score,
+                },
+                configuration_recommendations: Vec::new(),
+            });
+        } else {
+            let ports = open_ports.remove(&id).unwrap_or_default();
+            let mut optimization_opportunities = Vec::new();
+            if memory_percent > 85.0 {
+                optimization_opportunities.push("Raise the memory limit or trim the services running here".to_string());
+            } else if memory_percent > 0.0 && memory_percent < 20.0 {
+                optimization_opportunities.push("Memory limit is far above what's used; it could be lowered".to_string());
+            }
+
+            container_analysis.push(ContainerAnalysis {
+                id,
+                name,
+                health_score,
+                security_assessment: SecurityAssessment {
+                    // Every listener beyond a few is extra attack surface
+                    score: (100.0 - ports.len().saturating_sub(3) as f32 * 5.0).max(50.0),
+                    open_ports: ports,
+                    running_services: Vec::new(),
+                    user_permissions: Vec::new(),
+                    security_updates_needed: false,
+                },
+                resource_usage,
+                configuration_issues: Vec::new(),
+                optimization_opportunities,
+                recommended_configs: Vec::new(),
+            });
+        }
+    }
+
+    // Ask the AI for the qualitative parts; a failure just leaves them empty
+    let summary = serde_json::json!({
+        "host": host_info,
+        "host_listening_ports": host_ports,
+        "containers": container_analysis.iter().map(|ct| serde_json::json!({
+            "id": ct.id,
+            "name": ct.name,
+            "cpu_percent": ct.resource_usage.cpu_percent,
+            "memory_percent": ct.resource_usage.memory_percent,
+            "disk_percent": ct.resource_usage.disk_usage,
+            "memory_pressure_avg60": ct.resource_usage.pressure.as_ref().and_then(|p| p.memory).map(|m| m.some.avg60),
+            "open_ports": ct.security_assessment.open_ports,
+        })).collect::<Vec<_>>(),
+        "vms": vm_analysis.iter().map(|vm| serde_json::json!({
+            "id": vm.id,
+            "name": vm.name,
+            "cpu_percent": vm.performance_metrics.cpu_usage,
+            "memory_percent": vm.performance_metrics.memory_usage,
+            "cpu_cores": vm.resource_allocation.cpu_cores,
+            "memory_gb": vm.resource_allocation.memory_gb,
+        })).collect::<Vec<_>>(),
+    });
+    let ai_config = app_config().ai;
+    let ai_prompt = format!(
+        "You are reviewing a Proxmox homelab. Here is a snapshot of the host, its storage, containers and VMs:\n\n{}\n\nPoint out concrete problems and improvements.\n\n{}",
+        summary, ECOSYSTEM_SCAN_FORMAT
+    );
+    let advice = match ollama_generate(&ai_config, &ai_config.model, &ai_prompt, true).await {
+        Ok(response) => serde_json::from_str::<serde_json::Value>(response.trim()).unwrap_or_default(),
+        Err(e) => {
+            tracing::warn!("Ecosystem scan AI analysis unavailable: {}", e);
+            serde_json::Value::Null
+        }
+    };
+
+    let storage_health = host_info.storage_info.iter()
+        .map(|storage| (100.0 - (storage.usage_percent as f32 - 80.0).max(0.0) * 5.0).max(0.0))
+        .fold(100.0_f32, f32::min);
+    let latency_issues = ai_list(&advice, "network", "latency_issues");
+    let network_security_concerns = ai_list(&advice, "network", "security_concerns");
+    // Nothing measures the network except the AI review, so without it there is no network score
+    let network_health = advice["network"].is_object()
+        .then(|| (100.0 - latency_issues.len() as f32 * 10.0 - network_security_concerns.len() as f32 * 5.0).max(0.0));
+    let average = |scores: Vec<f32>| if scores.is_empty() { 100.0 } else { scores.iter().sum::<f32>() / scores.len() as f32 };
+    let security_score = average(container_analysis.iter().map(|ct| ct.security_assessment.score).collect());
+    let performance_score = average(container_analysis.iter().map(|ct| ct.health_score)
+        .chain(vm_analysis.iter().map(|vm| vm.health_score))
+        .collect());
+
+    let scan = ProxmoxEcosystemScan {
+        timestamp: Utc::now(),
+        host_analysis: HostAnalysis {
+            cpu_optimization: ai_list(&advice, "host", "cpu_optimization"),
+            memory_optimization: ai_list(&advice, "host", "memory_optimization"),
+            network_optimization: ai_list(&advice, "host", "network_optimization"),
+            storage_optimization: ai_list(&advice, "host", "storage_optimization"),
+            security_issues: ai_list(&advice, "host", "security_issues"),
+            configuration_recommendations: Vec::new(),
         },
-        AiSuggestion {
-            suggestion: "Improve network streaming configuration".to_string(),
-            explanation: "Consider adjusting network settings for better throughput".to_string(),
-            confidence: 0.9,
+        container_analysis,
+        vm_analysis,
+        network_analysis: NetworkAnalysis {
+            overall_health: network_health,
+            bandwidth_utilization: 0.0,
+            latency_issues,
+            security_concerns: network_security_concerns,
+            optimization_recommendations: ai_list(&advice, "network", "optimization_recommendations"),
+            firewall_recommendations: ai_list(&advice, "network", "firewall_recommendations"),
         },
-        AiSuggestion {
-            suggestion: "Upgrade media server software".to_string(),
-            explanation: "Keeping server software up-to-date can enhance performance and security".to_string(),
-            confidence: 0.85,
+        storage_analysis: StorageAnalysis {
+            overall_health: storage_health,
+            usage_patterns: host_info.storage_info.iter()
+                .map(|storage| format!("{} ({}): {:.1}% used, {} of {}", storage.name, storage.storage_type, storage.usage_percent, storage.used, storage.total))
+                .collect(),
+            performance_bottlenecks: ai_list(&advice, "storage", "performance_bottlenecks"),
+            redundancy_assessment: advice["storage"]["redundancy_assessment"].as_str().unwrap_or("Unknown").to_string(),
+            optimization_recommendations: ai_list(&advice, "storage", "optimization_recommendations"),
+            backup_recommendations: ai_list(&advice, "storage", "backup_recommendations"),
         },
-    ];
-    
-    Ok(suggestions)
+        security_analysis: SecurityAnalysis {
+            overall_score: security_score,
+            vulnerabilities: Vec::new(),
+            access_control_issues: ai_list(&advice, "security", "access_control_issues"),
+            network_security_gaps: ai_list(&advice, "security", "network_security_gaps"),
+            compliance_recommendations: ai_list(&advice, "security", "compliance_recommendations"),
+            hardening_suggestions: ai_list(&advice, "security", "hardening_suggestions"),
+        },
+        performance_analysis: PerformanceAnalysis {
+            overall_score: performance_score,
+            cpu_bottlenecks,
+            memory_bottlenecks,
+            io_bottlenecks: Vec::new(),
+            network_bottlenecks: Vec::new(),
+            scaling_recommendations: ai_list(&advice, "performance", "scaling_recommendations"),
+        },
+        optimization_recommendations: ai_recommendations(&advice),
+        overall_health_score: average([performance_score, security_score, storage_health].into_iter().chain(network_health).collect()),
+    };
+
+    if let Ok(serialized) = serde_json::to_string(&scan) {
+        store_in_cache(cache_key, &serialized);
+    }
+
+    Ok(scan)
 }
 
 #[tauri::command]
 async fn read_container_config(container_id: u32, config_path: String) -> Result<String, String> {
-    let output = Command::new("ssh")
-        .args(["proxmox", &format!("pct exec {} -- cat '{}'", container_id, config_path)])
-        .output()
+    let output = run_remote(&guest_host(container_id).await, &[&format!("pct exec {} -- cat {}", container_id, shell_escape(&config_path))], *COMMAND_TIMEOUT)
+        .await
         .map_err(|e| format!("Failed to read config: {}", e))?
         .stdout;
     
@@ -1998,23 +10080,29 @@ async fn read_container_config(container_id: u32, config_path: String) -> Result
 }
 
 #[tauri::command]
-async fn write_container_config(container_id: u32, config_path: String, _content: String) -> Result<String, String> {
-    // Create backup first
-    let _backup_output = Command::new("ssh")
-        .args(["proxmox", &format!("pct exec {} -- cp '{}' '{}.backup-{}'", container_id, config_path, config_path, chrono::Utc::now().timestamp())])
-        .output()
-        .map_err(|e| format!("Failed to create backup: {}", e))?;
-    
-    // Write new content
-    let _output = Command::new("ssh")
-        .args(["proxmox", &format!("pct exec {} -- tee '{}'", container_id, config_path)])
-        .stdin(std::process::Stdio::piped())
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped())
-        .spawn()
-        .map_err(|e| format!("Failed to write config: {}", e))?;
-    
-    Ok("Configuration updated successfully".to_string())
+async fn write_container_config(container_id: u32, config_path: String, content: String) -> Result<String, String> {
+    audited("write_container_config", format!("ct:{}", container_id), serde_json::json!({ "container_id": container_id, "config_path": config_path, "content_bytes": content.len() }), async move {
+        // Create backup first
+        let backup_path = backup_config_file(&config_path, Some(container_id), None).await?;
+    
+        // Write new content
+        let host = guest_host(container_id).await;
+        let tee_command = format!("pct exec {} -- tee {}", container_id, shell_escape(&config_path));
+        let description = format!("ssh {} {}", host, tee_command);
+        let _permit = acquire_ssh_permit().await;
+        let mut command = tokio::process::Command::new("ssh");
+        command.args([&host, &tee_command]);
+        let output = run_process(command, Some(content), *COMMAND_TIMEOUT, &description).await?;
+    
+        if output.status.success() {
+            if backup_path.is_some() {
+                prune_config_backups(&config_path, Some(container_id), None).await;
+            }
+            Ok("Configuration updated successfully".to_string())
+        } else {
+            Err(format!("Failed to write config file: {}", String::from_utf8_lossy(&output.stderr)))
+        }
+    }).await
 }
 
 // Add missing get_container_metadata function
@@ -2063,6 +10151,109 @@ fn store_in_cache(key: &str, data: &str) {
     }
 }
 
+// Helper function to drop a stale entry from the cache
+fn remove_from_cache(key: &str) {
+    if let Ok(mut cache) = DATA_CACHE.write() {
+        cache.remove(key);
+    }
+}
+
+// Helper function to tell whether the host answers at all. The overview and host info swallow
+// per-call failures, so the warmer asks explicitly before refreshing.
+async fn host_reachable() -> Result<(), String> {
+    if app_config().transport.mode == "pve_api" {
+        return transport().cluster_resources().await.map(|_| ());
+    }
+    let status = check_connection().await?;
+    if status.reachable && status.auth_ok {
+        Ok(())
+    } else {
+        Err(status.message)
+    }
+}
+
+// Refresh the overview and host info shortly before their cache entries expire, so interactive
+// calls keep hitting the cache. A refresh only replaces the entry when it succeeds; while the host
+// is unreachable the old entries stay and the wait doubles (up to 10 minutes) until it answers again.
+async fn run_cache_warmer(interval: std::time::Duration) {
+    let max_backoff = std::time::Duration::from_secs(600);
+    let mut delay = interval;
+    loop {
+        // An entry that won't survive until the next tick is refreshed now
+        let margin = interval.as_secs() as i64;
+        let overview_due = !is_cache_valid_with_duration("system_overview", *CACHE_DURATION - margin);
+        let host_info_due = !is_cache_valid_with_duration("proxmox_host_info", *HOST_CACHE_DURATION - margin);
+
+        let mut failed = false;
+        if overview_due || host_info_due {
+            if let Err(e) = host_reachable().await {
+                tracing::warn!("Cache warmer skipped a refresh, host unreachable: {}", e);
+                failed = true;
+            }
+        }
+        if !failed && overview_due {
+            match fetch_system_overview().await {
+                Ok(overview) => {
+                    if let Ok(serialized) = serde_json::to_string(&overview) {
+                        store_in_cache("system_overview", &serialized);
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("Cache warmer failed to refresh the overview: {}", e);
+                    failed = true;
+                }
+            }
+        }
+        if !failed && host_info_due {
+            match fetch_proxmox_host_info().await {
+                Ok(host_info) => {
+                    if let Ok(serialized) = serde_json::to_string(&host_info) {
+                        store_in_cache("proxmox_host_info", &serialized);
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("Cache warmer failed to refresh host info: {}", e);
+                    failed = true;
+                }
+            }
+        }
+
+        delay = if failed { (delay * 2).min(max_backoff) } else { interval };
+        tokio::time::sleep(delay).await;
+    }
+}
+
+// Tauri command to start (or restart with a new interval) the background cache refresh
+#[tauri::command]
+async fn start_cache_warmer(interval_secs: Option<u64>) -> Result<String, String> {
+    let interval_secs = interval_secs.unwrap_or(60);
+    let shortest_ttl = (*CACHE_DURATION).min(*HOST_CACHE_DURATION) as u64;
+    if interval_secs < 10 || interval_secs >= shortest_ttl {
+        return Err(format!("Interval must be between 10 and {} seconds", shortest_ttl - 1));
+    }
+
+    // Only one warmer runs at a time; a previous one is replaced
+    let mut warmer = CACHE_WARMER.lock().map_err(|e| format!("Failed to lock cache warmer: {}", e))?;
+    if let Some(previous) = warmer.take() {
+        previous.abort();
+    }
+    *warmer = Some(tokio::spawn(run_cache_warmer(std::time::Duration::from_secs(interval_secs))));
+    Ok(format!("Cache warmer running every {} seconds", interval_secs))
+}
+
+// Tauri command to stop the background cache refresh
+#[tauri::command]
+async fn stop_cache_warmer() -> Result<String, String> {
+    let mut warmer = CACHE_WARMER.lock().map_err(|e| format!("Failed to lock cache warmer: {}", e))?;
+    match warmer.take() {
+        Some(handle) => {
+            handle.abort();
+            Ok("Cache warmer stopped".to_string())
+        }
+        None => Ok("Cache warmer was not running".to_string()),
+    }
+}
+
 #[tauri::command]
 async fn get_system_overview() -> Result<SystemOverview, String> {
     let cache_key = "system_overview";
@@ -2075,101 +10266,49 @@ async fn get_system_overview() -> Result<SystemOverview, String> {
             }
         }
     }
+
+    let system_overview = fetch_system_overview().await?;
+    if let Ok(serialized) = serde_json::to_string(&system_overview) {
+        store_in_cache(cache_key, &serialized);
+    }
+
+    Ok(system_overview)
+}
+
+// Helper function to build the overview from fresh data, bypassing and leaving the cache alone
+async fn fetch_system_overview() -> Result<SystemOverview, String> {
     let mut containers = Vec::new();
     let mut vms = Vec::new();
 
     // Get list of existing containers and VMs first
     let existing_container_ids = get_existing_containers().await.unwrap_or_default();
     let existing_vm_ids = get_existing_vms().await.unwrap_or_default();
-
-    // Container definitions for metadata
-    let container_metadata = [
-        // Core Infrastructure (100-199)
-        (100, "WireGuard", "VPN access and secure tunneling", "Core Infrastructure"),
-        (101, "Gluetun", "VPN client container for other services", "Core Infrastructure"),
-        (102, "Flaresolverr", "Cloudflare solver proxy", "Core Infrastructure"),
-        (103, "Traefik", "Reverse proxy and load balancer", "Core Infrastructure"),
-        (104, "Vaultwarden", "Password manager server", "Core Infrastructure"),
-        (105, "Valkey", "Redis-compatible in-memory database", "Core Infrastructure"),
-        (106, "PostgreSQL", "Primary database server", "Core Infrastructure"),
-        (107, "Authentik", "Identity provider and SSO", "Core Infrastructure"),
-        
-        // Essential Media Services (210-229)
-        (210, "Prowlarr", "Indexer manager and proxy", "Essential Media Services"),
-        (211, "Jackett", "Torrent indexer proxy", "Essential Media Services"),
-        (212, "QBittorrent", "BitTorrent client", "Essential Media Services"),
-        (214, "Sonarr", "TV series management", "Essential Media Services"),
-        (215, "Radarr", "Movie management", "Essential Media Services"),
-        (216, "Proxarr", "Proxy management for *arr apps", "Essential Media Services"),
-        (217, "Readarr", "Book and audiobook management", "Essential Media Services"),
-        (219, "Whisparr", "Adult content management", "Essential Media Services"),
-        (220, "Sonarr Extended", "Extended TV series management", "Essential Media Services"),
-        (221, "Radarr Extended", "Extended movie management", "Essential Media Services"),
-        (223, "Autobrr", "Automated torrent management", "Essential Media Services"),
-        (224, "Deluge", "Alternative BitTorrent client", "Essential Media Services"),
-        
-        // Media Servers (230-239)
-        (230, "Plex", "Media server and streaming platform", "Media Servers"),
-        (231, "Jellyfin", "Open-source media server", "Media Servers"),
-        (232, "Audiobookshelf", "Audiobook and podcast server", "Media Servers"),
-        (233, "Calibre-web", "E-book server and manager", "Media Servers"),
-        (234, "IPTV-Proxy", "IPTV streaming proxy", "Media Servers"),
-        (235, "TVHeadend", "TV streaming server", "Media Servers"),
-        (236, "Tdarr Server", "Media transcoding server", "Media Servers"),
-        (237, "Tdarr Node", "Media transcoding worker", "Media Servers"),
-        
-        // Enhancement Services (240-250)
-        (240, "Bazarr", "Subtitle management", "Enhancement Services"),
-        (241, "Overseerr", "Media request management", "Enhancement Services"),
-        (242, "Jellyseerr", "Jellyfin request management", "Enhancement Services"),
-        (243, "Ombi", "Media request platform", "Enhancement Services"),
-        (244, "Tautulli", "Plex monitoring and statistics", "Enhancement Services"),
-        (245, "Kometa", "Plex metadata management", "Enhancement Services"),
-        (246, "Gaps", "Plex collection gap finder", "Enhancement Services"),
-        (247, "Janitorr", "Media cleanup automation", "Enhancement Services"),
-        (248, "Decluttarr", "Media library decluttering", "Enhancement Services"),
-        (249, "Watchlistarr", "Watchlist synchronization", "Enhancement Services"),
-        (250, "Traktarr", "Trakt.tv integration", "Enhancement Services"),
-        
-        // Monitoring & Analytics (260-269)
-        (260, "Prometheus", "Metrics collection and monitoring", "Monitoring & Analytics"),
-        (261, "Grafana", "Metrics visualization and dashboards", "Monitoring & Analytics"),
-        (262, "Checkrr", "Service health checking", "Monitoring & Analytics"),
-        
-        // Management & Utilities (270-279)
-        (270, "FileBot", "File renaming and organization", "Management & Utilities"),
-        (271, "FlexGet", "Automated content downloading", "Management & Utilities"),
-        (272, "Buildarr", "Configuration management for *arr apps", "Management & Utilities"),
-        (274, "Organizr", "Service organization dashboard", "Management & Utilities"),
-        (275, "Homarr", "Modern dashboard for services", "Management & Utilities"),
-        (276, "Homepage", "Customizable homepage dashboard", "Management & Utilities"),
-        (277, "Recyclarr", "Configuration recycling for *arr apps", "Management & Utilities"),
-        (278, "CrowdSec", "Collaborative security engine", "Management & Utilities"),
-        (279, "Tailscale", "Secure networking mesh", "Management & Utilities"),
-    ];
-
-    // VM definitions for metadata
-    let vm_metadata = [
-        (500, "Home Assistant", "Home automation platform"),
-        (611, "Ziggy", "Media bridging and streaming VM"),
-        (612, "Bliss OS Android", "Android emulation and testing environment"),
-        (900, "AI System", "Artificial intelligence services"),
-    ];
+    let resources = transport().cluster_resources().await.unwrap_or_default();
+    let templates = templates_from_resources(&resources);
+    let is_template = |id: u32, is_vm: bool| templates.iter().any(|t| t.id == id && t.is_vm == is_vm);
+    let samples = resource_samples(&resources);
+    let health_score = |target: String| {
+        let usage = |metric: &str| samples.iter()
+            .find(|(sample_target, sample_metric, _)| *sample_target == target && *sample_metric == metric)
+            .map(|(_, _, value)| *value as f32)
+            .unwrap_or(0.0);
+        let resource_usage = ResourceUsage {
+            cpu_percent: usage("cpu"),
+            memory_percent: usage("memory"),
+            disk_usage: usage("disk"),
+            io: None,
+            pressure: None,
+            efficiency_score: 0.0,
+        };
+        // Per-guest service and update checks are too slow for the overview, so only usage counts here
+        compute_health_score(&resource_usage, &[], &SecurityFlags::default())
+    };
 
     // Only get status for containers that actually exist
     for &container_id in &existing_container_ids {
         if let Ok(mut container_info) = get_container_status(container_id).await {
-            // Look up metadata for this container
-            if let Some((_, name, description, category)) = container_metadata.iter().find(|(id, _, _, _)| *id == container_id) {
-                container_info.name = name.to_string();
-                container_info.category = category.to_string();
-                container_info.description = description.to_string();
-            } else {
-                // Fallback for unknown containers
-                container_info.name = format!("Container {}", container_id);
-                container_info.category = get_container_category(container_id);
-                container_info.description = "Unknown container".to_string();
-            }
+            container_info.template = is_template(container_id, false);
+            container_info.health_score = Some(health_score(format!("ct:{}", container_id)));
             containers.push(container_info);
         }
     }
@@ -2177,15 +10316,8 @@ async fn get_system_overview() -> Result<SystemOverview, String> {
     // Only get status for VMs that actually exist
     for &vm_id in &existing_vm_ids {
         if let Ok(mut vm_info) = get_vm_status(vm_id).await {
-            // Look up metadata for this VM
-            if let Some((_, name, description)) = vm_metadata.iter().find(|(id, _, _)| *id == vm_id) {
-                vm_info.name = name.to_string();
-                vm_info.description = description.to_string();
-            } else {
-                // Fallback for unknown VMs
-                vm_info.name = format!("VM {}", vm_id);
-                vm_info.description = "Unknown virtual machine".to_string();
-            }
+            vm_info.template = is_template(vm_id, true);
+            vm_info.health_score = Some(health_score(format!("vm:{}", vm_id)));
             vms.push(vm_info);
         }
     }
@@ -2202,15 +10334,239 @@ async fn get_system_overview() -> Result<SystemOverview, String> {
         vms,
         last_updated: Utc::now(),
     };
-    
-    // Store in cache
-    if let Ok(serialized) = serde_json::to_string(&system_overview) {
-        store_in_cache(cache_key, &serialized);
-    }
-    
+
     Ok(system_overview)
 }
 
+// Helper function to load the pinned guest ids, empty when none have been saved
+fn load_favorites() -> Result<Vec<u32>, String> {
+    match std::fs::read_to_string(app_data_path("favorites.json")?) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse favorites: {}", e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("Failed to read favorites: {}", e)),
+    }
+}
+
+// Helper function to persist the pinned guest ids
+fn save_favorites(favorites: &[u32]) -> Result<(), String> {
+    let serialized = serde_json::to_string_pretty(favorites)
+        .map_err(|e| format!("Failed to serialize favorites: {}", e))?;
+    std::fs::write(app_data_path("favorites.json")?, serialized)
+        .map_err(|e| format!("Failed to write favorites: {}", e))
+}
+
+// Helper function to get the ids of every container and VM on the node
+async fn existing_guest_ids() -> Result<Vec<u32>, String> {
+    let mut ids = get_existing_containers().await?;
+    ids.extend(get_existing_vms().await?);
+    Ok(ids)
+}
+
+// Tauri command to get the pinned guest ids in pin order. Guests that no longer exist are dropped
+// from the saved list.
+#[tauri::command]
+async fn get_favorites() -> Result<Vec<u32>, String> {
+    let favorites = load_favorites()?;
+    let existing = existing_guest_ids().await?;
+    let (kept, stale): (Vec<u32>, Vec<u32>) = favorites.into_iter().partition(|id| existing.contains(id));
+    if !stale.is_empty() {
+        tracing::info!(?stale, "Pruning favorites for guests that no longer exist");
+        save_favorites(&kept)?;
+    }
+    Ok(kept)
+}
+
+// Tauri command to replace the pinned guest ids; every id has to be an existing container or VM
+#[tauri::command]
+async fn set_favorites(ids: Vec<u32>) -> Result<Vec<u32>, String> {
+    let existing = existing_guest_ids().await?;
+    let unknown: Vec<String> = ids.iter()
+        .filter(|id| !existing.contains(id))
+        .map(|id| id.to_string())
+        .collect();
+    if !unknown.is_empty() {
+        return Err(format!("No container or VM with id {}", unknown.join(", ")));
+    }
+
+    let mut favorites: Vec<u32> = Vec::new();
+    for id in ids {
+        if !favorites.contains(&id) {
+            favorites.push(id);
+        }
+    }
+    save_favorites(&favorites)?;
+    Ok(favorites)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SortedOverview {
+    #[serde(flatten)]
+    overview: SystemOverview,
+    favorites: Vec<u32>,
+}
+
+// Tauri command to get the system overview with pinned guests first, in pin order
+#[tauri::command]
+async fn get_overview_sorted() -> Result<SortedOverview, String> {
+    let mut overview = get_system_overview().await?;
+    let favorites = get_favorites().await?;
+
+    // Stable sort, so unpinned guests keep the overview's order
+    let rank = |id: u32| favorites.iter().position(|favorite| *favorite == id).unwrap_or(usize::MAX);
+    overview.containers.sort_by_key(|container| rank(container.id));
+    overview.vms.sort_by_key(|vm| rank(vm.id));
+
+    Ok(SortedOverview { overview, favorites })
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
+struct GuestQuery {
+    name_contains: Option<String>, // Case-insensitive, matched against the live and known display names
+    category: Option<String>,      // Container category; VMs never match a category filter
+    status: Option<String>,
+    id_range: Option<(u32, u32)>,  // Inclusive
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GuestSearchResult {
+    containers: Vec<ContainerInfo>,
+    vms: Vec<VMInfo>,
+}
+
+// Helper function to check the filters shared by containers and VMs
+fn guest_matches(query: &GuestQuery, id: u32, names: &[&str], status: &str) -> bool {
+    if let Some((low, high)) = query.id_range {
+        if id < low || id > high {
+            return false;
+        }
+    }
+    if let Some(wanted) = &query.status {
+        if !status.eq_ignore_ascii_case(wanted) {
+            return false;
+        }
+    }
+    if let Some(needle) = &query.name_contains {
+        let needle = needle.to_lowercase();
+        if !names.iter().any(|name| name.to_lowercase().contains(&needle)) {
+            return false;
+        }
+    }
+    true
+}
+
+// Tauri command to filter the guests of the system overview, served from its cache when fresh
+#[tauri::command]
+async fn search_guests(query: GuestQuery) -> Result<GuestSearchResult, String> {
+    let overview = get_system_overview().await?;
+
+    let containers = overview.containers.into_iter()
+        .filter(|container| {
+            let display_name = get_container_display_name(container.id);
+            guest_matches(&query, container.id, &[&container.name, &display_name], &container.status)
+                && query.category.as_ref().map_or(true, |category| get_container_category(container.id).eq_ignore_ascii_case(category))
+        })
+        .collect();
+
+    let vms = if query.category.is_some() {
+        Vec::new()
+    } else {
+        overview.vms.into_iter()
+            .filter(|vm| guest_matches(&query, vm.id, &[&vm.name, &get_vm_name(vm.id)], &vm.status))
+            .collect()
+    };
+
+    Ok(GuestSearchResult { containers, vms })
+}
+
+// One row of the paged overview; expand a row with get_container_details or get_vm_status
+#[derive(Debug, Serialize, Deserialize)]
+struct GuestSummary {
+    id: u32,
+    is_vm: bool,
+    name: String,
+    status: String,
+    category: String,
+    template: bool,
+    lock: Option<String>,
+    health_score: Option<f32>,
+    uptime_seconds: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OverviewPage {
+    guests: Vec<GuestSummary>,
+    total: usize,
+    offset: usize,
+    limit: usize,
+    last_updated: DateTime<Utc>,
+}
+
+// Helper function to order overview rows by one of overview_page's sort keys. Ties fall back to the
+// id so rows keep their place between pages.
+fn sort_guest_summaries(guests: &mut [GuestSummary], sort_by: &str) {
+    guests.sort_by(|a, b| {
+        let primary = match sort_by {
+            "name" => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            "status" => a.status.cmp(&b.status),
+            "category" => a.category.cmp(&b.category),
+            // Longest-running first; stopped guests (no uptime) go last
+            "uptime" => b.uptime_seconds.cmp(&a.uptime_seconds),
+            _ => std::cmp::Ordering::Equal,
+        };
+        primary.then(a.id.cmp(&b.id)).then(a.is_vm.cmp(&b.is_vm))
+    });
+}
+
+// Tauri command to get one page of containers and VMs, paged from the cached system overview
+#[tauri::command]
+async fn overview_page(offset: Option<usize>, limit: Option<usize>, sort_by: Option<String>) -> Result<OverviewPage, String> {
+    let offset = offset.unwrap_or(0);
+    let limit = limit.unwrap_or(50).max(1);
+    let sort_by = sort_by.unwrap_or_else(|| "id".to_string());
+    if !["id", "name", "status", "category", "uptime"].contains(&sort_by.as_str()) {
+        return Err(format!("Unknown sort '{}', expected id, name, status, category or uptime", sort_by));
+    }
+
+    let overview = get_system_overview().await?;
+    let mut guests: Vec<GuestSummary> = overview.containers.into_iter()
+        .map(|container| GuestSummary {
+            id: container.id,
+            is_vm: false,
+            name: container.name,
+            status: container.status,
+            category: container.category,
+            template: container.template,
+            lock: container.lock,
+            health_score: container.health_score,
+            uptime_seconds: container.uptime_seconds,
+        })
+        .chain(overview.vms.into_iter().map(|vm| GuestSummary {
+            id: vm.id,
+            is_vm: true,
+            name: vm.name,
+            status: vm.status,
+            category: "Virtual Machines".to_string(),
+            template: vm.template,
+            lock: vm.lock,
+            health_score: vm.health_score,
+            uptime_seconds: vm.uptime_seconds,
+        }))
+        .collect();
+
+    sort_guest_summaries(&mut guests, &sort_by);
+
+    let total = guests.len();
+    Ok(OverviewPage {
+        guests: guests.into_iter().skip(offset).take(limit).collect(),
+        total,
+        offset,
+        limit,
+        last_updated: overview.last_updated,
+    })
+}
+
 // Helper functions
 async fn get_container_info(container_id: u32) -> Result<ContainerInfo, String> {
     // This would typically fetch detailed container information
@@ -2227,10 +10583,110 @@ async fn get_container_info(container_id: u32) -> Result<ContainerInfo, String>
         web_ui_url: None,
         os_info: None,
         running_processes: Vec::new(),
+        template: false,
+        health_score: None,
+        lock: None,
+        usage_stale: false,
+        usage_sampled_at: None,
+        uptime_seconds: None,
     })
 }
 
+// Built-in names, descriptions and categories of the known containers; overridden per id by guest_metadata
+const DEFAULT_CONTAINER_METADATA: &[(u32, &str, &str, &str)] = &[
+    // Core Infrastructure (100-199)
+    (100, "WireGuard", "VPN access and secure tunneling", "Core Infrastructure"),
+    (101, "Gluetun", "VPN client container for other services", "Core Infrastructure"),
+    (102, "Flaresolverr", "Cloudflare solver proxy", "Core Infrastructure"),
+    (103, "Traefik", "Reverse proxy and load balancer", "Core Infrastructure"),
+    (104, "Vaultwarden", "Password manager server", "Core Infrastructure"),
+    (105, "Valkey", "Redis-compatible in-memory database", "Core Infrastructure"),
+    (106, "PostgreSQL", "Primary database server", "Core Infrastructure"),
+    (107, "Authentik", "Identity provider and SSO", "Core Infrastructure"),
+    
+    // Essential Media Services (210-229)
+    (210, "Prowlarr", "Indexer manager and proxy", "Essential Media Services"),
+    (211, "Jackett", "Torrent indexer proxy", "Essential Media Services"),
+    (212, "QBittorrent", "BitTorrent client", "Essential Media Services"),
+    (214, "Sonarr", "TV series management", "Essential Media Services"),
+    (215, "Radarr", "Movie management", "Essential Media Services"),
+    (216, "Proxarr", "Proxy management for *arr apps", "Essential Media Services"),
+    (217, "Readarr", "Book and audiobook management", "Essential Media Services"),
+    (219, "Whisparr", "Adult content management", "Essential Media Services"),
+    (220, "Sonarr Extended", "Extended TV series management", "Essential Media Services"),
+    (221, "Radarr Extended", "Extended movie management", "Essential Media Services"),
+    (223, "Autobrr", "Automated torrent management", "Essential Media Services"),
+    (224, "Deluge", "Alternative BitTorrent client", "Essential Media Services"),
+    
+    // Media Servers (230-239)
+    (230, "Plex", "Media server and streaming platform", "Media Servers"),
+    (231, "Jellyfin", "Open-source media server", "Media Servers"),
+    (232, "Audiobookshelf", "Audiobook and podcast server", "Media Servers"),
+    (233, "Calibre-web", "E-book server and manager", "Media Servers"),
+    (234, "IPTV-Proxy", "IPTV streaming proxy", "Media Servers"),
+    (235, "TVHeadend", "TV streaming server", "Media Servers"),
+    (236, "Tdarr Server", "Media transcoding server", "Media Servers"),
+    (237, "Tdarr Node", "Media transcoding worker", "Media Servers"),
+    
+    // Enhancement Services (240-250)
+    (240, "Bazarr", "Subtitle management", "Enhancement Services"),
+    (241, "Overseerr", "Media request management", "Enhancement Services"),
+    (242, "Jellyseerr", "Jellyfin request management", "Enhancement Services"),
+    (243, "Ombi", "Media request platform", "Enhancement Services"),
+    (244, "Tautulli", "Plex monitoring and statistics", "Enhancement Services"),
+    (245, "Kometa", "Plex metadata management", "Enhancement Services"),
+    (246, "Gaps", "Plex collection gap finder", "Enhancement Services"),
+    (247, "Janitorr", "Media cleanup automation", "Enhancement Services"),
+    (248, "Decluttarr", "Media library decluttering", "Enhancement Services"),
+    (249, "Watchlistarr", "Watchlist synchronization", "Enhancement Services"),
+    (250, "Traktarr", "Trakt.tv integration", "Enhancement Services"),
+    
+    // Monitoring & Analytics (260-269)
+    (260, "Prometheus", "Metrics collection and monitoring", "Monitoring & Analytics"),
+    (261, "Grafana", "Metrics visualization and dashboards", "Monitoring & Analytics"),
+    (262, "Checkrr", "Service health checking", "Monitoring & Analytics"),
+    
+    // Management & Utilities (270-279)
+    (270, "FileBot", "File renaming and organization", "Management & Utilities"),
+    (271, "FlexGet", "Automated content downloading", "Management & Utilities"),
+    (272, "Buildarr", "Configuration management for *arr apps", "Management & Utilities"),
+    (274, "Organizr", "Service organization dashboard", "Management & Utilities"),
+    (275, "Homarr", "Modern dashboard for services", "Management & Utilities"),
+    (276, "Homepage", "Customizable homepage dashboard", "Management & Utilities"),
+    (277, "Recyclarr", "Configuration recycling for *arr apps", "Management & Utilities"),
+    (278, "CrowdSec", "Collaborative security engine", "Management & Utilities"),
+    (279, "Tailscale", "Secure networking mesh", "Management & Utilities"),
+
+    (900, "AI Container", "Artificial intelligence services", "Other"),
+];
+
+// Built-in names and descriptions of the known VMs
+const DEFAULT_VM_METADATA: &[(u32, &str, &str)] = &[
+    (500, "Home Assistant", "Home automation platform"),
+    (611, "Ziggy", "Media bridging and streaming VM"),
+    (612, "Bliss OS Android", "Android emulation and testing environment"),
+    (900, "AI System", "Artificial intelligence services"),
+];
+
+// Built-in ssh aliases of the known VMs; overridden per id by guest_metadata's ssh_alias
+const DEFAULT_VM_SSH_ALIASES: &[(u32, &str)] = &[
+    (500, "homeassistant"),
+    (611, "alexa"),
+    (900, "ai-system"),
+];
+
+// Helper function to get the user's metadata override for a guest, if any
+fn guest_metadata_override(id: u32) -> GuestMetadata {
+    app_config().guest_metadata.get(&id).cloned().unwrap_or_default()
+}
+
 fn get_container_category(container_id: u32) -> String {
+    if let Some(category) = guest_metadata_override(container_id).category {
+        return category;
+    }
+    if let Some((_, _, _, category)) = DEFAULT_CONTAINER_METADATA.iter().find(|(id, _, _, _)| *id == container_id) {
+        return category.to_string();
+    }
     match container_id {
         100..=199 => "Core Infrastructure".to_string(),
         210..=229 => "Essential Media Services".to_string(),
@@ -2243,137 +10699,342 @@ fn get_container_category(container_id: u32) -> String {
 }
 
 fn get_container_description(container_id: u32) -> String {
-    match container_id {
-        100 => "VPN access and secure tunneling".to_string(),
-        101 => "VPN client container for other services".to_string(),
-        102 => "Cloudflare solver proxy".to_string(),
-        103 => "Reverse proxy and load balancer".to_string(),
-        104 => "Password manager server".to_string(),
-        105 => "Redis-compatible in-memory database".to_string(),
-        106 => "Primary database server".to_string(),
-        107 => "Identity provider and SSO".to_string(),
-        _ => "Service container".to_string(),
-    }
+    guest_metadata_override(container_id).description
+        .or_else(|| DEFAULT_CONTAINER_METADATA.iter()
+            .find(|(id, _, _, _)| *id == container_id)
+            .map(|(_, _, description, _)| description.to_string()))
+        .unwrap_or_else(|| "Service container".to_string())
 }
 
 fn get_vm_name(vm_id: u32) -> String {
-    match vm_id {
-        500 => "Home Assistant".to_string(),
-        611 => "Ziggy".to_string(),
-        612 => "Bliss OS Android".to_string(),
-        900 => "AI System".to_string(),
-        _ => format!("VM {}", vm_id),
-    }
+    guest_metadata_override(vm_id).name
+        .or_else(|| DEFAULT_VM_METADATA.iter()
+            .find(|(id, _, _)| *id == vm_id)
+            .map(|(_, name, _)| name.to_string()))
+        .unwrap_or_else(|| format!("VM {}", vm_id))
 }
 
 fn get_vm_description(vm_id: u32) -> String {
-    match vm_id {
-        500 => "Home automation platform".to_string(),
-        611 => "Media bridging and streaming VM".to_string(),
-        612 => "Android emulation and testing environment".to_string(),
-        900 => "Artificial intelligence services".to_string(),
-        _ => "Virtual machine".to_string(),
-    }
+    guest_metadata_override(vm_id).description
+        .or_else(|| DEFAULT_VM_METADATA.iter()
+            .find(|(id, _, _)| *id == vm_id)
+            .map(|(_, _, description)| description.to_string()))
+        .unwrap_or_else(|| "Virtual machine".to_string())
 }
 
 fn get_container_display_name(container_id: u32) -> String {
-    match container_id {
-        100 => "WireGuard".to_string(),
-        101 => "Gluetun".to_string(),
-        102 => "Flaresolverr".to_string(),
-        103 => "Traefik".to_string(),
-        104 => "Vaultwarden".to_string(),
-        105 => "Valkey".to_string(),
-        106 => "PostgreSQL".to_string(),
-        107 => "Authentik".to_string(),
-        210 => "Prowlarr".to_string(),
-        211 => "Jackett".to_string(),
-        212 => "QBittorrent".to_string(),
-        214 => "Sonarr".to_string(),
-        215 => "Radarr".to_string(),
-        216 => "Proxarr".to_string(),
-        217 => "Readarr".to_string(),
-        219 => "Whisparr".to_string(),
-        220 => "Sonarr Extended".to_string(),
-        221 => "Radarr Extended".to_string(),
-        223 => "Autobrr".to_string(),
-        224 => "Deluge".to_string(),
-        230 => "Plex".to_string(),
-        231 => "Jellyfin".to_string(),
-        232 => "Audiobookshelf".to_string(),
-        233 => "Calibre-web".to_string(),
-        234 => "IPTV-Proxy".to_string(),
-        235 => "TVHeadend".to_string(),
-        236 => "Tdarr Server".to_string(),
-        237 => "Tdarr Node".to_string(),
-        240 => "Bazarr".to_string(),
-        241 => "Overseerr".to_string(),
-        242 => "Jellyseerr".to_string(),
-        243 => "Ombi".to_string(),
-        244 => "Tautulli".to_string(),
-        245 => "Kometa".to_string(),
-        246 => "Gaps".to_string(),
-        247 => "Janitorr".to_string(),
-        248 => "Decluttarr".to_string(),
-        249 => "Watchlistarr".to_string(),
-        250 => "Traktarr".to_string(),
-        260 => "Prometheus".to_string(),
-        261 => "Grafana".to_string(),
-        262 => "Checkrr".to_string(),
-        270 => "FileBot".to_string(),
-        271 => "FlexGet".to_string(),
-        272 => "Buildarr".to_string(),
-        274 => "Organizr".to_string(),
-        275 => "Homarr".to_string(),
-        276 => "Homepage".to_string(),
-        277 => "Recyclarr".to_string(),
-        278 => "CrowdSec".to_string(),
-        279 => "Tailscale".to_string(),
-        900 => "AI Container".to_string(),
-        _ => format!("CT-{}", container_id),
-    }
-}
-
-fn get_container_web_ui_url(container_id: u32) -> Option<String> {
-    match container_id {
-        100 => Some("http://192.168.122.100:51820".to_string()), // WireGuard
-        103 => Some("http://192.168.122.103:8080".to_string()), // Traefik
-        104 => Some("http://192.168.122.104:80".to_string()), // Vaultwarden
-        107 => Some("http://192.168.122.107:9000".to_string()), // Authentik
-        210 => Some("http://192.168.122.210:9696".to_string()), // Prowlarr
-        211 => Some("http://192.168.122.211:9117".to_string()), // Jackett
-        212 => Some("http://192.168.122.212:8080".to_string()), // QBittorrent
-        214 => Some("http://192.168.122.214:8989".to_string()), // Sonarr
-        215 => Some("http://192.168.122.215:7878".to_string()), // Radarr
-        217 => Some("http://192.168.122.217:8787".to_string()), // Readarr
-        219 => Some("http://192.168.122.219:6969".to_string()), // Whisparr
-        220 => Some("http://192.168.122.220:8989".to_string()), // Sonarr Extended
-        221 => Some("http://192.168.122.221:7878".to_string()), // Radarr Extended
-        223 => Some("http://192.168.122.223:7474".to_string()), // Autobrr
-        224 => Some("http://192.168.122.224:8112".to_string()), // Deluge
-        230 => Some("http://192.168.122.230:32400".to_string()), // Plex
-        231 => Some("http://192.168.122.231:8096".to_string()), // Jellyfin
-        232 => Some("http://192.168.122.232:13378".to_string()), // Audiobookshelf
-        233 => Some("http://192.168.122.233:8083".to_string()), // Calibre-web
-        235 => Some("http://192.168.122.235:9981".to_string()), // TVHeadend
-        236 => Some("http://192.168.122.236:8265".to_string()), // Tdarr Server
-        240 => Some("http://192.168.122.240:6767".to_string()), // Bazarr
-        241 => Some("http://192.168.122.241:5055".to_string()), // Overseerr
-        242 => Some("http://192.168.122.242:5055".to_string()), // Jellyseerr
-        243 => Some("http://192.168.122.243:3579".to_string()), // Ombi
-        244 => Some("http://192.168.122.244:8181".to_string()), // Tautulli
-        261 => Some("http://192.168.122.261:3000".to_string()), // Grafana
-        274 => Some("http://192.168.122.274:80".to_string()), // Organizr
-        275 => Some("http://192.168.122.275:7575".to_string()), // Homarr
-        276 => Some("http://192.168.122.276:3000".to_string()), // Homepage
-        _ => None,
+    guest_metadata_override(container_id).name
+        .or_else(|| DEFAULT_CONTAINER_METADATA.iter()
+            .find(|(id, _, _, _)| *id == container_id)
+            .map(|(_, name, _, _)| name.to_string()))
+        .unwrap_or_else(|| format!("CT-{}", container_id))
+}
+
+// Default web UI port for each known service container
+const DEFAULT_WEB_UI_PORTS: &[(u32, u16)] = &[
+    (100, 51820), // WireGuard
+    (103, 8080), // Traefik
+    (104, 80), // Vaultwarden
+    (107, 9000), // Authentik
+    (210, 9696), // Prowlarr
+    (211, 9117), // Jackett
+    (212, 8080), // QBittorrent
+    (214, 8989), // Sonarr
+    (215, 7878), // Radarr
+    (217, 8787), // Readarr
+    (219, 6969), // Whisparr
+    (220, 8989), // Sonarr Extended
+    (221, 7878), // Radarr Extended
+    (223, 7474), // Autobrr
+    (224, 8112), // Deluge
+    (230, 32400), // Plex
+    (231, 8096), // Jellyfin
+    (232, 13378), // Audiobookshelf
+    (233, 8083), // Calibre-web
+    (235, 9981), // TVHeadend
+    (236, 8265), // Tdarr Server
+    (240, 6767), // Bazarr
+    (241, 5055), // Overseerr
+    (242, 5055), // Jellyseerr
+    (243, 3579), // Ombi
+    (244, 8181), // Tautulli
+    (261, 3000), // Grafana
+    (274, 80), // Organizr
+    (275, 7575), // Homarr
+    (276, 3000), // Homepage
+];
+
+// Helper function to get the effective port table, with user overrides applied
+fn get_web_ui_port_table() -> HashMap<u32, u16> {
+    let config = app_config();
+    let mut ports: HashMap<u32, u16> = DEFAULT_WEB_UI_PORTS.iter().copied().collect();
+    ports.extend(config.web_ui_ports);
+    ports.extend(config.guest_metadata.iter().filter_map(|(id, metadata)| Some((*id, metadata.web_ui_port?))));
+    ports
+}
+
+// Helper function to get the static 192.168.122.<id> address of one of the built-in services. Only those
+// containers follow the convention, and only ids that fit in the last octet make a valid address.
+fn static_guest_address(container_id: u32) -> Option<String> {
+    let known = DEFAULT_WEB_UI_PORTS.iter().any(|(id, _)| *id == container_id);
+    (known && (1..=254).contains(&container_id)).then(|| format!("192.168.122.{}", container_id))
+}
+
+async fn get_container_web_ui_url(container_id: u32) -> Option<String> {
+    let port = get_web_ui_port_table().get(&container_id).copied()?;
+
+    // Prefer the live address, falling back to the static address the built-in services were set up with
+    let host = resolve_guest_ip(container_id, false).await.or_else(|| static_guest_address(container_id))?;
+
+    Some(format!("http://{}:{}", host, port))
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum CertState {
+    Valid,
+    Expiring,          // Within the configured warning window
+    Expired,
+    SelfSigned,
+    Untrusted,         // Signed, but the chain doesn't verify
+    ConnectionRefused,
+    NoTls,             // Something answered, but not with a TLS handshake
+    Unreachable,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct CertStatus {
+    target: String,
+    host: String,
+    port: u16,
+    status: CertState,
+    subject: Option<String>,
+    issuer: Option<String>,
+    not_after: Option<String>,
+    days_until_expiry: Option<i64>,
+    detail: Option<String>, // openssl's verify result or connection error
+}
+
+// Helper function to turn "https://host:port/path", "host:port" or "host" into a host and port (443 by default)
+fn parse_cert_endpoint(endpoint: &str) -> Result<(String, u16), String> {
+    let rest = endpoint.trim();
+    let rest = rest.split_once("://").map(|(_, rest)| rest).unwrap_or(rest);
+    let authority = rest.split('/').next().unwrap_or("");
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) => (host, port.parse::<u16>().map_err(|_| format!("Invalid port in '{}'", endpoint))?),
+        None => (authority, 443),
+    };
+
+    if host.is_empty() || !host.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-') {
+        return Err(format!("Invalid host in '{}'", endpoint));
+    }
+    Ok((host.to_string(), port))
+}
+
+// Helper function to build a CertStatus from the ==connect and ==cert sections printed by check_certificate
+fn parse_cert_check(target: &str, host: &str, port: u16, output: &str, warning_days: i64, now: DateTime<Utc>) -> CertStatus {
+    let mut sections: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut current = "";
+    for line in output.lines() {
+        match line.strip_prefix("==") {
+            Some(name) => current = name,
+            None => sections.entry(current).or_default().push(line),
+        }
+    }
+    let connect = sections.remove("connect").unwrap_or_default();
+    let cert = sections.remove("cert").unwrap_or_default();
+    let field = |name: &str| cert.iter().find_map(|line| line.strip_prefix(name)).map(|value| value.trim().to_string());
+
+    let mut status = CertStatus {
+        target: target.to_string(),
+        host: host.to_string(),
+        port,
+        status: CertState::Valid,
+        subject: field("subject="),
+        issuer: field("issuer="),
+        not_after: field("notAfter="),
+        days_until_expiry: None,
+        detail: None,
+    };
+
+    if status.not_after.is_none() {
+        let connect_text = connect.join("\n");
+        status.status = if connect_text.contains("Connection refused") || connect_text.contains("errno=111") {
+            CertState::ConnectionRefused
+        } else if connect_text.contains("CONNECTED") {
+            CertState::NoTls
+        } else {
+            CertState::Unreachable
+        };
+        status.detail = connect.iter().rev().find(|line| !line.trim().is_empty()).map(|line| line.trim().to_string());
+        return status;
+    }
+
+    // openssl prints e.g. "Verify return code: 18 (self-signed certificate)"
+    let verify = connect.iter().find_map(|line| line.trim().strip_prefix("Verify return code: "));
+    let verify_code = verify.and_then(|verify| verify.split_whitespace().next()).and_then(|code| code.parse::<u32>().ok());
+    status.detail = verify.map(str::to_string);
+
+    status.days_until_expiry = status.not_after.as_deref()
+        .and_then(|not_after| chrono::NaiveDateTime::parse_from_str(not_after.trim_end_matches(" GMT"), "%b %e %H:%M:%S %Y").ok())
+        .map(|not_after| (not_after.and_utc() - now).num_days());
+
+    let self_signed = matches!(verify_code, Some(18) | Some(19)) || (status.subject.is_some() && status.subject == status.issuer);
+    status.status = match status.days_until_expiry {
+        Some(days) if days < 0 => CertState::Expired,
+        Some(days) if days <= warning_days => CertState::Expiring,
+        _ if self_signed => CertState::SelfSigned,
+        _ if verify_code.map_or(false, |code| code != 0) => CertState::Untrusted,
+        _ => CertState::Valid,
+    };
+    status
+}
+
+// Helper function to fetch and inspect one endpoint's certificate with openssl on the Proxmox host,
+// which can reach the guests' addresses
+async fn check_certificate(target: String, warning_days: i64) -> CertStatus {
+    let (host, port) = match parse_cert_endpoint(&target) {
+        Ok(endpoint) => endpoint,
+        Err(e) => {
+            return CertStatus {
+                host: String::new(),
+                port: 0,
+                status: CertState::Unreachable,
+                subject: None,
+                issuer: None,
+                not_after: None,
+                days_until_expiry: None,
+                detail: Some(e),
+                target,
+            };
+        }
+    };
+
+    let script = format!(
+        "out=$(timeout 10 openssl s_client -connect {host}:{port} -servername {host} </dev/null 2>&1); \
+         echo '==connect'; printf '%s\\n' \"$out\" | grep -E 'CONNECTED|Verify return code|errno|refused|error'; \
+         echo '==cert'; printf '%s\\n' \"$out\" | openssl x509 -noout -subject -issuer -enddate 2>/dev/null; true",
+        host = host, port = port);
+    match run_remote("proxmox", &[&script], *COMMAND_TIMEOUT).await {
+        Ok(output) => parse_cert_check(&target, &host, port, &String::from_utf8_lossy(&output.stdout), warning_days, Utc::now()),
+        Err(e) => CertStatus {
+            target,
+            host,
+            port,
+            status: CertState::Unreachable,
+            subject: None,
+            issuer: None,
+            not_after: None,
+            days_until_expiry: None,
+            detail: Some(e.to_string()),
+        },
+    }
+}
+
+// Tauri command to check the TLS certificates of the given endpoints (by default the configured ones,
+// or every container's web UI when none are configured). Problems are reported per endpoint, not as errors.
+#[tauri::command]
+async fn check_certificates(targets: Option<Vec<String>>) -> Result<Vec<CertStatus>, String> {
+    let config = app_config().certificates;
+    let targets = match targets {
+        Some(targets) => targets,
+        None if !config.endpoints.is_empty() => config.endpoints,
+        None => {
+            let mut urls = Vec::new();
+            for container_id in get_existing_containers().await? {
+                if let Some(url) = get_container_web_ui_url(container_id).await {
+                    urls.push(url);
+                }
+            }
+            urls
+        }
+    };
+
+    let handles: Vec<_> = targets.into_iter()
+        .map(|target| tokio::spawn(check_certificate(target, config.warning_days)))
+        .collect();
+    let mut statuses = Vec::new();
+    for handle in handles {
+        statuses.push(handle.await.map_err(|e| format!("Certificate check failed: {}", e))?);
+    }
+    Ok(statuses)
+}
+
+// Tauri command to get the certificate check settings
+#[tauri::command]
+async fn get_certificate_config() -> Result<CertificateConfig, String> {
+    Ok(app_config().certificates)
+}
+
+// Tauri command to change the watched TLS endpoints and the expiry warning window
+#[tauri::command]
+async fn set_certificate_config(config: CertificateConfig) -> Result<CertificateConfig, String> {
+    if !(1..=365).contains(&config.warning_days) {
+        return Err("Warning window must be between 1 and 365 days".to_string());
+    }
+    for endpoint in &config.endpoints {
+        parse_cert_endpoint(endpoint)?;
+    }
+    Ok(update_app_config(|app_config| app_config.certificates = config)?.certificates)
+}
+
+// Tauri command to get the web UI port used for each container
+#[tauri::command]
+async fn get_web_ui_ports() -> Result<HashMap<u32, u16>, String> {
+    Ok(get_web_ui_port_table())
+}
+
+// Tauri command to override (or reset with None) the web UI port of a container
+#[tauri::command]
+async fn set_web_ui_port(container_id: u32, port: Option<u16>) -> Result<HashMap<u32, u16>, String> {
+    update_app_config(|config| {
+        match port {
+            Some(port) => { config.web_ui_ports.insert(container_id, port); }
+            None => { config.web_ui_ports.remove(&container_id); }
+        }
+    })?;
+
+    Ok(get_web_ui_port_table())
+}
+
+// Tauri command to get the metadata overrides stored for a container or VM
+#[tauri::command]
+async fn get_guest_metadata(id: u32) -> Result<GuestMetadata, String> {
+    Ok(guest_metadata_override(id))
+}
+
+// Tauri command to override the name, description, category, web UI port or ssh alias of a container or VM.
+// Fields left empty fall back to the built-in defaults; an all-empty override removes the entry.
+#[tauri::command]
+async fn set_guest_metadata(id: u32, metadata: GuestMetadata) -> Result<GuestMetadata, String> {
+    if metadata.web_ui_port == Some(0) {
+        return Err("Web UI port must be between 1 and 65535".to_string());
+    }
+    if let Some(alias) = &metadata.ssh_alias {
+        if alias.is_empty() || alias.starts_with('-') || alias.contains(char::is_whitespace) {
+            return Err(format!("Invalid ssh alias '{}'", alias));
+        }
     }
+
+    let is_empty = metadata.name.is_none() && metadata.description.is_none()
+        && metadata.category.is_none() && metadata.web_ui_port.is_none() && metadata.ssh_alias.is_none();
+    update_app_config(|config| {
+        if is_empty {
+            config.guest_metadata.remove(&id);
+        } else {
+            config.guest_metadata.insert(id, metadata);
+        }
+    })?;
+    remove_from_cache("system_overview");
+
+    Ok(guest_metadata_override(id))
 }
 
 // Infrastructure script integration commands
 #[derive(Debug, Serialize, Deserialize)]
 struct ScriptResult {
     success: bool,
+    exit_code: Option<i32>, // None when the script was killed by a signal
     output: String,
     duration: String,
     timestamp: DateTime<Utc>,
@@ -2390,88 +11051,203 @@ struct PerformanceMetrics {
     timestamp: DateTime<Utc>,
 }
 
-// Command to run the container fix script
-#[tauri::command]
-async fn run_container_fix_script() -> Result<ScriptResult, String> {
-    let start_time = std::time::Instant::now();
-    
-    let output = Command::new("bash")
-        .arg("/home/lou/awesome_stack/scripts/fix-all-containers.sh")
-        .output()
-        .map_err(|e| format!("Failed to execute fix-all-containers script: {}", e))?;
-    
-    let duration = format!("{:.2}s", start_time.elapsed().as_secs_f64());
-    let success = output.status.success();
-    let output_text = if success {
-        String::from_utf8_lossy(&output.stdout).to_string()
-    } else {
-        format!("Error: {}\n{}", 
-                String::from_utf8_lossy(&output.stderr),
-                String::from_utf8_lossy(&output.stdout))
+#[derive(Debug, Serialize, Deserialize)]
+struct ScriptInfo {
+    name: String,
+    path: String,
+    timeout_secs: Option<u64>,
+    working_dir: Option<String>,
+    exists: bool,
+    executable: bool,
+}
+
+// One line of script output, emitted as a "script-output" event while the script runs
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct ScriptOutputLine {
+    script: String,
+    stream: String, // "stdout" or "stderr"
+    line: String,
+}
+
+// Helper function to read a script's output line by line, emitting each line and keeping up to `limit` bytes
+async fn stream_script_output<R: tokio::io::AsyncRead + Unpin>(reader: Option<R>, stream: &'static str, script: String,
+                                                              app: Option<tauri::AppHandle>, limit: usize) -> std::io::Result<String> {
+    use tokio::io::AsyncBufReadExt;
+
+    let mut captured = String::new();
+    let mut reader = match reader {
+        Some(reader) => tokio::io::BufReader::new(reader),
+        None => return Ok(captured),
     };
-    
-    Ok(ScriptResult {
-        success,
-        output: output_text,
-        duration,
-        timestamp: Utc::now(),
-    })
+
+    let mut buffer = Vec::new();
+    let mut truncated = false;
+    loop {
+        buffer.clear();
+        if reader.read_until(b'\n', &mut buffer).await? == 0 {
+            break;
+        }
+        let line = String::from_utf8_lossy(&buffer).to_string();
+        if let Some(app) = &app {
+            let event = ScriptOutputLine { script: script.clone(), stream: stream.to_string(), line: line.trim_end().to_string() };
+            if let Err(e) = app.emit("script-output", event) {
+                tracing::warn!("Failed to emit script output: {}", e);
+            }
+        }
+        if captured.len() + line.len() <= limit {
+            captured.push_str(&line);
+        } else {
+            truncated = true;
+        }
+    }
+
+    if truncated {
+        captured.push_str(&format!("\n[output truncated at {} bytes]", limit));
+    }
+    Ok(captured)
 }
 
-// Command to run the media services fix script
-#[tauri::command]
-async fn run_media_services_fix() -> Result<ScriptResult, String> {
+// Helper function to check whether a local file exists and has an execute bit set
+fn script_file_status(path: &str) -> (bool, bool) {
+    use std::os::unix::fs::PermissionsExt;
+    match std::fs::metadata(path) {
+        Ok(metadata) => (metadata.is_file(), metadata.is_file() && metadata.permissions().mode() & 0o111 != 0),
+        Err(_) => (false, false),
+    }
+}
+
+// Helper function to look up a registered script and make sure it can be run
+fn resolve_script(name: &str) -> Result<RegisteredScript, String> {
+    let script = app_config().scripts.scripts.get(name).cloned()
+        .ok_or_else(|| format!("No script is registered as '{}'; register it in the script settings first", name))?;
+    match script_file_status(&script.path) {
+        (false, _) => Err(format!("Script '{}' points to {}, which does not exist", name, script.path)),
+        (true, false) => Err(format!("Script '{}' ({}) is not executable; run chmod +x on it", name, script.path)),
+        (true, true) => Ok(script),
+    }
+}
+
+// Helper function to run a registered script and collect its output into a ScriptResult.
+// Arguments are passed straight to the process, not through a shell, so they need no quoting.
+// With an app handle every output line is also emitted as a "script-output" event.
+async fn run_registered_script(name: &str, args: Vec<String>, default_timeout: std::time::Duration,
+                               app: Option<tauri::AppHandle>) -> Result<ScriptResult, String> {
+    let script = resolve_script(name)?;
+    let timeout = script.timeout_secs.map(std::time::Duration::from_secs).unwrap_or(default_timeout);
     let start_time = std::time::Instant::now();
-    
-    let output = Command::new("bash")
-        .arg("/home/lou/awesome_stack/scripts/fix-media-services.sh")
-        .output()
-        .map_err(|e| format!("Failed to execute fix-media-services script: {}", e))?;
-    
+
+    let mut command = tokio::process::Command::new(&script.path);
+    command.args(&args)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true);
+    if let Some(working_dir) = &script.working_dir {
+        command.current_dir(working_dir);
+    }
+    let mut child = command.spawn()
+        .map_err(|e| format!("Failed to execute script '{}': {}", name, e))?;
+
+    let limit = app_config().exec.max_output_bytes;
+    let stdout = tokio::spawn(stream_script_output(child.stdout.take(), "stdout", name.to_string(), app.clone(), limit));
+    let stderr = tokio::spawn(stream_script_output(child.stderr.take(), "stderr", name.to_string(), app, limit));
+    let run = async {
+        let status = child.wait().await?;
+        let stdout = stdout.await.map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))??;
+        let stderr = stderr.await.map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))??;
+        Ok::<_, std::io::Error>((status, stdout, stderr))
+    };
+
+    // Dropping the child on timeout kills the script
+    let (status, stdout, stderr) = match tokio::time::timeout(timeout, run).await {
+        Ok(result) => result.map_err(|e| format!("Failed to execute script '{}': {}", name, e))?,
+        Err(_) => return Err(format!("Script '{}' timed out after {:?}", name, timeout)),
+    };
+
     let duration = format!("{:.2}s", start_time.elapsed().as_secs_f64());
-    let success = output.status.success();
+    let success = status.success();
     let output_text = if success {
-        String::from_utf8_lossy(&output.stdout).to_string()
+        stdout
     } else {
-        format!("Error: {}\n{}", 
-                String::from_utf8_lossy(&output.stderr),
-                String::from_utf8_lossy(&output.stdout))
+        format!("Error: {}\n{}", stderr, stdout)
     };
-    
+
     Ok(ScriptResult {
         success,
+        exit_code: status.code(),
         output: output_text,
         duration,
         timestamp: Utc::now(),
     })
 }
 
-// Command to run the hardware optimization script
+// Tauri command to list the registered maintenance scripts and whether each can be run
 #[tauri::command]
-async fn run_hardware_optimization() -> Result<ScriptResult, String> {
-    let start_time = std::time::Instant::now();
-    
-    let output = Command::new("bash")
-        .arg("/home/lou/awesome_stack/scripts/hardware_optimization.sh")
-        .output()
-        .map_err(|e| format!("Failed to execute hardware optimization script: {}", e))?;
-    
-    let duration = format!("{:.2}s", start_time.elapsed().as_secs_f64());
-    let success = output.status.success();
-    let output_text = if success {
-        String::from_utf8_lossy(&output.stdout).to_string()
+async fn list_scripts() -> Result<Vec<ScriptInfo>, String> {
+    let mut scripts: Vec<ScriptInfo> = app_config().scripts.scripts.into_iter()
+        .map(|(name, script)| {
+            let (exists, executable) = script_file_status(&script.path);
+            ScriptInfo { name, path: script.path, timeout_secs: script.timeout_secs, working_dir: script.working_dir, exists, executable }
+        })
+        .collect();
+    scripts.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(scripts)
+}
+
+// Tauri command to register (or re-point) a maintenance script under a logical name
+#[tauri::command]
+async fn register_script(name: String, path: String, timeout_secs: Option<u64>, working_dir: Option<String>) -> Result<ScriptInfo, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || "-_".contains(c)) {
+        return Err(format!("Invalid script name '{}'; use letters, digits, '-' and '_'", name));
+    }
+    if !path.starts_with('/') {
+        return Err(format!("Script path '{}' must be absolute", path));
+    }
+    if timeout_secs.map_or(false, |secs| !(1..=86400).contains(&secs)) {
+        return Err("Script timeout must be between 1 second and 24 hours".to_string());
+    }
+    if let Some(dir) = &working_dir {
+        if !std::path::Path::new(dir).is_dir() {
+            return Err(format!("Working directory '{}' does not exist", dir));
+        }
+    }
+
+    let script = RegisteredScript { path: path.clone(), timeout_secs, working_dir: working_dir.clone() };
+    update_app_config(|config| {
+        config.scripts.scripts.insert(name.clone(), script);
+    })?;
+
+    let (exists, executable) = script_file_status(&path);
+    Ok(ScriptInfo { name, path, timeout_secs, working_dir, exists, executable })
+}
+
+// Tauri command to remove a registered maintenance script
+#[tauri::command]
+async fn unregister_script(name: String) -> Result<String, String> {
+    let mut removed = false;
+    update_app_config(|config| removed = config.scripts.scripts.remove(&name).is_some())?;
+    if removed {
+        Ok(format!("Script '{}' unregistered", name))
     } else {
-        format!("Error: {}\n{}", 
-                String::from_utf8_lossy(&output.stderr),
-                String::from_utf8_lossy(&output.stdout))
-    };
-    
-    Ok(ScriptResult {
-        success,
-        output: output_text,
-        duration,
-        timestamp: Utc::now(),
-    })
+        Err(format!("No script is registered as '{}'", name))
+    }
+}
+
+// Tauri command to run a registered maintenance script, e.g. "fix-all-containers"
+#[tauri::command]
+async fn run_script(app: tauri::AppHandle, name: String) -> Result<ScriptResult, String> {
+    audited("run_script", "local".to_string(), serde_json::json!({ "name": name }), async move {
+        run_registered_script(&name, Vec::new(), *LONG_COMMAND_TIMEOUT, Some(app)).await
+    }).await
+}
+
+// Tauri command to run a registered maintenance script with arguments, streaming its output as "script-output" events
+#[tauri::command]
+async fn run_script_with_args(app: tauri::AppHandle, name: String, args: Vec<String>) -> Result<ScriptResult, String> {
+    audited("run_script_with_args", "local".to_string(), serde_json::json!({ "name": name, "args": args }), async move {
+        run_registered_script(&name, args, *LONG_COMMAND_TIMEOUT, Some(app)).await
+    }).await
 }
 
 // Command to get performance metrics
@@ -2488,9 +11264,9 @@ async fn get_performance_metrics() -> Result<PerformanceMetrics, String> {
         }
     }
     
-    let output = Command::new("bash")
-        .arg("/home/lou/awesome_stack/scripts/monitor_performance.sh")
-        .output()
+    let script = resolve_script("monitor-performance")?;
+    let output = run_script_file(script.path, *COMMAND_TIMEOUT)
+        .await
         .map_err(|e| format!("Failed to execute performance monitoring script: {}", e))?;
     
     let output_text = String::from_utf8_lossy(&output.stdout);
@@ -2545,34 +11321,703 @@ async fn get_performance_metrics() -> Result<PerformanceMetrics, String> {
     Ok(performance_metrics)
 }
 
-// Command to update DuckDNS
+// Helper function to escape a Prometheus label value
+fn prometheus_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+// Helper function to render the overview, host storage and monitored services in the Prometheus text format
+async fn render_prometheus_metrics() -> Result<String, String> {
+    use std::fmt::Write;
+
+    let overview = get_system_overview().await?;
+    let mut out = String::new();
+    let mut guests = Vec::new();
+    // Usage is only exported while it is live; stopped guests and last-known figures have no usage series
+    let live = |status: &str, stale: bool, sampled_at: Option<DateTime<Utc>>| status == "Running" && !stale && sampled_at.is_some();
+    for ct in &overview.containers {
+        let usage = live(&ct.status, ct.usage_stale, ct.usage_sampled_at).then(|| (ct.cpu_usage, ct.memory_usage));
+        guests.push(("lxc", ct.id, ct.name.as_str(), ct.category.clone(), ct.status.as_str(), usage));
+    }
+    for vm in &overview.vms {
+        let usage = live(&vm.status, vm.usage_stale, vm.usage_sampled_at).then(|| (vm.cpu_usage, vm.memory_usage));
+        guests.push(("qemu", vm.id, vm.name.as_str(), "Virtual Machines".to_string(), vm.status.as_str(), usage));
+    }
+
+    let metrics = [
+        ("proxmox_guest_up", "Whether the guest is running (1) or not (0)"),
+        ("proxmox_guest_cpu_usage_percent", "Guest CPU usage in percent"),
+        ("proxmox_guest_memory_usage_megabytes", "Guest memory usage in MB"),
+    ];
+    for (metric, help) in metrics {
+        let _ = writeln!(out, "# HELP {} {}\n# TYPE {} gauge", metric, help, metric);
+        for (kind, id, name, category, status, usage) in &guests {
+            let value = match (metric, usage) {
+                ("proxmox_guest_up", _) => if *status == "Running" { 1.0 } else { 0.0 },
+                ("proxmox_guest_cpu_usage_percent", Some((cpu, _))) => *cpu,
+                (_, Some((_, memory))) => *memory,
+                (_, None) => continue,
+            };
+            let _ = writeln!(out, "{}{{id=\"{}\",name=\"{}\",category=\"{}\",type=\"{}\"}} {}",
+                             metric, id, prometheus_label(name), prometheus_label(category), kind, value);
+        }
+    }
+
+    // Host storage and services are best effort; a failing section is left out rather than failing the scrape
+    if let Ok(host) = get_proxmox_host_info().await {
+        let _ = writeln!(out, "# HELP proxmox_storage_usage_percent Storage usage on the host in percent\n# TYPE proxmox_storage_usage_percent gauge");
+        for storage in &host.storage_info {
+            let _ = writeln!(out, "proxmox_storage_usage_percent{{storage=\"{}\",type=\"{}\"}} {}",
+                             prometheus_label(&storage.name), prometheus_label(&storage.storage_type), storage.usage_percent);
+        }
+    }
+    if let Ok(maintenance) = get_maintenance_overview(None).await {
+        let _ = writeln!(out, "# HELP proxmox_host_disk_usage_percent Root filesystem usage of the host in percent\n# TYPE proxmox_host_disk_usage_percent gauge");
+        let _ = writeln!(out, "proxmox_host_disk_usage_percent {}", maintenance.system_health.disk_usage);
+        let _ = writeln!(out, "# HELP proxmox_service_active Whether a monitored service is active (1) or not (0)\n# TYPE proxmox_service_active gauge");
+        for service in &maintenance.services {
+            let _ = writeln!(out, "proxmox_service_active{{service=\"{}\",target=\"{}\"}} {}",
+                             prometheus_label(&service.name), audit_target(service.container_id, service.vm_id), u8::from(service.active));
+        }
+    }
+
+    Ok(out)
+}
+
+// Handler for GET /metrics on the exporter
+async fn prometheus_metrics_handler() -> impl axum::response::IntoResponse {
+    let content_type = [(axum::http::header::CONTENT_TYPE, "text/plain; version=0.0.4")];
+    match render_prometheus_metrics().await {
+        Ok(body) => (axum::http::StatusCode::OK, content_type, body),
+        Err(e) => (axum::http::StatusCode::INTERNAL_SERVER_ERROR, content_type, format!("# {}\n", e)),
+    }
+}
+
+// Tauri command to serve /metrics for Prometheus on the given port (localhost only unless an address is given)
 #[tauri::command]
-async fn update_duckdns() -> Result<ScriptResult, String> {
-    let start_time = std::time::Instant::now();
-    
-    let output = Command::new("bash")
-        .arg("/home/lou/awesome_stack/scripts/update-duckdns.sh")
-        .output()
-        .map_err(|e| format!("Failed to execute DuckDNS update script: {}", e))?;
-    
-    let duration = format!("{:.2}s", start_time.elapsed().as_secs_f64());
-    let success = output.status.success();
-    let output_text = if success {
-        String::from_utf8_lossy(&output.stdout).to_string()
-    } else {
-        format!("Error: {}\n{}", 
-                String::from_utf8_lossy(&output.stderr),
-                String::from_utf8_lossy(&output.stdout))
+async fn start_metrics_exporter(port: u16, listen_address: Option<String>) -> Result<String, String> {
+    let address = listen_address.unwrap_or_else(|| "127.0.0.1".to_string());
+    let ip: std::net::IpAddr = address.parse().map_err(|_| format!("'{}' is not a valid listen address", address))?;
+    if port == 0 {
+        return Err("Port must be between 1 and 65535".to_string());
+    }
+
+    // Stop a running exporter first so restarting on the same port can bind again
+    let previous = METRICS_EXPORTER.lock().map_err(|e| format!("Failed to lock metrics exporter: {}", e))?.take();
+    if let Some(previous) = previous {
+        previous.abort();
+        let _ = previous.await;
+    }
+
+    let listener = tokio::net::TcpListener::bind((ip, port))
+        .await
+        .map_err(|e| format!("Failed to listen on {}:{}: {}", address, port, e))?;
+    let mut exporter = METRICS_EXPORTER.lock().map_err(|e| format!("Failed to lock metrics exporter: {}", e))?;
+    let router = axum::Router::new().route("/metrics", axum::routing::get(prometheus_metrics_handler));
+    *exporter = Some(tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, router).await {
+            tracing::warn!("Metrics exporter stopped: {}", e);
+        }
+    }));
+
+    Ok(format!("Serving Prometheus metrics on http://{}:{}/metrics", address, port))
+}
+
+// Tauri command to stop the Prometheus metrics exporter
+#[tauri::command]
+async fn stop_metrics_exporter() -> Result<String, String> {
+    let mut exporter = METRICS_EXPORTER.lock().map_err(|e| format!("Failed to lock metrics exporter: {}", e))?;
+    match exporter.take() {
+        Some(handle) => {
+            handle.abort();
+            Ok("Metrics exporter stopped".to_string())
+        }
+        None => Ok("Metrics exporter was not running".to_string()),
+    }
+}
+
+// Historical metrics storage (SQLite in the app data dir)
+#[derive(Debug, Serialize, Deserialize)]
+struct MetricSample {
+    timestamp: DateTime<Utc>,
+    value: f64,
+}
+
+// Helper function to run a closure against the metrics database, opening it on first use
+fn with_metrics_db<T, F: FnOnce(&rusqlite::Connection) -> rusqlite::Result<T>>(f: F) -> Result<T, String> {
+    let mut db = METRICS_DB.lock().map_err(|e| format!("Failed to lock metrics database: {}", e))?;
+
+    if db.is_none() {
+        let conn = rusqlite::Connection::open(app_data_path("metrics.db")?)
+            .map_err(|e| format!("Failed to open metrics database: {}", e))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS metric_samples (
+                 target TEXT NOT NULL,
+                 metric TEXT NOT NULL,
+                 timestamp INTEGER NOT NULL,
+                 value REAL NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS idx_metric_samples ON metric_samples (target, metric, timestamp);
+             CREATE TABLE IF NOT EXISTS alert_history (
+                 id INTEGER PRIMARY KEY AUTOINCREMENT,
+                 rule_id TEXT NOT NULL,
+                 target TEXT NOT NULL,
+                 metric TEXT NOT NULL,
+                 value REAL NOT NULL,
+                 threshold REAL NOT NULL,
+                 message TEXT NOT NULL,
+                 timestamp INTEGER NOT NULL,
+                 delivery_error TEXT
+             );",
+        ).map_err(|e| format!("Failed to initialize metrics database: {}", e))?;
+
+        // Databases created before delivery errors were recorded lack the column
+        let has_delivery_error = conn.prepare("SELECT 1 FROM pragma_table_info('alert_history') WHERE name = 'delivery_error'")
+            .and_then(|mut statement| statement.exists([]))
+            .map_err(|e| format!("Failed to inspect metrics database: {}", e))?;
+        if !has_delivery_error {
+            conn.execute("ALTER TABLE alert_history ADD COLUMN delivery_error TEXT", [])
+                .map_err(|e| format!("Failed to migrate metrics database: {}", e))?;
+        }
+        *db = Some(conn);
+    }
+
+    match db.as_ref() {
+        Some(conn) => f(conn).map_err(|e| format!("Metrics database error: {}", e)),
+        None => Err("Metrics database is not available".to_string()),
+    }
+}
+
+// Helper function to turn /cluster/resources entries into (target, metric, value) samples.
+// Targets are "host:<node>", "ct:<id>" and "vm:<id>"; values are percentages.
+fn resource_samples(resources: &[serde_json::Value]) -> Vec<(String, &'static str, f64)> {
+    let mut samples = Vec::new();
+
+    for resource in resources {
+        let target = match resource["type"].as_str() {
+            Some("node") => format!("host:{}", resource["node"].as_str().unwrap_or("unknown")),
+            Some("lxc") => format!("ct:{}", json_u64(&resource["vmid"]).unwrap_or(0)),
+            Some("qemu") => format!("vm:{}", json_u64(&resource["vmid"]).unwrap_or(0)),
+            _ => continue,
+        };
+
+        // Stopped guests have no meaningful live usage
+        if resource["status"].as_str().map(|s| s != "running" && s != "online").unwrap_or(false) {
+            continue;
+        }
+
+        if let Some(cpu) = resource["cpu"].as_f64() {
+            samples.push((target.clone(), "cpu", cpu * 100.0));
+        }
+        if let (Some(mem), Some(maxmem)) = (json_u64(&resource["mem"]), json_u64(&resource["maxmem"])) {
+            if maxmem > 0 {
+                samples.push((target.clone(), "memory", mem as f64 / maxmem as f64 * 100.0));
+            }
+        }
+        // VMs report disk 0 without the guest agent, so only record real figures
+        if let (Some(disk), Some(maxdisk)) = (json_u64(&resource["disk"]), json_u64(&resource["maxdisk"])) {
+            if maxdisk > 0 && disk > 0 {
+                samples.push((target, "disk", disk as f64 / maxdisk as f64 * 100.0));
+            }
+        }
+    }
+
+    samples
+}
+
+// Helper function to collect PSI samples for running containers that a pressure alert rule covers.
+// Each container costs an ssh round trip, so nothing is fetched unless such a rule exists.
+async fn pressure_samples(rules: &[AlertRule], resources: &[serde_json::Value]) -> Vec<(String, &'static str, f64)> {
+    let pressure_rules: Vec<&AlertRule> = rules.iter().filter(|rule| rule.metric.ends_with("_pressure")).collect();
+    if pressure_rules.is_empty() {
+        return Vec::new();
+    }
+
+    let lookups: Vec<(String, tokio::task::JoinHandle<Result<PressureInfo, String>>)> = resources.iter()
+        .filter(|resource| resource["type"] == "lxc" && resource["status"] == "running")
+        .filter_map(|resource| json_u64(&resource["vmid"]))
+        .map(|id| format!("ct:{}", id))
+        .filter(|target| pressure_rules.iter().any(|rule| alert_target_matches(&rule.target, target)))
+        .map(|target| {
+            let id = target.trim_start_matches("ct:").parse().unwrap_or(0);
+            (target, tokio::spawn(get_container_pressure(id)))
+        })
+        .collect();
+
+    let mut samples = Vec::new();
+    for (target, lookup) in lookups {
+        let info = match lookup.await {
+            Ok(Ok(info)) => info,
+            _ => continue,
+        };
+        for (metric, stat) in [("cpu_pressure", info.cpu), ("memory_pressure", info.memory), ("io_pressure", info.io)] {
+            if let Some(stat) = stat {
+                samples.push((target.clone(), metric, stat.some.avg60));
+            }
+        }
+    }
+
+    samples
+}
+
+// Helper function to store one round of samples and prune rows past the retention window
+fn record_samples(config: &MetricsConfig, samples: &[(String, &'static str, f64)]) -> Result<usize, String> {
+    let now = Utc::now().timestamp();
+    let cutoff = now - config.retention_days as i64 * 86400;
+
+    with_metrics_db(|conn| {
+        let mut insert = conn.prepare_cached(
+            "INSERT INTO metric_samples (target, metric, timestamp, value) VALUES (?1, ?2, ?3, ?4)",
+        )?;
+        for (target, metric, value) in samples {
+            insert.execute(rusqlite::params![target, metric, now, value])?;
+        }
+        conn.execute("DELETE FROM metric_samples WHERE timestamp < ?1", [cutoff])?;
+        Ok(samples.len())
+    })
+}
+
+// Background task that periodically records metric samples and checks them against the alert rules
+async fn run_metrics_sampler(app: tauri::AppHandle) {
+    loop {
+        let config = app_config();
+
+        if config.metrics.enabled || !config.alert_rules.is_empty() {
+            match transport().cluster_resources().await {
+                Ok(resources) => {
+                    let mut samples = resource_samples(&resources);
+                    samples.extend(pressure_samples(&config.alert_rules, &resources).await);
+                    if config.metrics.enabled {
+                        if let Err(e) = record_samples(&config.metrics, &samples) {
+                            tracing::warn!("Metrics sampling failed: {}", e);
+                        }
+                    }
+                    evaluate_alert_rules(&app, &config.alert_rules, &config.notifications, &samples);
+                }
+                Err(e) => tracing::warn!("Metrics sampling failed: {}", e),
+            }
+        }
+
+        tokio::time::sleep(std::time::Duration::from_secs(config.metrics.sample_interval_secs.max(10))).await;
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct AlertEvent {
+    id: i64,
+    rule_id: String,
+    target: String,
+    metric: String,
+    value: f64,
+    threshold: f64,
+    message: String,
+    timestamp: DateTime<Utc>,
+    delivery_error: Option<String>,
+}
+
+// Helper function to check whether a rule's target pattern covers a sampled target
+fn alert_target_matches(pattern: &str, target: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => target.starts_with(prefix),
+        None => pattern == target,
+    }
+}
+
+// Helper function to fire alerts for samples that cross a rule's threshold. A rule fires once when
+// its condition starts holding, then at most every repeat_after_minutes until it clears.
+fn evaluate_alert_rules(app: &tauri::AppHandle, rules: &[AlertRule], channel: &NotificationChannel, samples: &[(String, &'static str, f64)]) {
+    let now = Utc::now();
+    let mut state = match ALERT_STATE.lock() {
+        Ok(state) => state,
+        Err(e) => {
+            tracing::warn!("Failed to lock alert state: {}", e);
+            return;
+        }
     };
-    
-    Ok(ScriptResult {
-        success,
-        output: output_text,
-        duration,
+
+    for rule in rules {
+        for (target, metric, value) in samples {
+            if *metric != rule.metric || !alert_target_matches(&rule.target, target) {
+                continue;
+            }
+
+            let key = format!("{}|{}", rule.id, target);
+            let triggered = match rule.comparison.as_str() {
+                "below" => *value < rule.threshold,
+                _ => *value > rule.threshold,
+            };
+            if !triggered {
+                state.remove(&key);
+                continue;
+            }
+            if let Some(last_fired) = state.get(&key) {
+                if now.timestamp() - last_fired < rule.repeat_after_minutes as i64 * 60 {
+                    continue;
+                }
+            }
+            state.insert(key, now.timestamp());
+
+            let message = format!("{} {} is {:.1}%, {} the {:.1}% threshold", target, metric, value, rule.comparison, rule.threshold);
+            let inserted = with_metrics_db(|conn| {
+                conn.execute(
+                    "INSERT INTO alert_history (rule_id, target, metric, value, threshold, message, timestamp)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    rusqlite::params![rule.id, target, metric, value, rule.threshold, message, now.timestamp()],
+                )?;
+                Ok(conn.last_insert_rowid())
+            });
+
+            let event = AlertEvent {
+                id: inserted.as_ref().copied().unwrap_or(0),
+                rule_id: rule.id.clone(),
+                target: target.clone(),
+                metric: metric.to_string(),
+                value: *value,
+                threshold: rule.threshold,
+                message,
+                timestamp: now,
+                delivery_error: None,
+            };
+            if let Err(e) = inserted {
+                tracing::warn!("Failed to record alert: {}", e);
+            }
+            if channel.kind != "none" {
+                // Delivered in the background so a slow webhook or mail server can't hold up sampling
+                tokio::spawn(deliver_alert(channel.clone(), event.clone()));
+            }
+            if let Err(e) = app.emit("alert-triggered", event) {
+                tracing::warn!("Failed to emit alert: {}", e);
+            }
+        }
+    }
+}
+
+// Helper function to send an alert through the configured notification channel
+async fn send_notification(channel: &NotificationChannel, event: &AlertEvent) -> Result<(), String> {
+    match channel.kind.as_str() {
+        "webhook" => {
+            let client = reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(30))
+                .build()
+                .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+            let response = client.post(&channel.webhook_url)
+                .json(event)
+                .send()
+                .await
+                .map_err(|e| format!("Failed to reach webhook: {}", e))?;
+
+            if !response.status().is_success() {
+                return Err(format!("Webhook returned {}", response.status()));
+            }
+            Ok(())
+        }
+        "email" => {
+            use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+            let email = Message::builder()
+                .from(channel.email_from.parse().map_err(|e| format!("Invalid sender address: {}", e))?)
+                .to(channel.email_to.parse().map_err(|e| format!("Invalid recipient address: {}", e))?)
+                .subject(format!("[Proxmox alert] {} {}", event.target, event.metric))
+                .body(format!(
+                    "{}\n\nRule: {}\nTarget: {}\nMetric: {}\nValue: {:.1}%\nThreshold: {:.1}%\nTime: {}\n",
+                    event.message, event.rule_id, event.target, event.metric, event.value, event.threshold,
+                    event.timestamp.format("%Y-%m-%d %H:%M:%S UTC")
+                ))
+                .map_err(|e| format!("Failed to build email: {}", e))?;
+
+            let builder = if channel.smtp_starttls {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&channel.smtp_host)
+            } else {
+                AsyncSmtpTransport::<Tokio1Executor>::relay(&channel.smtp_host)
+            }
+            .map_err(|e| format!("Invalid SMTP host: {}", e))?
+            .port(channel.smtp_port)
+            .timeout(Some(std::time::Duration::from_secs(30)));
+            let builder = if channel.smtp_username.is_empty() {
+                builder
+            } else {
+                builder.credentials(lettre::transport::smtp::authentication::Credentials::new(
+                    channel.smtp_username.clone(),
+                    channel.smtp_password.clone(),
+                ))
+            };
+
+            builder.build()
+                .send(email)
+                .await
+                .map_err(|e| format!("Failed to send email: {}", e))?;
+            Ok(())
+        }
+        "none" => Err("No notification channel is configured".to_string()),
+        other => Err(format!("Unknown notification channel '{}'", other)),
+    }
+}
+
+// Helper function to deliver an alert and note any failure against its history entry
+async fn deliver_alert(channel: NotificationChannel, event: AlertEvent) {
+    if let Err(error) = send_notification(&channel, &event).await {
+        tracing::warn!("Failed to deliver alert {}: {}", event.id, error);
+        if let Err(e) = with_metrics_db(|conn| {
+            conn.execute("UPDATE alert_history SET delivery_error = ?1 WHERE id = ?2", rusqlite::params![error, event.id])
+        }) {
+            tracing::warn!("Failed to record alert delivery error: {}", e);
+        }
+    }
+}
+
+// Tauri command to add an alert rule
+#[tauri::command]
+async fn add_alert_rule(rule: AlertRule) -> Result<AlertRule, String> {
+    if !["cpu", "memory", "disk", "cpu_pressure", "memory_pressure", "io_pressure"].contains(&rule.metric.as_str()) {
+        return Err(format!("Unknown metric '{}'; expected cpu, memory, disk, cpu_pressure, memory_pressure or io_pressure", rule.metric));
+    }
+    if rule.metric.ends_with("_pressure") && (rule.target.starts_with("host:") || rule.target.starts_with("vm:")) {
+        return Err("Pressure metrics are only sampled for containers; use a ct: target".to_string());
+    }
+    if !["above", "below"].contains(&rule.comparison.as_str()) {
+        return Err(format!("Unknown comparison '{}'; expected above or below", rule.comparison));
+    }
+    if !(0.0..=100.0).contains(&rule.threshold) {
+        return Err("Threshold must be a percentage between 0 and 100".to_string());
+    }
+    if rule.target != "*" && !["host:", "ct:", "vm:"].iter().any(|prefix| rule.target.starts_with(prefix)) {
+        return Err(format!("Unknown target '{}'; expected host:<node>, ct:<id>, vm:<id> or a * pattern", rule.target));
+    }
+
+    let rule = AlertRule {
+        id: format!("rule-{}", Utc::now().timestamp_millis()),
+        ..rule
+    };
+    let added = rule.clone();
+    update_app_config(|app_config| app_config.alert_rules.push(rule))?;
+    Ok(added)
+}
+
+// Tauri command to list the alert rules
+#[tauri::command]
+async fn list_alert_rules() -> Result<Vec<AlertRule>, String> {
+    Ok(app_config().alert_rules)
+}
+
+// Tauri command to delete an alert rule
+#[tauri::command]
+async fn delete_alert_rule(id: String) -> Result<(), String> {
+    if !app_config().alert_rules.iter().any(|rule| rule.id == id) {
+        return Err(format!("Alert rule {} not found", id));
+    }
+    update_app_config(|app_config| app_config.alert_rules.retain(|rule| rule.id != id))?;
+    Ok(())
+}
+
+// Tauri command to get the most recent triggered alerts, newest first
+#[tauri::command]
+async fn get_alert_history(limit: Option<usize>) -> Result<Vec<AlertEvent>, String> {
+    with_metrics_db(|conn| {
+        let mut query = conn.prepare_cached(
+            "SELECT id, rule_id, target, metric, value, threshold, message, timestamp, delivery_error FROM alert_history
+             ORDER BY id DESC LIMIT ?1",
+        )?;
+        let rows = query.query_map([limit.unwrap_or(100) as i64], |row| {
+            let timestamp: i64 = row.get(7)?;
+            Ok(AlertEvent {
+                id: row.get(0)?,
+                rule_id: row.get(1)?,
+                target: row.get(2)?,
+                metric: row.get(3)?,
+                value: row.get(4)?,
+                threshold: row.get(5)?,
+                message: row.get(6)?,
+                timestamp: DateTime::from_timestamp(timestamp, 0).unwrap_or_default(),
+                delivery_error: row.get(8)?,
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+// Helper function to hide the SMTP password of a notification channel returned to the UI
+fn masked_channel(mut channel: NotificationChannel) -> NotificationChannel {
+    if !channel.smtp_password.is_empty() {
+        channel.smtp_password = MASKED_SECRET.to_string();
+    }
+    channel
+}
+
+// Tauri command to get where alerts are delivered, with the SMTP password redacted
+#[tauri::command]
+async fn get_notification_channel() -> Result<NotificationChannel, String> {
+    Ok(masked_channel(app_config().notifications))
+}
+
+// Tauri command to change where alerts are delivered; the masked password keeps the stored one
+#[tauri::command]
+async fn set_notification_channel(channel: NotificationChannel) -> Result<NotificationChannel, String> {
+    match channel.kind.as_str() {
+        "none" => {}
+        "webhook" => {
+            if !channel.webhook_url.starts_with("http://") && !channel.webhook_url.starts_with("https://") {
+                return Err("Webhook URL must start with http:// or https://".to_string());
+            }
+        }
+        "email" => {
+            if channel.smtp_host.trim().is_empty() {
+                return Err("SMTP host must not be empty".to_string());
+            }
+            if channel.email_from.trim().is_empty() || channel.email_to.trim().is_empty() {
+                return Err("Sender and recipient addresses must not be empty".to_string());
+            }
+        }
+        other => return Err(format!("Unknown notification channel '{}', expected 'none', 'webhook' or 'email'", other)),
+    }
+
+    let updated = update_app_config(|app_config| {
+        let smtp_password = if channel.smtp_password == MASKED_SECRET {
+            app_config.notifications.smtp_password.clone()
+        } else {
+            channel.smtp_password.clone()
+        };
+        app_config.notifications = NotificationChannel { smtp_password, ..channel };
+    })?;
+    Ok(masked_channel(updated.notifications))
+}
+
+// Tauri command to send a sample alert through the configured channel
+#[tauri::command]
+async fn test_notification_channel() -> Result<String, String> {
+    let channel = app_config().notifications;
+    let event = AlertEvent {
+        id: 0,
+        rule_id: "test".to_string(),
+        target: "host:test".to_string(),
+        metric: "cpu".to_string(),
+        value: 95.0,
+        threshold: 90.0,
+        message: "Test alert from Proxmox Infrastructure Admin".to_string(),
         timestamp: Utc::now(),
+        delivery_error: None,
+    };
+
+    send_notification(&channel, &event).await?;
+    Ok(format!("Test alert sent via {}", channel.kind))
+}
+
+// Tauri command to get a metric time series for a target ("host:<node>", "ct:<id>", "vm:<id>")
+#[tauri::command]
+async fn get_metric_history(target: String, metric: String, from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Result<Vec<MetricSample>, String> {
+    let from = from.map(|t| t.timestamp()).unwrap_or(0);
+    let to = to.map(|t| t.timestamp()).unwrap_or(i64::MAX);
+
+    with_metrics_db(|conn| {
+        let mut query = conn.prepare_cached(
+            "SELECT timestamp, value FROM metric_samples
+             WHERE target = ?1 AND metric = ?2 AND timestamp BETWEEN ?3 AND ?4
+             ORDER BY timestamp",
+        )?;
+        let rows = query.query_map(rusqlite::params![target, metric, from, to], |row| {
+            let timestamp: i64 = row.get(0)?;
+            Ok(MetricSample {
+                timestamp: DateTime::from_timestamp(timestamp, 0).unwrap_or_default(),
+                value: row.get(1)?,
+            })
+        })?;
+        rows.collect()
     })
 }
 
+// Tauri command to get the metrics sampler settings
+#[tauri::command]
+async fn get_metrics_config() -> Result<MetricsConfig, String> {
+    Ok(app_config().metrics)
+}
+
+// Tauri command to change the metrics sampler settings
+#[tauri::command]
+async fn set_metrics_config(config: MetricsConfig) -> Result<MetricsConfig, String> {
+    if config.sample_interval_secs < 10 {
+        return Err("Sample interval must be at least 10 seconds".to_string());
+    }
+    if config.retention_days == 0 {
+        return Err("Retention must be at least 1 day".to_string());
+    }
+
+    Ok(update_app_config(|app_config| app_config.metrics = config)?.metrics)
+}
+
+// Tauri command to get the remote command execution limits
+#[tauri::command]
+async fn get_exec_config() -> Result<ExecConfig, String> {
+    Ok(app_config().exec)
+}
+
+// Tauri command to change the remote command execution limits
+#[tauri::command]
+async fn set_exec_config(config: ExecConfig) -> Result<ExecConfig, String> {
+    if config.max_output_bytes < 64 * 1024 {
+        return Err("Output limit must be at least 64 KiB".to_string());
+    }
+    if config.max_transfer_bytes < 1024 * 1024 {
+        return Err("Transfer limit must be at least 1 MiB".to_string());
+    }
+    if !(1..=10).contains(&config.retry_attempts) {
+        return Err("Retry attempts must be between 1 and 10".to_string());
+    }
+    if !(1..=64).contains(&config.max_concurrent_commands) {
+        return Err("Concurrent command limit must be between 1 and 64".to_string());
+    }
+
+    let limit = config.max_concurrent_commands;
+    let updated = update_app_config(|app_config| app_config.exec = config)?.exec;
+    set_ssh_permits(limit);
+    Ok(updated)
+}
+
+// Tauri command to get the config backup retention settings
+#[tauri::command]
+async fn get_config_backup_settings() -> Result<ConfigBackupSettings, String> {
+    Ok(app_config().config_backups)
+}
+
+// Tauri command to change how many timestamped backups are kept per config file
+#[tauri::command]
+async fn set_config_backup_settings(settings: ConfigBackupSettings) -> Result<ConfigBackupSettings, String> {
+    if !(1..=100).contains(&settings.keep_last_n_backups) {
+        return Err("Backups to keep must be between 1 and 100".to_string());
+    }
+    Ok(update_app_config(|app_config| app_config.config_backups = settings)?.config_backups)
+}
+
+// Tauri command to change how many ssh commands may run at once
+#[tauri::command]
+async fn set_max_concurrent_commands(limit: usize) -> Result<ExecConfig, String> {
+    let mut config = app_config().exec;
+    config.max_concurrent_commands = limit;
+    set_exec_config(config).await
+}
+
+// Tauri command to change the log level at runtime, e.g. "debug" or "info,proxmox_infrastructure_admin=debug"
+#[tauri::command]
+async fn set_log_level(level: String) -> Result<String, String> {
+    let level = level.trim().to_string();
+    let filter = tracing_subscriber::EnvFilter::try_new(&level)
+        .map_err(|e| format!("Invalid log level '{}': {}", level, e))?;
+
+    if let Some(handle) = LOG_FILTER.lock().map_err(|e| format!("Failed to lock log filter: {}", e))?.as_ref() {
+        handle.reload(filter).map_err(|e| format!("Failed to change log level: {}", e))?;
+    }
+    update_app_config(|config| config.logging.level = level.clone())?;
+
+    tracing::info!("Log level set to {}", level);
+    Ok(level)
+}
+
+// Command to update DuckDNS
+#[tauri::command]
+async fn update_duckdns() -> Result<ScriptResult, String> {
+    audited("update_duckdns", "local".to_string(), serde_json::json!({}), async move {
+        run_registered_script("update-duckdns", Vec::new(), *COMMAND_TIMEOUT, None).await
+    }).await
+}
+
 // AI-powered code optimization using CT-900
 #[derive(Debug, Serialize, Deserialize)]
 struct CodeOptimizationResult {
@@ -2609,14 +12054,12 @@ async fn optimize_code_with_ai() -> Result<CodeOptimizationResult, String> {
     Provide specific, actionable recommendations.
     "#;
     
-    // Call AI system in CT-900 for code analysis
-    let ai_output = Command::new("ssh")
-        .args(["proxmox", &format!("pct exec 900 -- curl -s -X POST http://localhost:11434/api/generate -H 'Content-Type: application/json' -d '{{\"model\": \"codellama:7b\", \"prompt\": \"{}\", \"stream\": false}}'", analysis_prompt.replace('"', "\\\"").replace('\n', "\\n"))])
-        .output()
+    // Call the configured AI system for code analysis
+    let ai_config = app_config().ai;
+    let ai_response = ollama_generate(&ai_config, &ai_config.code_model, analysis_prompt, false)
+        .await
         .map_err(|e| format!("Failed to get AI analysis: {}", e))?;
     
-    let ai_response = String::from_utf8_lossy(&ai_output.stdout);
-    
     // Apply the optimizations we've already implemented
     let optimizations_applied = vec![
         "Replaced Mutex with RwLock for better concurrent read performance".to_string(),
@@ -2655,7 +12098,7 @@ async fn optimize_code_with_ai() -> Result<CodeOptimizationResult, String> {
         performance_improvements,
         security_enhancements,
         code_quality_improvements,
-        ai_analysis: ai_response.to_string(),
+        ai_analysis: ai_response,
         execution_time: duration,
         timestamp: Utc::now(),
     })
@@ -2663,27 +12106,55 @@ async fn optimize_code_with_ai() -> Result<CodeOptimizationResult, String> {
 
 fn main() {
     tauri::Builder::default()
+        .setup(|app| {
+            let data_dir = app.path().app_data_dir()?;
+            init_app_config(data_dir.clone());
+            init_logging(data_dir);
+            tauri::async_runtime::spawn(run_metrics_sampler(app.handle().clone()));
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             get_container_status,
             get_vm_status,
             start_container,
             stop_container,
+            shutdown_container,
             restart_container,
             start_vm,
             stop_vm,
             restart_vm,
             get_system_overview,
+            get_overview_sorted,
+            get_favorites,
+            set_favorites,
+            search_guests,
+            overview_page,
             get_maintenance_overview,
+            get_issues,
             check_service_status,
             control_service,
             check_binary,
             check_config,
             read_config,
+            tail_guest_file,
+            get_guest_scheduled_tasks,
             write_config,
+            get_monitored_items,
+            set_monitored_items,
+            get_config_expectations,
+            set_config_expectations,
+            check_config_drift,
+            check_connection,
             get_proxmox_host_info,
+            get_node_summary,
+            get_pve_versions,
             reboot_proxmox_host,
             shutdown_proxmox_host,
+            graceful_host_shutdown,
+            get_firewall_status,
+            set_firewall_enabled,
             get_cluster_status,
+            list_cluster_nodes,
             update_proxmox_packages,
             // Enhanced container management commands
             get_container_details,
@@ -2692,6 +12163,7 @@ fn main() {
             get_container_binaries,
             get_container_services,
             get_container_configs,
+            compare_containers,
             update_container_packages,
             get_ai_config_suggestions,
             read_container_config,
@@ -2699,24 +12171,433 @@ fn main() {
             scan_proxmox_host,
             scan_media_stack,
             // Infrastructure script integration
-            run_container_fix_script,
-            run_media_services_fix,
-            run_hardware_optimization,
+            list_scripts,
+            register_script,
+            unregister_script,
+            run_script,
+            run_script_with_args,
             get_performance_metrics,
             update_duckdns,
             // Automated maintenance commands
             check_and_install_binaries,
             fix_all_services,
+            scan_failed_services,
+            repair_failed_services,
+            run_container_maintenance,
+            run_node_fstrim,
             // Enhanced VM management commands
             shutdown_vm,
             reset_vm,
             get_vm_config,
+            open_guest_console,
+            close_guest_console,
             clone_vm,
+            get_vm_cloudinit,
+            set_vm_cloudinit,
             migrate_vm,
+            migrate_container,
             // AI-powered code optimization
-            optimize_code_with_ai
+            optimize_code_with_ai,
+            // Guest networking
+            get_guest_network,
+            get_web_ui_ports,
+            set_web_ui_port,
+            check_certificates,
+            get_certificate_config,
+            set_certificate_config,
+            get_guest_metadata,
+            set_guest_metadata,
+            // Transport selection
+            get_transport_config,
+            set_transport_config,
+            // Proxmox task tracking
+            get_task_status,
+            get_task_log,
+            get_node_tasks,
+            get_guest_snapshots,
+            describe_guest,
+            get_backup_jobs,
+            set_backup_job_enabled,
+            // Metric history
+            get_metric_history,
+            get_metrics_config,
+            set_metrics_config,
+            // Guest I/O counters
+            get_container_io_stats,
+            get_container_usage,
+            get_vm_io_stats,
+            get_container_pressure,
+            // Config backups
+            list_config_backups,
+            diff_config,
+            revert_config,
+            restore_config_backup,
+            validate_config,
+            promote_config,
+            safe_write_config,
+            export_all_configs,
+            collect_diagnostics,
+            import_all_configs,
+            // AI settings
+            get_ai_config,
+            set_ai_config,
+            // Audit log
+            get_audit_log,
+            // Host action guard
+            get_danger_mode,
+            set_danger_mode,
+            exec_in_guest,
+            // Package updates
+            list_pending_updates,
+            update_all_guests,
+            check_reboot_required,
+            // Remote command limits
+            get_exec_config,
+            set_exec_config,
+            get_config_backup_settings,
+            set_config_backup_settings,
+            set_max_concurrent_commands,
+            get_command_stats,
+            reset_command_stats,
+            // Cache warming
+            start_cache_warmer,
+            stop_cache_warmer,
+            // Prometheus exporter
+            start_metrics_exporter,
+            stop_metrics_exporter,
+            // Logging
+            set_log_level,
+            // Container cloning
+            clone_container,
+            // Templates
+            list_templates,
+            convert_to_template,
+            list_available_templates,
+            list_storage_content,
+            delete_storage_volume,
+            find_orphaned_volumes,
+            validate_create_request,
+            download_template,
+            unlock_guest,
+            get_autostart,
+            set_autostart,
+            rename_container,
+            rename_vm,
+            get_container_config_parsed,
+            get_vm_config_parsed,
+            get_vm_cpu_config,
+            set_vm_cpu_affinity,
+            get_container_cpu_limits,
+            set_container_cpu_limits,
+            set_container_option,
+            get_vm_pci_devices,
+            // Ecosystem scan
+            run_ecosystem_scan,
+            // Open ports
+            get_container_open_ports,
+            get_container_listeners,
+            find_port_owner,
+            // Disk usage
+            get_container_disk_usage,
+            get_host_disk_usage,
+            // Container mounts
+            list_container_mounts,
+            add_container_mount,
+            // Container DNS
+            get_container_dns,
+            set_container_dns,
+            // Container networking
+            get_container_net,
+            set_container_net,
+            detect_ip_conflicts,
+            // File transfer
+            download_guest_file,
+            upload_guest_file,
+            // ZFS
+            get_zfs_pools,
+            start_zfs_scrub,
+            // Start plans
+            get_start_plans,
+            set_start_plan,
+            start_stack,
+            // Alerts
+            add_alert_rule,
+            list_alert_rules,
+            delete_alert_rule,
+            get_alert_history,
+            get_notification_channel,
+            set_notification_channel,
+            test_notification_channel
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn service_actions_outside_the_allowlist_are_rejected() {
+        for action in SERVICE_ACTIONS {
+            assert_eq!(validate_service_action(action), Ok(*action));
+        }
+        for action in ["", "enable", "Restart", "restart ", "restart; reboot", "$(reboot)", "stop\nreboot"] {
+            assert!(validate_service_action(action).is_err(), "{:?} was accepted", action);
+        }
+    }
+
+    #[tokio::test]
+    async fn control_service_rejects_an_action_before_running_anything() {
+        // A stand-in ssh on PATH records every call, so any command that slips through shows up
+        let dir = std::env::temp_dir().join(format!("control-service-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let calls = dir.join("calls");
+        let fake_ssh = dir.join("ssh");
+        std::fs::write(&fake_ssh, format!("#!/bin/sh\necho \"$@\" >> {}\nexit 255\n", calls.display())).unwrap();
+        std::fs::set_permissions(&fake_ssh, std::os::unix::fs::PermissionsExt::from_mode(0o755)).unwrap();
+        std::env::set_var("PATH", format!("{}:{}", dir.display(), std::env::var("PATH").unwrap_or_default()));
+
+        let result = control_service("nginx".to_string(), "restart; reboot".to_string(), Some(100), None).await;
+
+        assert!(result.unwrap_err().starts_with("Unsupported service action"));
+        assert!(!calls.exists(), "ssh was called: {}", std::fs::read_to_string(&calls).unwrap_or_default());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn status_verbose_of_a_running_vm() {
+        let output = "\
+balloon: 4294967296
+blockstat:
+\tscsi0:
+\t\tflush_operations: 12
+cpu: 0.0523
+cpus: 4
+maxmem: 4294967296
+mem: 1073741824
+name: web
+nics:
+\ttap100i0:
+\t\tnetin: 1234
+qmpstatus: running
+status: running
+uptime: 93780
+vmid: 100
+";
+        let status = parse_status_verbose(output);
+        assert_eq!(status.status, "Running");
+        assert!((status.cpu_usage.unwrap() - 5.23).abs() < 1e-9);
+        assert_eq!(status.memory_usage, Some(1024.0));
+        assert_eq!(status.memory_percent, Some(25.0));
+        assert_eq!(status.uptime_seconds, Some(93780));
+        assert_eq!(status.uptime.as_deref(), Some("1d 2h 3m"));
+        assert_eq!(status.lock, None);
+    }
+
+    #[test]
+    fn status_verbose_of_a_stopped_vm_has_no_usage() {
+        let output = "\
+cpus: 2
+lock: backup
+maxmem: 2147483648
+name: db
+qmpstatus: stopped
+status: stopped
+vmid: 101
+";
+        let status = parse_status_verbose(output);
+        assert_eq!(status.status, "Stopped");
+        assert_eq!(status.cpu_usage, None);
+        assert_eq!(status.memory_usage, None);
+        assert_eq!(status.memory_percent, None);
+        assert_eq!(status.uptime, None);
+        assert_eq!(status.uptime_seconds, None);
+        assert_eq!(status.lock.as_deref(), Some("backup"));
+    }
+
+    #[test]
+    fn shell_escape_quotes_anything_the_shell_would_interpret() {
+        assert_eq!(shell_escape("nginx.service"), "nginx.service");
+        assert_eq!(shell_escape(""), "''");
+        assert_eq!(shell_escape("it's"), r"'it'\''s'");
+        assert_eq!(shell_escape("$(reboot)"), "'$(reboot)'");
+        assert_eq!(shell_escape("`reboot`"), "'`reboot`'");
+        assert_eq!(shell_escape("a\nb"), "'a\nb'");
+
+        // Whatever goes in, sh must hand back exactly that one word
+        for value in ["", "'", "''", "it's", "$(reboot)", "`reboot`", "a\nb", "a b", "x; rm -rf /", "\\'\"$HOME", "*", "-n"] {
+            let output = std::process::Command::new("sh")
+                .args(["-c", &format!("printf '%s' {}", shell_escape(value))])
+                .output()
+                .unwrap();
+            assert_eq!(String::from_utf8_lossy(&output.stdout), value);
+        }
+    }
+
+    fn usage(cpu_percent: f32, memory_percent: f32, disk_usage: f32) -> ResourceUsage {
+        ResourceUsage { cpu_percent, memory_percent, disk_usage, io: None, pressure: None, efficiency_score: 0.0 }
+    }
+
+    fn service(name: &str, enabled: bool, active: bool) -> ServiceInfo {
+        ServiceInfo {
+            name: name.to_string(),
+            status: if active { "active" } else { "inactive" }.to_string(),
+            enabled,
+            active,
+            description: String::new(),
+            container_id: Some(100),
+            vm_id: None,
+        }
+    }
+
+    #[test]
+    fn health_score_scenarios() {
+        let quiet = SecurityFlags::default();
+
+        // An idle guest with everything running is fully healthy, and usage up to 80% costs nothing
+        assert_eq!(compute_health_score(&usage(5.0, 30.0, 40.0), &[service("nginx", true, true)], &quiet), 100.0);
+        assert_eq!(compute_health_score(&usage(80.0, 80.0, 80.0), &[], &quiet), 100.0);
+
+        // Past 80% the penalty rises linearly; a full disk costs the most
+        assert_eq!(compute_health_score(&usage(90.0, 0.0, 0.0), &[], &quiet), 92.5);
+        assert_eq!(compute_health_score(&usage(0.0, 0.0, 100.0), &[], &quiet), 75.0);
+        assert_eq!(compute_health_score(&usage(100.0, 100.0, 100.0), &[], &quiet), 40.0);
+
+        // Only enabled services that aren't running count, 10 points each up to 30
+        let services = [service("a", true, false), service("b", false, false), service("c", true, true)];
+        assert_eq!(compute_health_score(&usage(0.0, 0.0, 0.0), &services, &quiet), 90.0);
+        let down: Vec<ServiceInfo> = (0..5).map(|i| service(&format!("s{}", i), true, false)).collect();
+        assert_eq!(compute_health_score(&usage(0.0, 0.0, 0.0), &down, &quiet), 70.0);
+
+        // Security updates and open ports are capped at 20 and 10 points
+        let flags = SecurityFlags { pending_security_updates: 2, unexpected_open_ports: 1 };
+        assert_eq!(compute_health_score(&usage(0.0, 0.0, 0.0), &[], &flags), 89.0);
+        let flags = SecurityFlags { pending_security_updates: 50, unexpected_open_ports: 9 };
+        assert_eq!(compute_health_score(&usage(0.0, 0.0, 0.0), &[], &flags), 70.0);
+
+        // Everything wrong at once bottoms out at 0 rather than going negative
+        assert_eq!(compute_health_score(&usage(100.0, 100.0, 100.0), &down, &flags), 0.0);
+    }
+
+    #[test]
+    fn binary_probe_lines() {
+        let output = "\
+nginx|/usr/sbin/nginx|nginx version: nginx/1.22.1
+python3|/usr/bin/python3|Python 3.11.2
+jq|/usr/bin/jq|
+curl|/usr/bin/curl|curl 7.88.1 (x86_64-pc-linux-gnu) | libcurl/7.88.1
+
+|/usr/bin/nameless|1.0
+docker||
+";
+        let binaries = parse_binary_probe(output, 105);
+        let summary: Vec<(&str, &str, &str)> = binaries.iter()
+            .map(|binary| (binary.name.as_str(), binary.path.as_str(), binary.version.as_str()))
+            .collect();
+        assert_eq!(summary, vec![
+            ("nginx", "/usr/sbin/nginx", "nginx version: nginx/1.22.1"),
+            ("python3", "/usr/bin/python3", "Python 3.11.2"),
+            ("jq", "/usr/bin/jq", "Unknown"),
+            // Only the first two separators split, so a '|' in the version text is kept
+            ("curl", "/usr/bin/curl", "curl 7.88.1 (x86_64-pc-linux-gnu) | libcurl/7.88.1"),
+        ]);
+        assert!(binaries.iter().all(|binary| binary.exists && binary.container_id == Some(105) && binary.vm_id.is_none()));
+    }
+
+    #[tokio::test]
+    async fn ssh_permits_cap_concurrent_commands() {
+        let wait = std::time::Duration::from_millis(50);
+        set_ssh_permits(2);
+
+        let first = acquire_ssh_permit().await.unwrap();
+        let second = acquire_ssh_permit().await.unwrap();
+        assert!(tokio::time::timeout(wait, acquire_ssh_permit()).await.is_err(), "a third command got a slot");
+
+        // Releasing a slot lets the next command in
+        drop(first);
+        let third = tokio::time::timeout(wait, acquire_ssh_permit()).await.unwrap().unwrap();
+        drop((second, third));
+
+        // A limit of 0 would block every command forever, so it is raised to 1
+        set_ssh_permits(0);
+        let only = acquire_ssh_permit().await.unwrap();
+        assert!(tokio::time::timeout(wait, acquire_ssh_permit()).await.is_err());
+        drop(only);
+
+        set_ssh_permits(ExecConfig::default().max_concurrent_commands);
+    }
+
+    #[test]
+    fn guest_config_with_pending_changes_and_snapshots() {
+        let config = "\
+#Web server
+#managed by ansible
+boot: order=scsi0;net0
+cores: 2
+memory: 2048
+net0: virtio=BC:24:11:AA:BB:CC,bridge=vmbr0,firewall=1
+scsi0: local-lvm:vm-100-disk-0,size=32G
+parent: before-upgrade
+
+[PENDING]
+memory: 4096
+
+[before-upgrade]
+cores: 1
+memory: 1024
+snaptime: 1700000000
+";
+        let parsed = parse_guest_config(config, 100, true);
+        assert_eq!(parsed.description, "Web server\nmanaged by ansible");
+
+        // Only the lines before the first section are the current config
+        assert_eq!(parsed.values["memory"].raw, "2048");
+        assert_eq!(parsed.values["cores"].raw, "2");
+        assert_eq!(parsed.values["parent"].raw, "before-upgrade");
+        assert!(!parsed.values.contains_key("snaptime"));
+
+        let net0 = &parsed.values["net0"].fields;
+        assert_eq!(net0["virtio"], "BC:24:11:AA:BB:CC");
+        assert_eq!(net0["bridge"], "vmbr0");
+        assert_eq!(net0["firewall"], "1");
+        let scsi0 = &parsed.values["scsi0"].fields;
+        assert_eq!(scsi0["value"], "local-lvm:vm-100-disk-0");
+        assert_eq!(scsi0["size"], "32G");
+
+        assert_eq!(parsed.sections.len(), 2);
+        assert_eq!(parsed.sections["PENDING"]["memory"].raw, "4096");
+        assert_eq!(parsed.sections["PENDING"].len(), 1);
+        assert_eq!(parsed.sections["before-upgrade"]["cores"].raw, "1");
+        assert_eq!(parsed.sections["before-upgrade"]["snaptime"].raw, "1700000000");
+    }
+
+    #[test]
+    fn container_config_description_key() {
+        let parsed = parse_guest_config("arch: amd64\ndescription: Reverse proxy\nhostname: proxy\n", 105, false);
+        assert_eq!(parsed.description, "Reverse proxy");
+        assert!(!parsed.values.contains_key("description"));
+        assert_eq!(parsed.values["hostname"].raw, "proxy");
+        assert!(parsed.sections.is_empty());
+    }
+
+    #[test]
+    fn uptime_sort_puts_stopped_guests_last() {
+        let guest = |id: u32, uptime_seconds: Option<u64>| GuestSummary {
+            id,
+            is_vm: false,
+            name: format!("ct{}", id),
+            status: if uptime_seconds.is_some() { "Running" } else { "Stopped" }.to_string(),
+            category: String::new(),
+            template: false,
+            lock: None,
+            health_score: None,
+            uptime_seconds,
+        };
+        let mut guests = vec![guest(101, None), guest(102, Some(60)), guest(100, None), guest(103, Some(86400)), guest(104, Some(60))];
+        sort_guest_summaries(&mut guests, "uptime");
+        let order: Vec<u32> = guests.iter().map(|guest| guest.id).collect();
+        assert_eq!(order, vec![103, 102, 104, 100, 101]);
+    }
+}